@@ -0,0 +1,9 @@
+-- Same schema as the SQLite baseline, dates are stored as days from the common era.
+
+CREATE TABLE IF NOT EXISTS daily_solves (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    username VARCHAR(250) NOT NULL,
+    date INTEGER NOT NULL,
+    time INTEGER NOT NULL,
+    CONSTRAINT one_solve_per_day_per_user UNIQUE (username, date)
+);
@@ -0,0 +1,6 @@
+-- Solutions are optional, solves without one stay unverified
+ALTER TABLE daily_solves ADD COLUMN move_count INTEGER;
+ALTER TABLE daily_solves ADD COLUMN verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Ranks are counted per date
+CREATE INDEX daily_solves_by_date ON daily_solves (date, time);
@@ -0,0 +1,6 @@
+CREATE TABLE daily_scrambles (
+    date INTEGER PRIMARY KEY NOT NULL,
+    scramble TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    fmc_best_length INTEGER
+);
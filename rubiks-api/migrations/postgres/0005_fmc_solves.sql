@@ -0,0 +1,8 @@
+CREATE TABLE daily_fmc_solves (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    username VARCHAR(250) NOT NULL,
+    date INTEGER NOT NULL,
+    solution TEXT NOT NULL,
+    move_count INTEGER NOT NULL,
+    CONSTRAINT one_fmc_solve_per_day_per_user UNIQUE (username, date)
+);
@@ -0,0 +1,28 @@
+CREATE TABLE events (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    format TEXT NOT NULL,
+    scramble_kind TEXT NOT NULL,
+    position INTEGER NOT NULL
+);
+
+CREATE TABLE daily_round_scrambles (
+    event TEXT NOT NULL,
+    date INTEGER NOT NULL,
+    attempt INTEGER NOT NULL,
+    scramble TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    PRIMARY KEY (event, date, attempt)
+);
+
+CREATE TABLE daily_rounds (
+    id INTEGER GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    event TEXT NOT NULL,
+    username VARCHAR(250) NOT NULL,
+    date INTEGER NOT NULL,
+    format TEXT NOT NULL,
+    attempts TEXT NOT NULL,
+    average INTEGER,
+    best INTEGER,
+    CONSTRAINT one_round_per_day_per_user_per_event UNIQUE (event, username, date)
+);
@@ -0,0 +1,10 @@
+-- Schema as created by `ensure_db` before versioned migrations,
+-- the table is only created if missing so existing databases keep their data.
+
+CREATE TABLE IF NOT EXISTS daily_solves (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    username VARCHAR(250) NOT NULL,
+    date INTEGER NOT NULL,
+    time INTEGER NOT NULL,
+    CONSTRAINT one_solve_per_day_per_user UNIQUE (username, date)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    username VARCHAR(250) NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    country TEXT,
+    club TEXT
+);
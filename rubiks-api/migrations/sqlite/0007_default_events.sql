@@ -0,0 +1,8 @@
+-- Events already stored are left as edited
+INSERT OR IGNORE INTO events (id, name, format, scramble_kind, position) VALUES
+    ('333', '3x3', 'ao5', '3x3', 0),
+    ('222', '2x2', 'ao5', '2x2', 1),
+    ('333oh', '3x3 One-Handed', 'ao5', '3x3', 2),
+    ('333bf', '3x3 Blindfolded', 'bo3', 'bld', 3),
+    ('333ll', '3x3 Last Layer', 'mo3', 'll', 4),
+    ('333f2l', '3x3 F2L', 'single', 'cross_solved', 5);
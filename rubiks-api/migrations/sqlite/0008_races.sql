@@ -0,0 +1,17 @@
+-- Finished race rooms, running ones are only kept in memory
+CREATE TABLE IF NOT EXISTS races (
+    id TEXT PRIMARY KEY NOT NULL,
+    event TEXT NOT NULL,
+    owner VARCHAR(250) NOT NULL,
+    date INTEGER NOT NULL,
+    round_count INTEGER NOT NULL
+);
+
+-- Attempts are `time:penalty` pairs in round order, as in daily_rounds
+CREATE TABLE IF NOT EXISTS race_results (
+    race_id TEXT NOT NULL REFERENCES races (id),
+    username VARCHAR(250) NOT NULL,
+    rank INTEGER NOT NULL,
+    attempts TEXT NOT NULL,
+    PRIMARY KEY (race_id, username)
+);
@@ -0,0 +1,25 @@
+use axum::extract::FromRequestParts;
+use axum::http::header::AUTHORIZATION;
+use axum::http::request::Parts;
+use crate::api::error::ApiError;
+use crate::api::state::AppState;
+
+/// Username from the `Authorization: Bearer <token>` header.
+pub struct AuthenticatedUser(pub String);
+
+impl FromRequestParts<AppState> for AuthenticatedUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let token = parts.headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::Unauthorized("bearer token is missing.".into()))?;
+
+        state.users_service
+            .authenticate(token)
+            .map(AuthenticatedUser)
+            .map_err(ApiError::from)
+    }
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+
+/// Source of the current instant, replaced in tests to cross day boundaries.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock of the system.
+#[derive(Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
@@ -1,20 +1,215 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToResponse, ToSchema};
 
-use crate::domain::models::DailySolve;
+use crate::domain::challenge::ChallengeWindow;
+use crate::domain::cube_state::{CubeState, PieceState};
+use crate::domain::events::Event;
+use crate::domain::models::{DailyRank, DailyRound, DailySolve, DailySummary, LeaderboardEntry, RollingAverage, UserStats};
+use crate::domain::races::{FinishedRace, Race, RaceStanding, RaceStatus};
+use crate::domain::results::{Attempt, Penalty};
+use crate::domain::solutions::{Solution, SolverKind};
+use crate::services::scramble_service::SCRAMBLE_GENERATOR_VERSION;
 
-#[derive(Deserialize)]
+/// Scramble with what is needed to display it.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ScrambleDto {
+    /// Moves separated by spaces
+    pub scramble: String,
+    pub moves: Vec<String>,
+    /// Puzzle the moves apply to, like `3x3x3`
+    pub puzzle: String,
+    /// Version of the generator, scrambles of different versions differ for the same seed
+    pub generator_version: i32,
+    /// Image of the scrambled puzzle, when the server links to an image service
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub svg_url: Option<String>,
+}
+
+impl ScrambleDto {
+    /// `svg_url` is a link template, `{moves}` standing for the moves.
+    pub fn new(scramble: String, puzzle: &str, generator_version: i32, svg_url: Option<&str>) -> Self {
+        let encoded_moves = scramble.replace(' ', "%20").replace('\'', "%27");
+        Self {
+            moves: scramble.split_whitespace().map(str::to_string).collect(),
+            svg_url: svg_url.map(|url| url.replace("{moves}", &encoded_moves)),
+            scramble,
+            puzzle: puzzle.to_string(),
+            generator_version,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CubeSolvedDto {
+    pub solved: bool,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MovesDto {
+    /// Moves applied to a solved cube, separated by spaces
+    pub moves: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PieceStateDto {
+    /// Location on the cube, like `URF` or `UR`
+    pub location: String,
+    /// Location the piece belongs to when solved
+    pub piece: String,
+    /// Clockwise twists of a corner, or 1 for a flipped edge
+    pub twist: u8,
+    pub solved: bool,
+}
+
+impl From<PieceState> for PieceStateDto {
+    fn from(piece: PieceState) -> Self {
+        Self {
+            location: piece.location,
+            piece: piece.piece,
+            twist: piece.twist,
+            solved: piece.solved,
+        }
+    }
+}
+
+/// Blindfolded solve features, buffers at ULB and UR.
+#[derive(Serialize, ToSchema)]
+pub struct BldSummaryDto {
+    pub misplaced_corners: usize,
+    pub misplaced_edges: usize,
+    /// Corners in place but twisted, buffer excluded
+    pub twisted_corners: usize,
+    /// Edges in place but flipped, buffer excluded
+    pub flipped_edges: usize,
+    pub corner_targets: usize,
+    pub edge_targets: usize,
+    pub corner_cycle_breaks: usize,
+    pub edge_cycle_breaks: usize,
+    /// Odd number of corner targets
+    pub parity: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CubeStateDto {
+    /// Sticker colors by face, in the order U, R, F, D, L, B
+    pub facelets: String,
+    /// Corners then edges
+    pub pieces: Vec<PieceStateDto>,
+    pub solved: bool,
+    /// Faces of a single color, like `U`
+    pub solved_faces: Vec<String>,
+    pub bld: BldSummaryDto,
+}
+
+impl From<CubeState> for CubeStateDto {
+    fn from(state: CubeState) -> Self {
+        Self {
+            facelets: state.facelets,
+            pieces: state.pieces.into_iter().map(PieceStateDto::from).collect(),
+            solved: state.solved,
+            solved_faces: state.solved_faces,
+            bld: BldSummaryDto {
+                misplaced_corners: state.misplaced_corners,
+                misplaced_edges: state.misplaced_edges,
+                twisted_corners: state.bld.twisted_corners,
+                flipped_edges: state.bld.flipped_edges,
+                corner_targets: state.bld.corner_targets,
+                edge_targets: state.bld.edge_targets,
+                corner_cycle_breaks: state.bld.corner_cycle_breaks,
+                edge_cycle_breaks: state.bld.edge_cycle_breaks,
+                parity: state.bld.parity,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SolverDto {
+    #[default]
+    Kociemba,
+    /// Shortest solution found in time, not an optimal one
+    Shortest,
+    Pochmann,
+}
+
+impl From<SolverDto> for SolverKind {
+    fn from(solver: SolverDto) -> Self {
+        match solver {
+            SolverDto::Kociemba => SolverKind::Kociemba,
+            SolverDto::Shortest => SolverKind::Shortest,
+            SolverDto::Pochmann => SolverKind::Pochmann,
+        }
+    }
+}
+
+impl From<SolverKind> for SolverDto {
+    fn from(solver: SolverKind) -> Self {
+        match solver {
+            SolverKind::Kociemba => SolverDto::Kociemba,
+            SolverKind::Shortest => SolverDto::Shortest,
+            SolverKind::Pochmann => SolverDto::Pochmann,
+        }
+    }
+}
+
+/// Cube to solve, given by exactly one of `moves` and `facelets`.
+#[derive(Deserialize, ToSchema)]
+pub struct SolveRequestDto {
+    /// Moves applied to a solved cube, separated by spaces
+    pub moves: Option<String>,
+    /// Sticker colors by face, like `UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB`
+    pub facelets: Option<String>,
+    #[serde(default)]
+    pub solver: SolverDto,
+    /// Longest solution accepted, 20 by default. Move solvers can't search below 18 moves.
+    #[schema(minimum = 1, maximum = 30)]
+    pub max_length: Option<usize>,
+    /// Search time, capped by the server and by default its maximum
+    pub timeout_seconds: Option<f32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SolutionDto {
+    pub solver: SolverDto,
+    /// Moves separated by spaces, or for Pochmann the stickers to memorize, corners then edges
+    pub solution: String,
+    /// Moves, or for Pochmann targets, in the solution
+    pub length: usize,
+    /// Time spent searching in milliseconds
+    pub time_ms: u64,
+}
+
+impl SolutionDto {
+    pub fn new(solver: SolverKind, solution: Solution) -> Self {
+        Self {
+            solver: solver.into(),
+            length: solution.steps.len(),
+            solution: solution.steps.join(" "),
+            time_ms: solution.time.as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct DailySolveRequestDto {
-    pub username: String,
     pub time: i32,
+    /// Moves solving the daily scramble, rotations and wide moves allowed
+    pub solution: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct DailySolveDto {
     pub username: String,
     pub time: i32,
+    pub move_count: Option<i32>,
+    pub verified: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct DailySolveListDto {
     pub solves: Vec<DailySolveDto>
 }
@@ -24,6 +219,8 @@ impl From<DailySolve> for DailySolveDto {
         Self {
             username: solve.username,
             time: solve.time,
+            move_count: solve.move_count,
+            verified: solve.verified,
         }
     }
 }
@@ -35,3 +232,515 @@ impl From<Vec<DailySolve>> for DailySolveListDto {
         }
     }
 }
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationQueryDto {
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DailySummaryDto {
+    pub date: String,
+    pub participants: i64,
+    pub winner: Option<DailySolveDto>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DailyHistoryDto {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub dailies: Vec<DailySummaryDto>,
+}
+
+impl From<DailySummary> for DailySummaryDto {
+    fn from(summary: DailySummary) -> Self {
+        Self {
+            date: summary.date.format("%Y-%m-%d").to_string(),
+            participants: summary.participants,
+            winner: summary.winner.map(DailySolveDto::from),
+        }
+    }
+}
+
+/// Current daily, with the time left to take part for countdowns.
+#[derive(Serialize, ToSchema)]
+pub struct DailyChallengeDto {
+    pub date: String,
+    /// IANA name of the timezone in which dailies start at midnight
+    pub timezone: String,
+    /// RFC 3339 instants in UTC
+    pub starts_at: String,
+    pub ends_at: String,
+    pub remaining_seconds: i64,
+}
+
+impl DailyChallengeDto {
+    pub fn new(challenge: ChallengeWindow, timezone: Tz, now: DateTime<Utc>) -> Self {
+        Self {
+            date: challenge.date.format("%Y-%m-%d").to_string(),
+            timezone: timezone.name().to_string(),
+            starts_at: challenge.start.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ends_at: challenge.end.to_rfc3339_opts(SecondsFormat::Secs, true),
+            remaining_seconds: challenge.remaining(now).num_seconds(),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CredentialsDto {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TokenDto {
+    pub token: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct FmcSolveRequestDto {
+    pub solution: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FmcSolveDto {
+    pub username: String,
+    pub move_count: i32,
+    /// Hidden until the day is over
+    pub solution: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct FmcLeaderboardDto {
+    pub solves: Vec<FmcSolveDto>,
+    /// Length of the shortest solution the solver found, revealed once the day is over and its search ended.
+    /// An upper bound rather than the optimal length, a solve may beat it.
+    pub best_known_upper_bound: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, ToSchema)]
+pub enum PenaltyDto {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "+2")]
+    PlusTwo,
+    #[serde(rename = "DNF")]
+    Dnf,
+}
+
+impl From<PenaltyDto> for Penalty {
+    fn from(penalty: PenaltyDto) -> Self {
+        match penalty {
+            PenaltyDto::None => Penalty::None,
+            PenaltyDto::PlusTwo => Penalty::PlusTwo,
+            PenaltyDto::Dnf => Penalty::Dnf,
+        }
+    }
+}
+
+impl From<Penalty> for PenaltyDto {
+    fn from(penalty: Penalty) -> Self {
+        match penalty {
+            Penalty::None => PenaltyDto::None,
+            Penalty::PlusTwo => PenaltyDto::PlusTwo,
+            Penalty::Dnf => PenaltyDto::Dnf,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AttemptDto {
+    /// Time in milliseconds, without the penalty
+    pub time: i32,
+    #[serde(default)]
+    pub penalty: PenaltyDto,
+}
+
+impl From<AttemptDto> for Attempt {
+    fn from(attempt: AttemptDto) -> Self {
+        Self {
+            time: attempt.time,
+            penalty: attempt.penalty.into(),
+        }
+    }
+}
+
+impl From<Attempt> for AttemptDto {
+    fn from(attempt: Attempt) -> Self {
+        Self {
+            time: attempt.time,
+            penalty: attempt.penalty.into(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EventDto {
+    pub id: String,
+    pub name: String,
+    pub format: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EventListDto {
+    pub events: Vec<EventDto>,
+}
+
+impl From<Event> for EventDto {
+    fn from(event: Event) -> Self {
+        Self {
+            id: event.id,
+            name: event.name,
+            format: event.format.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DailyRoundScramblesDto {
+    pub event: String,
+    pub format: String,
+    pub scrambles: Vec<ScrambleDto>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct DailyRoundRequestDto {
+    /// One attempt per round scramble, in order
+    pub attempts: Vec<AttemptDto>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DailyRoundDto {
+    pub username: String,
+    pub format: String,
+    pub attempts: Vec<AttemptDto>,
+    /// Milliseconds, `None` for a DNF
+    pub average: Option<i32>,
+    pub best: Option<i32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DailyRoundListDto {
+    pub rounds: Vec<DailyRoundDto>,
+}
+
+impl From<DailyRound> for DailyRoundDto {
+    fn from(round: DailyRound) -> Self {
+        Self {
+            username: round.username,
+            format: round.format.to_string(),
+            attempts: round.attempts.into_iter().map(AttemptDto::from).collect(),
+            average: round.average,
+            best: round.best,
+        }
+    }
+}
+
+impl From<Vec<DailyRound>> for DailyRoundListDto {
+    fn from(rounds: Vec<DailyRound>) -> Self {
+        Self {
+            rounds: rounds.into_iter().map(DailyRoundDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UserSolveDto {
+    pub date: String,
+    pub time: i32,
+    pub move_count: Option<i32>,
+    pub verified: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UserSolvesDto {
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub solves: Vec<UserSolveDto>,
+}
+
+impl From<DailySolve> for UserSolveDto {
+    fn from(solve: DailySolve) -> Self {
+        Self {
+            date: solve.date.format("%Y-%m-%d").to_string(),
+            time: solve.time,
+            move_count: solve.move_count,
+            verified: solve.verified,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DailyRankDto {
+    pub date: String,
+    pub rank: i64,
+    pub participants: i64,
+}
+
+impl From<DailyRank> for DailyRankDto {
+    fn from(rank: DailyRank) -> Self {
+        Self {
+            date: rank.date.format("%Y-%m-%d").to_string(),
+            rank: rank.rank,
+            participants: rank.participants,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UserStatsDto {
+    pub solve_count: i64,
+    pub best_single: Option<i32>,
+    pub mean: Option<i32>,
+    pub best_ao5: Option<i32>,
+    pub best_ao12: Option<i32>,
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    /// Most recent first
+    pub rank_history: Vec<DailyRankDto>,
+}
+
+impl UserStatsDto {
+    pub fn new(stats: UserStats, ranks: Vec<DailyRank>) -> Self {
+        Self {
+            solve_count: stats.solve_count,
+            best_single: stats.best_single,
+            mean: stats.mean,
+            best_ao5: stats.best_ao5,
+            best_ao12: stats.best_ao12,
+            current_streak: stats.current_streak,
+            longest_streak: stats.longest_streak,
+            rank_history: ranks.into_iter().map(DailyRankDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RollingAverageQueryDto {
+    pub size: Option<u32>,
+    pub points: Option<u32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RollingAverageDto {
+    pub date: String,
+    pub average: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RollingAveragesDto {
+    pub size: u32,
+    /// Oldest first
+    pub averages: Vec<RollingAverageDto>,
+}
+
+impl From<RollingAverage> for RollingAverageDto {
+    fn from(average: RollingAverage) -> Self {
+        Self {
+            date: average.date.format("%Y-%m-%d").to_string(),
+            average: average.average,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ProfileDto {
+    /// ISO 3166-1 alpha-2 code
+    pub country: Option<String>,
+    pub club: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LeaderboardQueryDto {
+    /// `single` or `average`
+    pub ranking: Option<String>,
+    pub average_of: Option<u32>,
+    pub min_solves: Option<u32>,
+    pub country: Option<String>,
+    pub club: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LeaderboardEntryDto {
+    pub rank: i64,
+    pub username: String,
+    pub solve_count: i64,
+    pub best_single: i32,
+    pub best_average: Option<i32>,
+}
+
+impl From<LeaderboardEntry> for LeaderboardEntryDto {
+    fn from(entry: LeaderboardEntry) -> Self {
+        Self {
+            rank: entry.rank,
+            username: entry.username,
+            solve_count: entry.solve_count,
+            best_single: entry.best_single,
+            best_average: entry.best_average,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PeriodLeaderboardDto {
+    pub period: String,
+    /// `None` for all time
+    pub from: Option<String>,
+    pub to: String,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+    pub entries: Vec<LeaderboardEntryDto>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RaceRequestDto {
+    /// Event the scrambles are generated for, like `333`
+    pub event: String,
+    /// 5 by default
+    #[schema(minimum = 1, maximum = 12)]
+    pub rounds: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RaceLiveQueryDto {
+    /// Token from `POST /auth/login`, needed to send commands rather than only follow the race
+    pub token: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RaceStatusDto {
+    Waiting,
+    Running,
+    Finished,
+}
+
+impl From<RaceStatus> for RaceStatusDto {
+    fn from(status: RaceStatus) -> Self {
+        match status {
+            RaceStatus::Waiting => RaceStatusDto::Waiting,
+            RaceStatus::Running => RaceStatusDto::Running,
+            RaceStatus::Finished => RaceStatusDto::Finished,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RaceStandingDto {
+    /// Ranked by DNF count then total time over the closed rounds, ties sharing a rank
+    pub rank: usize,
+    pub username: String,
+    /// Attempt of each started round, `null` until submitted
+    pub attempts: Vec<Option<AttemptDto>>,
+    pub dnfs: usize,
+    /// Sum of the results of the closed rounds in milliseconds, DNFs excluded
+    pub total: i32,
+}
+
+impl From<RaceStanding> for RaceStandingDto {
+    fn from(standing: RaceStanding) -> Self {
+        Self {
+            rank: standing.rank,
+            username: standing.username,
+            attempts: standing.attempts.into_iter().map(|attempt| attempt.map(AttemptDto::from)).collect(),
+            dnfs: standing.dnfs,
+            total: standing.total,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RaceDto {
+    /// Code to share with the other participants
+    pub id: String,
+    pub event: String,
+    pub owner: String,
+    pub status: RaceStatusDto,
+    pub round_count: usize,
+    /// Running round, from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_round: Option<usize>,
+    /// Scramble of the running round
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scramble: Option<ScrambleDto>,
+    pub participants: Vec<String>,
+    pub standings: Vec<RaceStandingDto>,
+}
+
+impl RaceDto {
+    /// `svg_url` is a link template, `{moves}` standing for the moves.
+    pub fn live(race: Race, svg_url: Option<&str>) -> Self {
+        let current_round = race.current_round();
+        Self {
+            status: race.status().into(),
+            round_count: race.scrambles.len(),
+            current_round: current_round.map(|round| round + 1),
+            scramble: current_round.map(|round| {
+                ScrambleDto::new(race.scrambles[round].clone(), race.event.scramble_kind.puzzle(), SCRAMBLE_GENERATOR_VERSION, svg_url)
+            }),
+            standings: race.standings().into_iter().map(RaceStandingDto::from).collect(),
+            id: race.id,
+            event: race.event.id,
+            owner: race.owner,
+            participants: race.participants,
+        }
+    }
+
+    pub fn finished(race: FinishedRace) -> Self {
+        Self {
+            id: race.id,
+            event: race.event,
+            owner: race.owner,
+            status: RaceStatusDto::Finished,
+            round_count: race.round_count,
+            current_round: None,
+            scramble: None,
+            participants: race.standings.iter().map(|standing| standing.username.clone()).collect(),
+            standings: race.standings.into_iter().map(RaceStandingDto::from).collect(),
+        }
+    }
+}
+
+/// Command sent by a participant over the race WebSocket.
+#[derive(Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RaceCommandDto {
+    /// Starts the first round, owner only
+    Start,
+    /// Result of the running round
+    Submit(AttemptDto),
+    /// Closes the running round without waiting for everyone, owner only
+    Next,
+}
+
+/// Message sent to the clients of the race WebSocket.
+#[derive(Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RaceMessageDto {
+    /// State of the race, sent on connection and after every change
+    Race(Box<RaceDto>),
+    /// Rejected command, the connection stays open
+    Error { status: u16, detail: String },
+}
+
+/// Problem details of a failed request, see RFC 9457.
+#[derive(Serialize, Deserialize, ToSchema, ToResponse)]
+#[response(description = "Problem details of the failure", content_type = "application/problem+json")]
+pub struct ProblemDto {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+use axum::http::header::{CONTENT_TYPE, RETRY_AFTER};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use crate::api::dtos::ProblemDto;
+use crate::services::error::ServiceError;
+
+/// Seconds a client is told to wait before retrying a busy request
+const BUSY_RETRY_AFTER_SECONDS: u64 = 1;
+
+/// Error of a request, answered with an RFC 9457 problem body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// The request is well-formed but its values are not accepted
+    Validation(String),
+    Unauthorized(String),
+    /// The user is known but may not do this, e.g. start the race of another owner
+    Forbidden(String),
+    NotFound(String),
+    /// The request conflicts with stored data, e.g. a second solve of the same daily
+    Conflict(String),
+    /// The client sent too many requests and should wait before the next one
+    TooManyRequests(String),
+    /// The server can't answer in time right now, the request may be retried shortly
+    Busy(String),
+    /// The database could not be reached, the request may be retried
+    Unavailable(String),
+    /// Details are logged, not sent
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Busy(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Problem body sent to the client, the details of server errors being logged instead.
+    pub fn problem(&self) -> ProblemDto {
+        match self {
+            ApiError::Unavailable(message) => tracing::warn!("Database unavailable: {}", message),
+            ApiError::Internal(message) => tracing::error!("Internal error: {}", message),
+            _ => {}
+        }
+
+        let status = self.status();
+        ProblemDto {
+            problem_type: "about:blank".into(),
+            title: status.canonical_reason().unwrap_or_default().into(),
+            status: status.as_u16(),
+            detail: self.detail().into(),
+        }
+    }
+
+    /// Message shown to the client.
+    fn detail(&self) -> &str {
+        match self {
+            ApiError::Validation(message)
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::TooManyRequests(message)
+            | ApiError::Busy(message) => message,
+            ApiError::Unavailable(_) => "database is unavailable, try again later.",
+            ApiError::Internal(_) => "internal error.",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Unavailable(message) | ApiError::Internal(message) => write!(f, "{}", message),
+            _ => write!(f, "{}", self.detail()),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ServiceError> for ApiError {
+    fn from(error: ServiceError) -> Self {
+        match error {
+            ServiceError::Invalid(message) => ApiError::Validation(message),
+            ServiceError::Unauthorized(message) => ApiError::Unauthorized(message),
+            ServiceError::Forbidden(message) => ApiError::Forbidden(message),
+            ServiceError::NotFound(message) => ApiError::NotFound(message),
+            ServiceError::Conflict(message) => ApiError::Conflict(message),
+            ServiceError::Busy(message) | ServiceError::TimedOut(message) => ApiError::Busy(message),
+            ServiceError::Unavailable(message) => ApiError::Unavailable(message),
+            ServiceError::Internal(message) => ApiError::Internal(message),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status(), [(CONTENT_TYPE, "application/problem+json")], Json(self.problem())).into_response();
+        if let ApiError::Busy(_) = self {
+            response.headers_mut().insert(RETRY_AFTER, BUSY_RETRY_AFTER_SECONDS.into());
+        }
+        response
+    }
+}
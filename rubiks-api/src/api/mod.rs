@@ -1,3 +1,8 @@
+pub mod auth;
+pub mod clock;
 pub mod dtos;
+pub mod error;
+pub mod moves;
+pub mod openapi;
 pub mod routes;
 pub mod state;
\ No newline at end of file
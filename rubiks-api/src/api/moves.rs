@@ -0,0 +1,45 @@
+use axum::extract::{FromRequest, Query, Request};
+use axum::http::Method;
+use axum::Json;
+use rubiks::cube::Cube;
+use rubiks::notation::{NotationMove, Orientation};
+use crate::api::dtos::MovesDto;
+use crate::api::error::ApiError;
+
+/// Moves from the `moves` query parameter of a GET request, or the JSON body of any other,
+/// wide moves, slices and rotations allowed.
+pub struct ValidatedMoves(pub Vec<NotationMove>);
+
+impl ValidatedMoves {
+    pub fn parse(moves_str: &str) -> Result<Self, ApiError> {
+        NotationMove::parse_array(moves_str)
+            .map(ValidatedMoves)
+            .ok_or_else(|| ApiError::Validation("'moves' sequence is invalid.".into()))
+    }
+
+    /// Cube left by the moves applied to a solved cube.
+    pub fn cube(&self) -> Cube {
+        let mut cube = Cube::solved();
+        cube.apply_moves(&Orientation::default().lower_all(&self.0));
+        cube
+    }
+}
+
+impl<S: Send + Sync> FromRequest<S> for ValidatedMoves {
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let moves = if request.method() == Method::GET {
+            Query::<MovesDto>::try_from_uri(request.uri())
+                .map_err(|_| ApiError::Validation("'moves' query parameter is missing.".into()))?
+                .0
+        } else {
+            Json::<MovesDto>::from_request(request, state)
+                .await
+                .map_err(|rejection| ApiError::Validation(rejection.body_text()))?
+                .0
+        };
+
+        Self::parse(&moves.moves)
+    }
+}
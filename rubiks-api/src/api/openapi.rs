@@ -0,0 +1,57 @@
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use crate::api::dtos::{ProblemDto, RaceCommandDto, RaceMessageDto};
+use crate::api::routes;
+
+/// OpenAPI document generated from the route handlers and `api::dtos`.
+#[derive(OpenApi)]
+#[openapi(
+    info(description = "Daily Rubik's cube scrambles, solves and leaderboards", license(name = "MIT")),
+    paths(
+        routes::get_cube_scramble,
+        routes::get_cube_is_solved,
+        routes::post_cube_solve,
+        routes::get_cube_state,
+        routes::post_cube_state,
+        routes::get_daily_scramble,
+        routes::post_daily_scramble,
+        routes::get_daily_leaderboard,
+        routes::get_daily_history,
+        routes::get_daily_challenge,
+        routes::get_date_scramble,
+        routes::get_date_leaderboard,
+        routes::post_daily_fmc,
+        routes::get_daily_fmc_leaderboard,
+        routes::get_date_fmc_leaderboard,
+        routes::get_events,
+        routes::post_daily_event_round,
+        routes::get_daily_event_scrambles,
+        routes::get_date_event_scrambles,
+        routes::get_daily_event_leaderboard,
+        routes::get_date_event_leaderboard,
+        routes::get_period_leaderboard,
+        routes::post_user,
+        routes::put_user_profile,
+        routes::get_user_solves,
+        routes::get_user_stats,
+        routes::get_user_averages,
+        routes::post_login,
+        routes::post_race,
+        routes::post_race_participant,
+        routes::get_race,
+        routes::get_race_live,
+    ),
+    components(schemas(RaceCommandDto, RaceMessageDto), responses(ProblemDto)),
+    modifiers(&BearerAuth),
+)]
+pub struct ApiDoc;
+
+/// `bearer` scheme of the routes taking the token from `POST /auth/login`.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme("bearer", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+    }
+}
@@ -1,77 +1,847 @@
-use std::collections::HashMap;
-use axum::extract::{self, Query, State};
-use axum::http::StatusCode;
-use axum::routing::{get, post};
+use std::future::Future;
+use std::time::Duration;
+use axum::extract::{self, Path, Query, State};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
 use axum::{Json, Router};
-use chrono::Utc;
-use tower_http::cors::CorsLayer;
+use chrono::NaiveDate;
+use tokio::sync::broadcast;
+use tower_governor::governor::GovernorConfigBuilder;
+use tower_governor::{GovernorError, GovernorLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tower_http::trace::TraceLayer;
-use crate::api::dtos::{DailySolveListDto, DailySolveRequestDto};
+use utoipa::OpenApi;
+use crate::api::auth::AuthenticatedUser;
+use crate::api::dtos::{CredentialsDto, CubeSolvedDto, CubeStateDto, DailyChallengeDto, DailyHistoryDto, DailyRoundListDto, DailyRoundRequestDto, DailyRoundScramblesDto, EventDto, EventListDto, DailySolveListDto, DailySolveRequestDto, DailySummaryDto, FmcLeaderboardDto, FmcSolveDto, FmcSolveRequestDto, LeaderboardEntryDto, LeaderboardQueryDto, MovesDto, PaginationQueryDto, PeriodLeaderboardDto, ProblemDto, ProfileDto, RaceCommandDto, RaceDto, RaceLiveQueryDto, RaceMessageDto, RaceRequestDto, RollingAverageDto, RollingAverageQueryDto, RollingAveragesDto, ScrambleDto, SolutionDto, SolveRequestDto, TokenDto, UserSolveDto, UserSolvesDto, UserStatsDto};
+use crate::api::error::ApiError;
+use crate::api::moves::ValidatedMoves;
+use crate::api::openapi::ApiDoc;
 use crate::api::state::AppState;
-use crate::domain::models::DailySolve;
+use crate::config::settings::{CorsOrigins, RateLimit};
+use crate::domain::challenge::ChallengeWindow;
+use crate::domain::cube_state::CubeState;
+use crate::domain::models::{DailySolve, FmcSolve};
+use crate::domain::events::{Event, ScrambleKind};
+use crate::domain::leaderboards::{LeaderboardFilter, Period, Ranking};
+use crate::domain::races::Race;
+use crate::services::races::RaceView;
+use crate::services::scramble_service::SCRAMBLE_GENERATOR_VERSION;
 
-pub fn build_router(state: AppState) -> Router {
-    Router::new()
+/// Clients are told apart by their address, so the router must be served with `into_make_service_with_connect_info`.
+/// Also returns the cleanup of the rate limit, forgetting the clients whose quota is full again,
+/// to spawn once for as long as the router is served.
+pub fn build_router(state: AppState, cors_origins: &CorsOrigins, rate_limit: RateLimit) -> (Router, impl Future<Output = ()> + Send + 'static) {
+    let allowed_origins = match cors_origins {
+        CorsOrigins::Any => AllowOrigin::any(),
+        CorsOrigins::List(origins) => AllowOrigin::list(origins.clone()),
+    };
+    let cors = CorsLayer::new()
+        .allow_origin(allowed_origins)
+        .allow_methods(Any)
+        .allow_headers(Any);
+
+    let rate_limit_config = GovernorConfigBuilder::default()
+        .period(Duration::from_secs(60) / rate_limit.per_minute)
+        .burst_size(rate_limit.burst)
+        .finish()
+        .expect("Rate limit should be validated by the settings");
+    let limiter = rate_limit_config.limiter().clone();
+    let rate_limit_cleanup = async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            limiter.retain_recent();
+        }
+    };
+
+    // Routes running the solver, limited per client IP
+    let limited = Router::new()
         .route("/cube/scramble", get(get_cube_scramble))
+        .route("/cube/solve", post(post_cube_solve))
+        .route("/races", post(post_race))
+        // Dated dailies, which can be scanned day after day, today's one being generated on request
+        .route("/daily/{date}/scramble", get(get_date_scramble))
+        .route("/daily/{date}/fmc/leaderboard", get(get_date_fmc_leaderboard))
+        .layer(GovernorLayer::new(rate_limit_config).error_handler(rate_limit_error));
+
+    let router = Router::new()
+        .merge(limited)
         .route("/cube/solved", get(get_cube_is_solved))
+        .route("/cube/state", get(get_cube_state))
+        .route("/cube/state", post(post_cube_state))
         .route("/daily/scramble", get(get_daily_scramble))
         .route("/daily/scramble", post(post_daily_scramble))
         .route("/daily/leaderboard", get(get_daily_leaderboard))
-        .layer(CorsLayer::permissive())
+        .route("/daily/history", get(get_daily_history))
+        .route("/daily/challenge", get(get_daily_challenge))
+        .route("/daily/{date}/leaderboard", get(get_date_leaderboard))
+        .route("/daily/fmc", post(post_daily_fmc))
+        .route("/daily/fmc/leaderboard", get(get_daily_fmc_leaderboard))
+        .route("/events", get(get_events))
+        .route("/daily/events/{event}", post(post_daily_event_round))
+        .route("/daily/events/{event}/scrambles", get(get_daily_event_scrambles))
+        .route("/daily/events/{event}/{date}/scrambles", get(get_date_event_scrambles))
+        .route("/daily/events/{event}/leaderboard", get(get_daily_event_leaderboard))
+        .route("/daily/events/{event}/{date}/leaderboard", get(get_date_event_leaderboard))
+        .route("/leaderboards/{period}", get(get_period_leaderboard))
+        .route("/users", post(post_user))
+        .route("/users/me/profile", put(put_user_profile))
+        .route("/users/{name}/solves", get(get_user_solves))
+        .route("/users/{name}/stats", get(get_user_stats))
+        .route("/users/{name}/averages", get(get_user_averages))
+        .route("/auth/login", post(post_login))
+        .route("/races/{id}", get(get_race))
+        .route("/races/{id}/participants", post(post_race_participant))
+        .route("/races/{id}/live", get(get_race_live))
+        .route("/openapi.json", get(get_openapi))
+        .layer(cors)
         .layer(TraceLayer::new_for_http())
-        .with_state(state)
+        .with_state(state);
+    (router, rate_limit_cleanup)
+}
+
+fn rate_limit_error(error: GovernorError) -> Response {
+    match error {
+        GovernorError::TooManyRequests { wait_time, .. } => {
+            let mut response = ApiError::TooManyRequests(format!("too many requests, retry in {} seconds.", wait_time)).into_response();
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(wait_time));
+            response
+        },
+        error => ApiError::Internal(format!("rate limit failed: {}", error)).into_response(),
+    }
 }
 
-async fn get_cube_scramble(State(state): State<AppState>) -> String {
-    state.scramble_service.get_random_scramble().await
+#[utoipa::path(get, path = "/cube/scramble", tag = "cube",
+    responses(
+        (status = OK, description = "Random 3x3x3 scramble", body = ScrambleDto),
+        (status = TOO_MANY_REQUESTS, response = ProblemDto),
+        (status = SERVICE_UNAVAILABLE, response = ProblemDto),
+    )
+)]
+async fn get_cube_scramble(State(state): State<AppState>) -> Result<Json<ScrambleDto>, ApiError> {
+    let scramble = state.scramble_pool.take().await?;
+    Ok(Json(ScrambleDto::new(scramble, ScrambleKind::Cube3x3.puzzle(), SCRAMBLE_GENERATOR_VERSION, state.scramble_svg_url.as_deref())))
 }
 
-async fn get_daily_scramble(State(state): State<AppState>) -> String {
-    let date = Utc::now().date_naive();
-    state.scramble_service.get_daily_scramble(date).await
+#[utoipa::path(get, path = "/daily/scramble", tag = "daily",
+    responses((status = OK, description = "Scramble of the current daily", body = ScrambleDto))
+)]
+async fn get_daily_scramble(State(state): State<AppState>) -> Result<Json<ScrambleDto>, ApiError> {
+    let date = state.today();
+    daily_scramble(&state, date).await
 }
 
+#[utoipa::path(get, path = "/daily/{date}/scramble", tag = "daily",
+    params(("date" = String, Path, description = "YYYY-MM-DD")),
+    responses(
+        (status = OK, description = "Scramble of the daily of `date`", body = ScrambleDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_date_scramble(
+    State(state): State<AppState>,
+    Path(date_str): Path<String>
+) -> Result<Json<ScrambleDto>, ApiError> {
+    let date = parse_past_date(&state, &date_str)?;
+    daily_scramble(&state, date).await
+}
+
+/// Today's scramble is generated on the first request, past ones are only read back.
+async fn daily_scramble(state: &AppState, date: NaiveDate) -> Result<Json<ScrambleDto>, ApiError> {
+    let daily_scramble = if date == state.today() {
+        state.daily_scramble_service.get_daily_scramble(date).await?
+    } else {
+        state.daily_scramble_service.fetch_served_scramble(date).await?
+    };
+
+    Ok(Json(ScrambleDto::new(
+        daily_scramble.scramble,
+        ScrambleKind::Cube3x3.puzzle(),
+        daily_scramble.version,
+        state.scramble_svg_url.as_deref(),
+    )))
+}
+
+#[utoipa::path(post, path = "/daily/scramble", tag = "daily",
+    request_body = DailySolveRequestDto,
+    security(("bearer" = [])),
+    responses(
+        (status = OK, description = "Solve recorded"),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = CONFLICT, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
 async fn post_daily_scramble(
     State(state): State<AppState>,
+    AuthenticatedUser(username): AuthenticatedUser,
     extract::Json(request): extract::Json<DailySolveRequestDto>
-) -> Result<(), (StatusCode, String)> {
-    let today = Utc::now().date_naive();
+) -> Result<(), ApiError> {
+    let today = state.today();
+    let move_count = match request.solution {
+        Some(solution) => {
+            let daily_scramble = state.daily_scramble_service.get_daily_scramble(today).await?;
+            let move_count = state.scramble_service.verify_solution(&daily_scramble.scramble, &solution)?;
+            Some(move_count as i32)
+        },
+        None => None,
+    };
     let solve = DailySolve {
-        username: request.username,
+        username,
         date: today,
         time: request.time,
+        move_count,
+        verified: move_count.is_some(),
+    };
+    state.daily_solve_service.add_daily_solve(solve).await.map_err(ApiError::from)
+}
+
+#[utoipa::path(get, path = "/daily/leaderboard", tag = "daily",
+    responses((status = OK, description = "Solves of the current daily, fastest first", body = DailySolveListDto))
+)]
+async fn get_daily_leaderboard(State(state): State<AppState>) -> Result<Json<DailySolveListDto>, ApiError> {
+    let date = state.today();
+    daily_leaderboard(&state, date).await
+}
+
+async fn daily_leaderboard(state: &AppState, date: NaiveDate) -> Result<Json<DailySolveListDto>, ApiError> {
+    let solves = state.daily_solve_service.fetch_solves_by_date(date).await?;
+
+    Ok(Json(DailySolveListDto::from(solves)))
+}
+
+#[utoipa::path(get, path = "/daily/history", tag = "daily",
+    params(PaginationQueryDto),
+    responses(
+        (status = OK, description = "Past dailies, most recent first", body = DailyHistoryDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_daily_history(
+    State(state): State<AppState>,
+    Query(pagination): Query<PaginationQueryDto>
+) -> Result<Json<DailyHistoryDto>, ApiError> {
+    let today = state.today();
+    let page = pagination.page.unwrap_or(1);
+    let per_page = pagination.per_page.unwrap_or(20);
+    let (summaries, total) = state.daily_scramble_service.fetch_history(today, page, per_page).await?;
+
+    Ok(Json(DailyHistoryDto {
+        page,
+        per_page,
+        total,
+        dailies: summaries.into_iter().map(DailySummaryDto::from).collect(),
+    }))
+}
+
+#[utoipa::path(get, path = "/daily/challenge", tag = "daily",
+    responses((status = OK, description = "Window of the current daily", body = DailyChallengeDto))
+)]
+async fn get_daily_challenge(State(state): State<AppState>) -> Json<DailyChallengeDto> {
+    let now = state.clock.now();
+    let challenge = ChallengeWindow::containing(now, state.reset_timezone);
+    Json(DailyChallengeDto::new(challenge, state.reset_timezone, now))
+}
+
+#[utoipa::path(get, path = "/events", tag = "events",
+    responses((status = OK, description = "Events with a daily round", body = EventListDto))
+)]
+async fn get_events(State(state): State<AppState>) -> Result<Json<EventListDto>, ApiError> {
+    let events = state.events_service.fetch_events().await?;
+
+    Ok(Json(EventListDto { events: events.into_iter().map(EventDto::from).collect() }))
+}
+
+async fn find_event(state: &AppState, event_id: &str) -> Result<Event, ApiError> {
+    let event = state.events_service.fetch_event(event_id).await?;
+
+    event.ok_or_else(|| ApiError::NotFound(format!("event '{}' does not exist.", event_id)))
+}
+
+#[utoipa::path(get, path = "/daily/events/{event}/scrambles", tag = "events",
+    params(("event" = String, Path, description = "Event id")),
+    responses(
+        (status = OK, description = "Scrambles of the current round", body = DailyRoundScramblesDto),
+        (status = NOT_FOUND, response = ProblemDto),
+    )
+)]
+async fn get_daily_event_scrambles(
+    State(state): State<AppState>,
+    Path(event_id): Path<String>
+) -> Result<Json<DailyRoundScramblesDto>, ApiError> {
+    let date = state.today();
+    event_scrambles(&state, &event_id, date).await
+}
+
+#[utoipa::path(get, path = "/daily/events/{event}/{date}/scrambles", tag = "events",
+    params(("event" = String, Path, description = "Event id"), ("date" = String, Path, description = "YYYY-MM-DD")),
+    responses(
+        (status = OK, description = "Scrambles of the round of `date`", body = DailyRoundScramblesDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_date_event_scrambles(
+    State(state): State<AppState>,
+    Path((event_id, date_str)): Path<(String, String)>
+) -> Result<Json<DailyRoundScramblesDto>, ApiError> {
+    let date = parse_past_date(&state, &date_str)?;
+    event_scrambles(&state, &event_id, date).await
+}
+
+/// Today's scrambles are generated on the first request, past ones are only read back.
+async fn event_scrambles(state: &AppState, event_id: &str, date: NaiveDate) -> Result<Json<DailyRoundScramblesDto>, ApiError> {
+    let event = find_event(state, event_id).await?;
+    let scrambles = if date == state.today() {
+        state.daily_scramble_service.get_round_scrambles(&event, date).await?
+    } else {
+        state.daily_scramble_service.fetch_served_round_scrambles(&event, date).await?
+    };
+    let puzzle = event.scramble_kind.puzzle();
+
+    Ok(Json(DailyRoundScramblesDto {
+        event: event.id,
+        format: event.format.to_string(),
+        scrambles: scrambles
+            .into_iter()
+            .map(|scramble| ScrambleDto::new(scramble, puzzle, SCRAMBLE_GENERATOR_VERSION, state.scramble_svg_url.as_deref()))
+            .collect(),
+    }))
+}
+
+#[utoipa::path(post, path = "/daily/events/{event}", tag = "events",
+    params(("event" = String, Path, description = "Event id")),
+    request_body = DailyRoundRequestDto,
+    security(("bearer" = [])),
+    responses(
+        (status = OK, description = "Round recorded"),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = CONFLICT, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn post_daily_event_round(
+    State(state): State<AppState>,
+    Path(event_id): Path<String>,
+    AuthenticatedUser(username): AuthenticatedUser,
+    extract::Json(request): extract::Json<DailyRoundRequestDto>
+) -> Result<(), ApiError> {
+    let today = state.today();
+    let event = find_event(&state, &event_id).await?;
+    let attempts = request.attempts.into_iter().map(|attempt| attempt.into()).collect();
+    state.daily_round_service.add_daily_round(&event, username, today, attempts).await.map_err(ApiError::from)
+}
+
+#[utoipa::path(get, path = "/daily/{date}/leaderboard", tag = "daily",
+    params(("date" = String, Path, description = "YYYY-MM-DD")),
+    responses(
+        (status = OK, description = "Solves of the daily of `date`, fastest first", body = DailySolveListDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_date_leaderboard(
+    State(state): State<AppState>,
+    Path(date_str): Path<String>
+) -> Result<Json<DailySolveListDto>, ApiError> {
+    let date = parse_past_date(&state, &date_str)?;
+    daily_leaderboard(&state, date).await
+}
+
+#[utoipa::path(get, path = "/daily/events/{event}/leaderboard", tag = "events",
+    params(("event" = String, Path, description = "Event id")),
+    responses(
+        (status = OK, description = "Rounds of the current day, best first", body = DailyRoundListDto),
+        (status = NOT_FOUND, response = ProblemDto),
+    )
+)]
+async fn get_daily_event_leaderboard(
+    State(state): State<AppState>,
+    Path(event_id): Path<String>
+) -> Result<Json<DailyRoundListDto>, ApiError> {
+    let date = state.today();
+    event_leaderboard(&state, &event_id, date).await
+}
+
+#[utoipa::path(get, path = "/daily/events/{event}/{date}/leaderboard", tag = "events",
+    params(("event" = String, Path, description = "Event id"), ("date" = String, Path, description = "YYYY-MM-DD")),
+    responses(
+        (status = OK, description = "Rounds of `date`, best first", body = DailyRoundListDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_date_event_leaderboard(
+    State(state): State<AppState>,
+    Path((event_id, date_str)): Path<(String, String)>
+) -> Result<Json<DailyRoundListDto>, ApiError> {
+    let date = parse_past_date(&state, &date_str)?;
+    event_leaderboard(&state, &event_id, date).await
+}
+
+async fn event_leaderboard(state: &AppState, event_id: &str, date: NaiveDate) -> Result<Json<DailyRoundListDto>, ApiError> {
+    let event = find_event(state, event_id).await?;
+    let rounds = state.daily_round_service.fetch_rounds_by_date(&event, date).await?;
+
+    Ok(Json(DailyRoundListDto::from(rounds)))
+}
+
+#[utoipa::path(post, path = "/daily/fmc", tag = "fmc",
+    request_body = FmcSolveRequestDto,
+    security(("bearer" = [])),
+    responses(
+        (status = OK, description = "Solution recorded"),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = CONFLICT, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn post_daily_fmc(
+    State(state): State<AppState>,
+    AuthenticatedUser(username): AuthenticatedUser,
+    extract::Json(request): extract::Json<FmcSolveRequestDto>
+) -> Result<(), ApiError> {
+    let today = state.today();
+    let daily_scramble = state.daily_scramble_service.get_daily_scramble(today).await?;
+    let move_count = state.scramble_service.verify_fmc_solution(&daily_scramble.scramble, &request.solution)?;
+
+    let fmc_solve = FmcSolve {
+        username,
+        date: today,
+        solution: request.solution,
+        move_count: move_count as i32,
+    };
+    state.fmc_solve_service.add_fmc_solve(fmc_solve).await.map_err(ApiError::from)
+}
+
+#[utoipa::path(get, path = "/daily/fmc/leaderboard", tag = "fmc",
+    responses((status = OK, description = "Move counts of the current daily, solutions hidden", body = FmcLeaderboardDto))
+)]
+async fn get_daily_fmc_leaderboard(State(state): State<AppState>) -> Result<Json<FmcLeaderboardDto>, ApiError> {
+    let date = state.today();
+    fmc_leaderboard(&state, date).await
+}
+
+#[utoipa::path(get, path = "/daily/{date}/fmc/leaderboard", tag = "fmc",
+    params(("date" = String, Path, description = "YYYY-MM-DD")),
+    responses(
+        (status = OK, description = "Move counts of the daily of `date`, solutions shown once it is over", body = FmcLeaderboardDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_date_fmc_leaderboard(
+    State(state): State<AppState>,
+    Path(date_str): Path<String>
+) -> Result<Json<FmcLeaderboardDto>, ApiError> {
+    let date = parse_past_date(&state, &date_str)?;
+    fmc_leaderboard(&state, date).await
+}
+
+/// Solutions and the best known upper bound are only shown once the day is over.
+async fn fmc_leaderboard(state: &AppState, date: NaiveDate) -> Result<Json<FmcLeaderboardDto>, ApiError> {
+    let is_over = date < state.today();
+    let solves = state.fmc_solve_service.fetch_solves_by_date(date).await?;
+
+    let best_known_upper_bound = if is_over {
+        state.daily_scramble_service.fetch_served_scramble(date).await?.fmc_best_length
+    } else {
+        None
     };
-    let result = state.daily_solve_service.add_daily_solve(solve).await;
-    
-    result.map_err(|err_str| (StatusCode::BAD_REQUEST, err_str))
+
+    Ok(Json(FmcLeaderboardDto {
+        solves: solves
+            .into_iter()
+            .map(|solve| FmcSolveDto {
+                username: solve.username,
+                move_count: solve.move_count,
+                solution: is_over.then_some(solve.solution),
+            })
+            .collect(),
+        best_known_upper_bound,
+    }))
 }
 
-async fn get_daily_leaderboard(State(state): State<AppState>) -> Result<Json<DailySolveListDto>, (StatusCode, String)> {
-    let date = Utc::now().date_naive();
-    let solves = state.daily_solve_service.fetch_today_solves(date).await;
+/// Parses a `YYYY-MM-DD` date, dailies of future dates are not available yet.
+fn parse_past_date(state: &AppState, date_str: &str) -> Result<NaiveDate, ApiError> {
+    let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        .map_err(|_| ApiError::Validation("date must be formatted as YYYY-MM-DD.".into()))?;
 
-    match solves {
-        Ok(solves) => Ok(Json(DailySolveListDto::from(solves))),
-        Err(err_str) => Err((StatusCode::BAD_REQUEST, err_str)),
+    if date > state.today() {
+        return Err(ApiError::NotFound("daily is not available yet.".into()));
     }
+    Ok(date)
+}
+
+#[utoipa::path(post, path = "/users", tag = "users",
+    request_body = CredentialsDto,
+    responses(
+        (status = CREATED, description = "User registered"),
+        (status = CONFLICT, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn post_user(
+    State(state): State<AppState>,
+    extract::Json(request): extract::Json<CredentialsDto>
+) -> Result<StatusCode, ApiError> {
+    state.users_service.register(request.username, request.password).await?;
+
+    Ok(StatusCode::CREATED)
+}
 
+#[utoipa::path(get, path = "/leaderboards/{period}", tag = "leaderboards",
+    params(("period" = String, Path, description = "`week`, `month` or `all`"), LeaderboardQueryDto),
+    responses(
+        (status = OK, description = "Users ranked over the period", body = PeriodLeaderboardDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_period_leaderboard(
+    State(state): State<AppState>,
+    Path(period_str): Path<String>,
+    Query(query): Query<LeaderboardQueryDto>
+) -> Result<Json<PeriodLeaderboardDto>, ApiError> {
+    let today = state.today();
+    let period: Period = period_str.parse().map_err(ApiError::NotFound)?;
+    let ranking = query.ranking
+        .map(|ranking| ranking.parse::<Ranking>())
+        .transpose()
+        .map_err(ApiError::Validation)?
+        .unwrap_or_default();
+    let filter = LeaderboardFilter {
+        from: period.start(today),
+        to: today,
+        ranking,
+        average_of: query.average_of.unwrap_or(5),
+        min_solves: query.min_solves.unwrap_or(1),
+        country: query.country,
+        club: query.club,
+    };
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(20);
+    let (entries, total) = state.daily_solve_service.fetch_leaderboard(filter.clone(), page, per_page).await?;
+
+    Ok(Json(PeriodLeaderboardDto {
+        period: period.to_string(),
+        from: filter.from.map(|from| from.format("%Y-%m-%d").to_string()),
+        to: filter.to.format("%Y-%m-%d").to_string(),
+        page,
+        per_page,
+        total,
+        entries: entries.into_iter().map(LeaderboardEntryDto::from).collect(),
+    }))
 }
 
-// TODO Make an axum extractor for scrambles / put logic into service
-// Was here to test at start but useless now 
-async fn get_cube_is_solved(
+#[utoipa::path(put, path = "/users/me/profile", tag = "users",
+    request_body = ProfileDto,
+    security(("bearer" = [])),
+    responses(
+        (status = OK, description = "Profile updated"),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn put_user_profile(
     State(state): State<AppState>,
-    Query(params): Query<HashMap<String, String>>
-) -> Result<Json<bool>, (StatusCode, String)> {
-    let moves_key = params.get("moves");
+    AuthenticatedUser(username): AuthenticatedUser,
+    extract::Json(request): extract::Json<ProfileDto>
+) -> Result<(), ApiError> {
+    state.users_service.update_profile(&username, request.country, request.club).await.map_err(ApiError::from)
+}
 
-    match moves_key {
-        Some(moves_str) => {
-            match state.scramble_service.is_cube_solved(moves_str) {
-                Some(is_solved) => Ok(Json(is_solved)),
-                None => Err((StatusCode::BAD_REQUEST, "'moves' sequence is invalid.".into())),
-            }
-        },
-        None => Err((StatusCode::BAD_REQUEST, "'moves' query parameter is missing.".into())),
+#[utoipa::path(get, path = "/users/{name}/solves", tag = "users",
+    params(("name" = String, Path, description = "Username"), PaginationQueryDto),
+    responses(
+        (status = OK, description = "Daily solves of the user, most recent first", body = UserSolvesDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_user_solves(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Query(pagination): Query<PaginationQueryDto>
+) -> Result<Json<UserSolvesDto>, ApiError> {
+    let page = pagination.page.unwrap_or(1);
+    let per_page = pagination.per_page.unwrap_or(20);
+    let (solves, total) = state.daily_solve_service.fetch_user_solves(&username, page, per_page).await?;
+
+    Ok(Json(UserSolvesDto {
+        page,
+        per_page,
+        total,
+        solves: solves.into_iter().map(UserSolveDto::from).collect(),
+    }))
+}
+
+#[utoipa::path(get, path = "/users/{name}/stats", tag = "users",
+    params(("name" = String, Path, description = "Username")),
+    responses((status = OK, description = "Statistics of the user", body = UserStatsDto))
+)]
+async fn get_user_stats(
+    State(state): State<AppState>,
+    Path(username): Path<String>
+) -> Result<Json<UserStatsDto>, ApiError> {
+    let today = state.today();
+    let (stats, ranks) = state.daily_solve_service.fetch_user_stats(&username, today).await?;
+
+    Ok(Json(UserStatsDto::new(stats, ranks)))
+}
+
+#[utoipa::path(get, path = "/users/{name}/averages", tag = "users",
+    params(("name" = String, Path, description = "Username"), RollingAverageQueryDto),
+    responses(
+        (status = OK, description = "Rolling averages of the user, oldest first", body = RollingAveragesDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_user_averages(
+    State(state): State<AppState>,
+    Path(username): Path<String>,
+    Query(query): Query<RollingAverageQueryDto>
+) -> Result<Json<RollingAveragesDto>, ApiError> {
+    let size = query.size.unwrap_or(5);
+    let points = query.points.unwrap_or(100);
+    let averages = state.daily_solve_service.fetch_rolling_averages(&username, size, points).await?;
+
+    Ok(Json(RollingAveragesDto {
+        size,
+        averages: averages.into_iter().map(RollingAverageDto::from).collect(),
+    }))
+}
+
+#[utoipa::path(post, path = "/auth/login", tag = "users",
+    request_body = CredentialsDto,
+    responses(
+        (status = OK, description = "Bearer token valid for a week", body = TokenDto),
+        (status = UNAUTHORIZED, response = ProblemDto),
+    )
+)]
+async fn post_login(
+    State(state): State<AppState>,
+    extract::Json(request): extract::Json<CredentialsDto>
+) -> Result<Json<TokenDto>, ApiError> {
+    let token = state.users_service.login(request.username, request.password).await?;
+
+    Ok(Json(TokenDto { token }))
+}
+
+#[utoipa::path(get, path = "/cube/solved", tag = "cube",
+    params(MovesDto),
+    responses(
+        (status = OK, description = "Whether the moves leave the cube solved", body = CubeSolvedDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_cube_is_solved(moves: ValidatedMoves) -> Json<CubeSolvedDto> {
+    Json(CubeSolvedDto { solved: moves.cube().is_solved() })
+}
+
+#[utoipa::path(get, path = "/cube/state", tag = "cube",
+    params(MovesDto),
+    responses(
+        (status = OK, description = "State of the cube after the moves", body = CubeStateDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn get_cube_state(moves: ValidatedMoves) -> Json<CubeStateDto> {
+    Json(CubeState::of(&moves.cube()).into())
+}
+
+/// Same as `GET /cube/state`, for move sequences too long for a URL.
+#[utoipa::path(post, path = "/cube/state", tag = "cube",
+    request_body = MovesDto,
+    responses(
+        (status = OK, description = "State of the cube after the moves", body = CubeStateDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+    )
+)]
+async fn post_cube_state(moves: ValidatedMoves) -> Json<CubeStateDto> {
+    Json(CubeState::of(&moves.cube()).into())
+}
+
+#[utoipa::path(post, path = "/cube/solve", tag = "cube",
+    request_body = SolveRequestDto,
+    responses(
+        (status = OK, description = "Solution found by the solver", body = SolutionDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+        (status = TOO_MANY_REQUESTS, response = ProblemDto),
+        (status = SERVICE_UNAVAILABLE, response = ProblemDto),
+    )
+)]
+async fn post_cube_solve(
+    State(state): State<AppState>,
+    extract::Json(request): extract::Json<SolveRequestDto>
+) -> Result<Json<SolutionDto>, ApiError> {
+    let cube = match (&request.moves, &request.facelets) {
+        (Some(moves), None) => ValidatedMoves::parse(moves)?.cube(),
+        (None, Some(facelets)) => state.solver_service.cube_from_facelets(facelets)?,
+        _ => return Err(ApiError::Validation("exactly one of 'moves' and 'facelets' is required.".into())),
+    };
+
+    let max_length = request.max_length.unwrap_or(20);
+    let timeout = request.timeout_seconds.unwrap_or(state.solver_service.max_timeout());
+    let solver = request.solver.into();
+    let solution = state.solver_service.solve(cube, solver, max_length, timeout).await?;
+
+    Ok(Json(SolutionDto::new(solver, solution)))
+}
+
+#[utoipa::path(post, path = "/races", tag = "races",
+    request_body = RaceRequestDto,
+    security(("bearer" = [])),
+    responses(
+        (status = CREATED, description = "Race room opened, waiting for participants", body = RaceDto),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = UNPROCESSABLE_ENTITY, response = ProblemDto),
+        (status = TOO_MANY_REQUESTS, response = ProblemDto),
+    )
+)]
+async fn post_race(
+    State(state): State<AppState>,
+    AuthenticatedUser(username): AuthenticatedUser,
+    extract::Json(request): extract::Json<RaceRequestDto>
+) -> Result<(StatusCode, Json<RaceDto>), ApiError> {
+    let event = find_event(&state, &request.event).await?;
+    let race = state.races_service.create_race(username, event, request.rounds.unwrap_or(5)).await?;
+
+    Ok((StatusCode::CREATED, Json(RaceDto::live(race, state.scramble_svg_url.as_deref()))))
+}
+
+#[utoipa::path(post, path = "/races/{id}/participants", tag = "races",
+    params(("id" = String, Path, description = "Race id")),
+    security(("bearer" = [])),
+    responses(
+        (status = OK, description = "Race joined, joining twice does nothing", body = RaceDto),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = NOT_FOUND, response = ProblemDto),
+        (status = CONFLICT, response = ProblemDto),
+    )
+)]
+async fn post_race_participant(
+    State(state): State<AppState>,
+    AuthenticatedUser(username): AuthenticatedUser,
+    Path(id): Path<String>
+) -> Result<Json<RaceDto>, ApiError> {
+    let race = state.races_service.join_race(&id, &username)?;
+
+    Ok(Json(RaceDto::live(race, state.scramble_svg_url.as_deref())))
+}
+
+#[utoipa::path(get, path = "/races/{id}", tag = "races",
+    params(("id" = String, Path, description = "Race id")),
+    responses(
+        (status = OK, description = "Live race, or its results once finished", body = RaceDto),
+        (status = NOT_FOUND, response = ProblemDto),
+    )
+)]
+async fn get_race(
+    State(state): State<AppState>,
+    Path(id): Path<String>
+) -> Result<Json<RaceDto>, ApiError> {
+    let race = match state.races_service.fetch_race(&id).await? {
+        RaceView::Live(race) => RaceDto::live(race, state.scramble_svg_url.as_deref()),
+        RaceView::Finished(race) => RaceDto::finished(race),
+    };
+
+    Ok(Json(race))
+}
+
+/// WebSocket pushing a `RaceMessageDto` on every change of the race until it finishes.
+/// Participants connected with their token send `RaceCommandDto`s, other clients only follow the race.
+#[utoipa::path(get, path = "/races/{id}/live", tag = "races",
+    params(("id" = String, Path, description = "Race id"), RaceLiveQueryDto),
+    responses(
+        (status = SWITCHING_PROTOCOLS, description = "WebSocket opened"),
+        (status = UNAUTHORIZED, response = ProblemDto),
+        (status = NOT_FOUND, response = ProblemDto),
+    )
+)]
+async fn get_race_live(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<RaceLiveQueryDto>,
+    upgrade: WebSocketUpgrade
+) -> Result<Response, ApiError> {
+    let username = query.token.map(|token| state.users_service.authenticate(&token)).transpose()?;
+    let (race, updates) = state.races_service.subscribe(&id)?;
+
+    Ok(upgrade.on_upgrade(move |socket| race_socket(socket, state, id, username, race, updates)))
+}
+
+async fn race_socket(
+    mut socket: WebSocket,
+    state: AppState,
+    id: String,
+    username: Option<String>,
+    race: Race,
+    mut updates: broadcast::Receiver<Race>
+) {
+    let svg_url = state.scramble_svg_url.clone();
+    let race_message = |race: Race| RaceMessageDto::Race(Box::new(RaceDto::live(race, svg_url.as_deref())));
+    if send_race_message(&mut socket, race_message(race)).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(race) => {
+                    if send_race_message(&mut socket, race_message(race)).await.is_err() {
+                        return;
+                    }
+                },
+                // Later states follow
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                // Room closed once the race is stored
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Err(error) = race_command(&state, &id, username.as_deref(), &text).await {
+                        let problem = error.problem();
+                        let message = RaceMessageDto::Error { status: problem.status, detail: problem.detail };
+                        if send_race_message(&mut socket, message).await.is_err() {
+                            return;
+                        }
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                // Pings are answered by axum
+                Some(Ok(_)) => {},
+            },
+        }
     }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+async fn send_race_message(socket: &mut WebSocket, message: RaceMessageDto) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(&message).expect("Race messages should serialize");
+    socket.send(Message::Text(text.into())).await
+}
+
+async fn race_command(state: &AppState, id: &str, username: Option<&str>, text: &str) -> Result<(), ApiError> {
+    let username = username.ok_or_else(|| ApiError::Unauthorized("connect with a token to take part.".into()))?;
+    let command: RaceCommandDto = serde_json::from_str(text)
+        .map_err(|error| ApiError::Validation(format!("command is invalid: {}.", error)))?;
+
+    let result = match command {
+        RaceCommandDto::Start => state.races_service.start_race(id, username),
+        RaceCommandDto::Submit(attempt) => state.races_service.submit_attempt(id, username, attempt.into(), state.today()).await,
+        RaceCommandDto::Next => state.races_service.next_round(id, username, state.today()).await,
+    };
+    result.map_err(ApiError::from)
+}
+
+/// OpenAPI 3 document of the routes, to generate clients from.
+async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
 }
@@ -1,9 +1,48 @@
+use std::sync::Arc;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use crate::api::clock::Clock;
+use crate::services::scramble_pool::ScramblePool;
 use crate::services::scramble_service::ScrambleService;
+use crate::services::daily_rounds::DailyRoundsService;
+use crate::services::daily_scrambles::DailyScramblesService;
 use crate::services::daily_solves::DailySolvesService;
-use crate::database::daily_solves::SqliteDailySolvesRepository;
+use crate::services::events::EventsService;
+use crate::services::fmc_solves::FmcSolvesService;
+use crate::services::races::RacesService;
+use crate::services::solver::SolverService;
+use crate::services::users::UsersService;
+use crate::repositories::daily_round::DailyRoundsRepository;
+use crate::repositories::daily_scramble::DailyScramblesRepository;
+use crate::repositories::daily_solve::DailySolvesRepository;
+use crate::repositories::event::EventsRepository;
+use crate::repositories::fmc_solve::FmcSolvesRepository;
+use crate::repositories::race::RacesRepository;
+use crate::repositories::user::UsersRepository;
 
+/// Repositories are trait objects so the database backend is chosen at startup.
 #[derive(Clone)]
 pub struct AppState {
     pub scramble_service: ScrambleService,
-    pub daily_solve_service: DailySolvesService<SqliteDailySolvesRepository>
-}
\ No newline at end of file
+    pub scramble_pool: ScramblePool,
+    pub daily_scramble_service: DailyScramblesService<Arc<dyn DailyScramblesRepository>>,
+    pub daily_solve_service: DailySolvesService<Arc<dyn DailySolvesRepository>>,
+    pub daily_round_service: DailyRoundsService<Arc<dyn DailyRoundsRepository>>,
+    pub events_service: EventsService<Arc<dyn EventsRepository>>,
+    pub fmc_solve_service: FmcSolvesService<Arc<dyn FmcSolvesRepository>>,
+    pub users_service: UsersService<Arc<dyn UsersRepository>>,
+    pub solver_service: SolverService,
+    pub races_service: RacesService<Arc<dyn RacesRepository>>,
+    pub clock: Arc<dyn Clock>,
+    /// Timezone in which a new daily starts at midnight
+    pub reset_timezone: Tz,
+    /// Link to an image of a scramble, `{moves}` standing for the moves
+    pub scramble_svg_url: Option<String>,
+}
+
+impl AppState {
+    /// Date of the current daily.
+    pub fn today(&self) -> NaiveDate {
+        self.clock.now().with_timezone(&self.reset_timezone).date_naive()
+    }
+}
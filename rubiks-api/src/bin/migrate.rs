@@ -1,4 +1,76 @@
+use rubiks_api::config::settings::Settings;
+use rubiks_api::database::migration::{create_db_if_missing, database_exists, migration_status, pending_migrations, run_migrations, MigrationStatus};
+use rubiks_api::database::pool::{build_pool, Backend};
+
+const USAGE: &str = "usage: migrate [up|status] [--dry-run]";
+
 #[tokio::main]
 async fn main() {
-    rubiks_api::database::migration::ensure_db().await;
-}
\ No newline at end of file
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let command = args.iter().find(|arg| !arg.starts_with("--")).map_or("up", String::as_str);
+
+    let result = match Settings::load() {
+        Ok(settings) => match command {
+            "up" => up(&settings.database_url, dry_run).await,
+            "status" => status(&settings.database_url).await,
+            _ => Err(USAGE.to_string()),
+        },
+        Err(error) => Err(error),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+/// Applies the pending migrations, or only lists them on a dry run.
+async fn up(database_url: &str, dry_run: bool) -> Result<(), String> {
+    if !dry_run && create_db_if_missing(database_url).await? {
+        println!("Created database {}", database_url);
+    }
+
+    let pending: Vec<_> = status_of(database_url).await?
+        .into_iter()
+        .filter(|migration| !migration.applied)
+        .collect();
+
+    if pending.is_empty() {
+        println!("Database is up to date");
+        return Ok(());
+    }
+
+    for migration in &pending {
+        let action = if dry_run { "Would apply" } else { "Applying" };
+        println!("{} {} {}", action, migration.version, migration.description);
+    }
+
+    if !dry_run {
+        run_migrations(&build_pool(database_url)?).await?;
+        println!("Applied {} migrations", pending.len());
+    }
+    Ok(())
+}
+
+async fn status(database_url: &str) -> Result<(), String> {
+    for migration in status_of(database_url).await? {
+        let state = match (migration.applied, migration.checksum_mismatch) {
+            (true, true) => "applied, modified since",
+            (true, false) => "applied",
+            (false, _) => "pending",
+        };
+        println!("{:>4} {:<24} {}", migration.version, migration.description, state);
+    }
+    Ok(())
+}
+
+/// A missing database is left uncreated, every migration showing as pending.
+async fn status_of(database_url: &str) -> Result<Vec<MigrationStatus>, String> {
+    if !database_exists(database_url).await? {
+        return Ok(pending_migrations(Backend::from_url(database_url)?));
+    }
+
+    let pool = build_pool(database_url)?;
+    migration_status(&pool).await
+}
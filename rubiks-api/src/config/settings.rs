@@ -0,0 +1,186 @@
+use std::net::SocketAddr;
+use axum::http::HeaderValue;
+use chrono_tz::Tz;
+use ::config::{Config, Environment, File};
+use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+use crate::database::pool::Backend;
+
+/// File read when `RUBIKS_CONFIG` does not name another one.
+pub const DEFAULT_CONFIG_FILE: &str = "rubiks-api.toml";
+
+/// Settings as written in the config file or environment, before validation.
+#[derive(Deserialize)]
+struct RawSettings {
+    database_url: String,
+    bind_address: String,
+    cors_origins: Vec<String>,
+    solver_timeout_seconds: f32,
+    max_concurrent_solves: usize,
+    scramble_pool_size: usize,
+    race_idle_minutes: u32,
+    rate_limit_per_minute: u32,
+    rate_limit_burst: u32,
+    reset_timezone: String,
+    log_level: String,
+    daily_scramble_salt: Option<String>,
+    jwt_secret: Option<String>,
+    require_daily_solution: bool,
+    scramble_svg_url: Option<String>,
+}
+
+/// Where the origins of cross-origin requests are allowed from.
+#[derive(Debug, Clone)]
+pub enum CorsOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+/// Requests a client IP can send to the routes running the solver.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    /// Requests regained per minute
+    pub per_minute: u32,
+    /// Requests that can be sent at once
+    pub burst: u32,
+}
+
+/// Validated server configuration.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub database_url: String,
+    pub bind_address: SocketAddr,
+    pub cors_origins: CorsOrigins,
+    /// Time given to the solver for each scramble search, and the longest a solve request can ask for
+    pub solver_timeout_seconds: f32,
+    /// Solver searches running at once, for scrambles and solve requests alike, the others waiting for their turn
+    pub max_concurrent_solves: usize,
+    /// Random scrambles generated ahead of `GET /cube/scramble` requests
+    pub scramble_pool_size: usize,
+    /// Minutes a race room is kept open without activity
+    pub race_idle_minutes: u32,
+    pub rate_limit: RateLimit,
+    /// Timezone in which a new daily starts at midnight
+    pub reset_timezone: Tz,
+    /// `tracing` filter directives
+    pub log_level: String,
+    /// Secret making daily scrambles unpredictable, `None` when unset
+    pub daily_scramble_salt: Option<String>,
+    /// Secret signing authentication tokens, `None` when unset
+    pub jwt_secret: Option<String>,
+    /// Rejects daily solves without a verified solution
+    pub require_daily_solution: bool,
+    /// Link to an image of a scramble, `{moves}` standing for the moves, `None` for no images
+    pub scramble_svg_url: Option<String>,
+}
+
+impl Settings {
+    /// Reads the config file, `RUBIKS_CONFIG` or `rubiks-api.toml` if present, then the environment variables,
+    /// which take precedence. Variables are named after the settings in upper case, like `DATABASE_URL`,
+    /// and `CORS_ORIGINS` is a comma separated list.
+    pub fn load() -> Result<Self, String> {
+        let path = std::env::var("RUBIKS_CONFIG").ok();
+        let file = match &path {
+            Some(path) => File::with_name(path).required(true),
+            None => File::with_name(DEFAULT_CONFIG_FILE).required(false),
+        };
+
+        let raw: RawSettings = Config::builder()
+            .set_default("database_url", "sqlite://rubiks.db")
+            .and_then(|builder| builder.set_default("bind_address", "0.0.0.0:3000"))
+            .and_then(|builder| builder.set_default("cors_origins", vec!["*"]))
+            .and_then(|builder| builder.set_default("solver_timeout_seconds", 5.0))
+            .and_then(|builder| builder.set_default("max_concurrent_solves", 2))
+            .and_then(|builder| builder.set_default("scramble_pool_size", 32))
+            .and_then(|builder| builder.set_default("race_idle_minutes", 30))
+            .and_then(|builder| builder.set_default("rate_limit_per_minute", 60))
+            .and_then(|builder| builder.set_default("rate_limit_burst", 20))
+            .and_then(|builder| builder.set_default("reset_timezone", "UTC"))
+            .and_then(|builder| builder.set_default("log_level", "info"))
+            .and_then(|builder| builder.set_default("require_daily_solution", false))
+            .map_err(|error| error.to_string())?
+            .add_source(file)
+            .add_source(
+                Environment::default()
+                    .try_parsing(true)
+                    .list_separator(",")
+                    .with_list_parse_key("cors_origins")
+            )
+            .build()
+            .and_then(|config| config.try_deserialize())
+            .map_err(|error| format!("config is invalid: {}", error))?;
+
+        raw.validate()
+    }
+}
+
+impl RawSettings {
+    fn validate(self) -> Result<Settings, String> {
+        let bind_address = self.bind_address
+            .parse()
+            .map_err(|_| format!("bind_address '{}' is not an address like 0.0.0.0:3000.", self.bind_address))?;
+
+        let cors_origins = if self.cors_origins.iter().any(|origin| origin == "*") {
+            CorsOrigins::Any
+        } else {
+            let origins = self.cors_origins
+                .iter()
+                .map(|origin| HeaderValue::from_str(origin).map_err(|_| format!("cors origin '{}' is invalid.", origin)))
+                .collect::<Result<_, _>>()?;
+            CorsOrigins::List(origins)
+        };
+
+        Backend::from_url(&self.database_url).map_err(|error| format!("database_url: {}", error))?;
+
+        if !self.solver_timeout_seconds.is_finite() || self.solver_timeout_seconds <= 0.0 {
+            return Err("solver_timeout_seconds must be positive.".into());
+        }
+
+        if self.max_concurrent_solves == 0 {
+            return Err("max_concurrent_solves must be at least 1.".into());
+        }
+
+        if self.scramble_pool_size == 0 {
+            return Err("scramble_pool_size must be at least 1.".into());
+        }
+
+        if self.race_idle_minutes == 0 {
+            return Err("race_idle_minutes must be at least 1.".into());
+        }
+
+        if self.rate_limit_per_minute == 0 || self.rate_limit_burst == 0 {
+            return Err("rate_limit_per_minute and rate_limit_burst must be at least 1.".into());
+        }
+
+        let reset_timezone = self.reset_timezone
+            .parse()
+            .map_err(|_| format!("reset_timezone '{}' is not a known timezone.", self.reset_timezone))?;
+
+        if self.scramble_svg_url.as_ref().is_some_and(|url| !url.contains("{moves}")) {
+            return Err("scramble_svg_url must contain {moves}.".into());
+        }
+
+        EnvFilter::try_new(&self.log_level)
+            .map_err(|error| format!("log_level '{}' is invalid: {}", self.log_level, error))?;
+
+        Ok(Settings {
+            database_url: self.database_url,
+            bind_address,
+            cors_origins,
+            solver_timeout_seconds: self.solver_timeout_seconds,
+            max_concurrent_solves: self.max_concurrent_solves,
+            scramble_pool_size: self.scramble_pool_size,
+            race_idle_minutes: self.race_idle_minutes,
+            rate_limit: RateLimit {
+                per_minute: self.rate_limit_per_minute,
+                burst: self.rate_limit_burst,
+            },
+            reset_timezone,
+            log_level: self.log_level,
+            daily_scramble_salt: self.daily_scramble_salt,
+            jwt_secret: self.jwt_secret,
+            require_daily_solution: self.require_daily_solution,
+            scramble_svg_url: self.scramble_svg_url,
+        })
+    }
+}
@@ -1 +0,0 @@
-pub const DB_URL: &str = "sqlite://rubiks.db";
\ No newline at end of file
@@ -0,0 +1,95 @@
+use chrono::{Datelike, NaiveDate};
+use sqlx::SqlitePool;
+use crate::domain::models::DailyRound;
+use crate::domain::results::{Attempt, Penalty};
+use crate::repositories::daily_round::DailyRoundsRepository;
+use crate::repositories::error::RepositoryError;
+use super::rows::DailyRoundRow;
+
+
+#[derive(Clone)]
+pub struct SqliteDailyRoundsRepository {
+    pool: SqlitePool
+}
+
+impl SqliteDailyRoundsRepository {
+    pub fn new(pool: SqlitePool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DailyRoundsRepository for SqliteDailyRoundsRepository {
+    async fn insert(&self, daily_round: DailyRound) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO daily_rounds (event, username, date, format, attempts, average, best) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7);")
+            .bind(daily_round.event)
+            .bind(daily_round.username)
+            .bind(daily_round.date.num_days_from_ce())
+            .bind(daily_round.format.to_string())
+            .bind(attempts_to_string(&daily_round.attempts))
+            .bind(daily_round.average)
+            .bind(daily_round.best)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_all_by_date(&self, event: &str, date: NaiveDate) -> Result<Vec<DailyRound>, RepositoryError> {
+        let result = sqlx::query_as::<_, DailyRoundRow>(
+            "SELECT event, username, date, format, attempts, average, best, \
+                CASE WHEN format IN ('single', 'bo3') THEN best ELSE average END AS primary_result, \
+                CASE WHEN format IN ('single', 'bo3') THEN average ELSE best END AS secondary_result \
+            FROM daily_rounds WHERE event = ?1 AND date = ?2 \
+            ORDER BY primary_result IS NULL, primary_result ASC, secondary_result IS NULL, secondary_result ASC, id ASC;"
+        )
+            .bind(event)
+            .bind(date.num_days_from_ce())
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|rounds| rounds.into_iter().map(DailyRoundRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+}
+
+/// Attempts are stored as `time:penalty` pairs separated by spaces.
+pub(crate) fn attempts_to_string(attempts: &[Attempt]) -> String {
+    attempts
+        .iter()
+        .map(|attempt| format!("{}:{}", attempt.time, attempt.penalty))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+pub(crate) fn attempts_from_string(attempts_str: &str) -> Vec<Attempt> {
+    attempts_str
+        .split_whitespace()
+        .map(|attempt_str| {
+            let (time, penalty) = attempt_str.split_once(':').expect("Db attempt should have a penalty");
+            Attempt {
+                time: time.parse().expect("Db attempt time should be valid"),
+                penalty: penalty.parse::<Penalty>().expect("Db attempt penalty should be valid"),
+            }
+        })
+        .collect()
+}
+
+impl From<DailyRoundRow> for DailyRound {
+    fn from(row: DailyRoundRow) -> DailyRound {
+        DailyRound {
+            event: row.event,
+            username: row.username,
+            date: NaiveDate::from_num_days_from_ce_opt(row.date).expect("Db date should be valid"),
+            format: row.format.parse().expect("Db format should be valid"),
+            attempts: attempts_from_string(&row.attempts),
+            average: row.average,
+            best: row.best,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use chrono::{Datelike, NaiveDate};
 use sqlx::SqlitePool;
-use crate::{domain::models::DailySolve, repositories::daily_solve::DailySolvesRepository};
-use super::rows::DailySolveRow;
+use crate::domain::leaderboards::{LeaderboardFilter, Ranking};
+use crate::{domain::models::{DailyRank, DailySolve, LeaderboardEntry, RollingAverage, UserStats}, repositories::daily_solve::DailySolvesRepository};
+use super::rows::{DailyRankRow, DailySolveRow, LeaderboardEntryRow, RollingAverageRow, UserStatsRow};
+use crate::repositories::error::RepositoryError;
 
 
 #[derive(Clone)]
@@ -19,45 +21,271 @@ impl SqliteDailySolvesRepository {
 
 #[async_trait::async_trait]
 impl DailySolvesRepository for SqliteDailySolvesRepository {
-    async fn insert(&self, daily_solve: DailySolve) -> Result<(), String> {
-        let result = sqlx::query("INSERT INTO daily_solves (username, date, time) VALUES (?1, ?2, ?3);")
+    async fn insert(&self, daily_solve: DailySolve) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO daily_solves (username, date, time, move_count, verified) VALUES (?1, ?2, ?3, ?4, ?5);")
             .bind(daily_solve.username)
             .bind(daily_solve.date.num_days_from_ce())
             .bind(daily_solve.time)
+            .bind(daily_solve.move_count)
+            .bind(daily_solve.verified)
             .execute(&self.pool)
             .await;
 
         result
             .map(|_| ())
-            .map_err(db_error_to_string)
+            .map_err(RepositoryError::from)
     }
 
-    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, String> {
-        let result = sqlx::query_as::<_, DailySolveRow>("SELECT username, date, time FROM daily_solves WHERE date = ?1 ORDER BY time ASC;")
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, RepositoryError> {
+        let result = sqlx::query_as::<_, DailySolveRow>("SELECT username, date, time, move_count, verified FROM daily_solves WHERE date = ?1 ORDER BY time ASC;")
             .bind(date.num_days_from_ce())
             .fetch_all(&self.pool)
             .await;
 
         result
             .map(|solves| solves.into_iter().map(DailySolveRow::into).collect())
-            .map_err(|error| db_error_to_string(error))
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_by_username(&self, username: &str, limit: u32, offset: u32) -> Result<Vec<DailySolve>, RepositoryError> {
+        let result = sqlx::query_as::<_, DailySolveRow>("SELECT username, date, time, move_count, verified FROM daily_solves WHERE username = ?1 ORDER BY date DESC LIMIT ?2 OFFSET ?3;")
+            .bind(username)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|solves| solves.into_iter().map(DailySolveRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn count_by_username(&self, username: &str) -> Result<i64, RepositoryError> {
+        let result = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM daily_solves WHERE username = ?1;")
+            .bind(username)
+            .fetch_one(&self.pool)
+            .await;
+
+        result.map_err(RepositoryError::from)
+    }
+
+    async fn fetch_user_stats(&self, username: &str, today: NaiveDate) -> Result<UserStats, RepositoryError> {
+        // Streaks are islands of consecutive dates, found as dates minus their row number being constant
+        let result = sqlx::query_as::<_, UserStatsRow>(
+            "WITH solves AS (SELECT date, time FROM daily_solves WHERE username = ?1), \
+            ao5 AS (\
+                SELECT CASE WHEN COUNT(*) OVER w = 5 THEN (SUM(time) OVER w - MIN(time) OVER w - MAX(time) OVER w) / 3.0 END AS average \
+                FROM solves WINDOW w AS (ORDER BY date ROWS 4 PRECEDING)\
+            ), \
+            ao12 AS (\
+                SELECT CASE WHEN COUNT(*) OVER w = 12 THEN (SUM(time) OVER w - MIN(time) OVER w - MAX(time) OVER w) / 10.0 END AS average \
+                FROM solves WINDOW w AS (ORDER BY date ROWS 11 PRECEDING)\
+            ), \
+            streaks AS (\
+                SELECT COUNT(*) AS length, MAX(date) AS last_date \
+                FROM (SELECT date, date - ROW_NUMBER() OVER (ORDER BY date) AS island FROM solves) \
+                GROUP BY island\
+            ) \
+            SELECT \
+                (SELECT COUNT(*) FROM solves) AS solve_count, \
+                (SELECT MIN(time) FROM solves) AS best_single, \
+                (SELECT CAST(ROUND(AVG(time)) AS INTEGER) FROM solves) AS mean, \
+                (SELECT CAST(ROUND(MIN(average)) AS INTEGER) FROM ao5) AS best_ao5, \
+                (SELECT CAST(ROUND(MIN(average)) AS INTEGER) FROM ao12) AS best_ao12, \
+                (SELECT COALESCE(MAX(length), 0) FROM streaks WHERE last_date >= ?2 - 1) AS current_streak, \
+                (SELECT COALESCE(MAX(length), 0) FROM streaks) AS longest_streak;"
+        )
+            .bind(username)
+            .bind(today.num_days_from_ce())
+            .fetch_one(&self.pool)
+            .await;
+
+        result
+            .map(UserStatsRow::into)
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_rank_history(&self, username: &str, limit: u32) -> Result<Vec<DailyRank>, RepositoryError> {
+        let result = sqlx::query_as::<_, DailyRankRow>(
+            "SELECT solve.date AS date, \
+                (SELECT COUNT(*) FROM daily_solves AS other WHERE other.date = solve.date AND other.time < solve.time) + 1 AS rank, \
+                (SELECT COUNT(*) FROM daily_solves AS other WHERE other.date = solve.date) AS participants \
+            FROM daily_solves AS solve \
+            WHERE solve.username = ?1 \
+            ORDER BY solve.date DESC \
+            LIMIT ?2;"
+        )
+            .bind(username)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|ranks| ranks.into_iter().map(DailyRankRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_rolling_averages(&self, username: &str, size: u32, limit: u32) -> Result<Vec<RollingAverage>, RepositoryError> {
+        // Window frames can't be bound, `size` is a number so formatting it is safe
+        let result = sqlx::query_as::<_, RollingAverageRow>(&format!(
+            "SELECT date, average FROM (\
+                SELECT date, \
+                    CAST(ROUND((SUM(time) OVER w - MIN(time) OVER w - MAX(time) OVER w) / ({size} - 2.0)) AS INTEGER) AS average, \
+                    COUNT(*) OVER w AS count \
+                FROM daily_solves \
+                WHERE username = ?1 \
+                WINDOW w AS (ORDER BY date ROWS {preceding} PRECEDING) \
+                ORDER BY date DESC \
+                LIMIT ?2\
+            ) \
+            WHERE count = {size} \
+            ORDER BY date ASC;",
+            size = size,
+            preceding = size - 1,
+        ))
+            .bind(username)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|averages| averages.into_iter().map(RollingAverageRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_leaderboard(&self, filter: &LeaderboardFilter, limit: u32, offset: u32) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        let query = leaderboard_query(filter, "SELECT * FROM ranked ORDER BY rank ASC, username ASC LIMIT ?6 OFFSET ?7;");
+        let result = bind_leaderboard_filter(sqlx::query_as::<_, LeaderboardEntryRow>(&query), filter)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|entries| entries.into_iter().map(LeaderboardEntryRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn count_leaderboard(&self, filter: &LeaderboardFilter) -> Result<i64, RepositoryError> {
+        let query = leaderboard_query(filter, "SELECT COUNT(*) FROM ranked;");
+        let result = bind_leaderboard_filter(sqlx::query_as::<_, (i64,)>(&query), filter)
+            .fetch_one(&self.pool)
+            .await;
+
+        result
+            .map(|(count,)| count)
+            .map_err(RepositoryError::from)
     }
 }
 
-fn db_error_to_string(error: sqlx::Error) -> String {
-     error
-            .as_database_error()
-            .expect("Error should be a database error.")
-            .message()
-            .to_string()
+/// Ranks users on their solves within the filter, then runs `select` on the `ranked` table.
+/// Parameters 1 to 5 are bound by `bind_leaderboard_filter`.
+fn leaderboard_query(filter: &LeaderboardFilter, select: &str) -> String {
+    let result = match filter.ranking {
+        Ranking::Single => "best_single",
+        Ranking::Average => "best_average",
+    };
+
+    // Window frames can't be bound, `average_of` is a number so formatting it is safe
+    format!(
+        "WITH period_solves AS (\
+            SELECT daily_solves.username AS username, date, time FROM daily_solves \
+            LEFT JOIN users ON users.username = daily_solves.username \
+            WHERE date >= ?1 AND date <= ?2 \
+                AND (?3 IS NULL OR users.country = ?3) \
+                AND (?4 IS NULL OR users.club = ?4)\
+        ), \
+        averages AS (\
+            SELECT username, CAST(ROUND(MIN(average)) AS INTEGER) AS best_average FROM (\
+                SELECT username, \
+                    CASE WHEN COUNT(*) OVER w = {size} THEN (SUM(time) OVER w - MIN(time) OVER w - MAX(time) OVER w) / ({size} - 2.0) END AS average \
+                FROM period_solves \
+                WINDOW w AS (PARTITION BY username ORDER BY date ROWS {preceding} PRECEDING)\
+            ) \
+            GROUP BY username\
+        ), \
+        results AS (\
+            SELECT username, COUNT(*) AS solve_count, MIN(time) AS best_single FROM period_solves \
+            GROUP BY username \
+            HAVING COUNT(*) >= ?5\
+        ), \
+        ranked AS (\
+            SELECT RANK() OVER (ORDER BY {result} ASC) AS rank, results.username AS username, solve_count, best_single, best_average \
+            FROM results JOIN averages ON averages.username = results.username \
+            WHERE {result} IS NOT NULL\
+        ) \
+        {select}",
+        size = filter.average_of,
+        preceding = filter.average_of - 1,
+        result = result,
+        select = select,
+    )
 }
 
-impl Into<DailySolve> for DailySolveRow {
-    fn into(self) -> DailySolve {
+fn bind_leaderboard_filter<'q, O>(
+    query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+    filter: &LeaderboardFilter
+) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+    query
+        .bind(filter.from.map_or(0, |from| from.num_days_from_ce()))
+        .bind(filter.to.num_days_from_ce())
+        .bind(filter.country.clone())
+        .bind(filter.club.clone())
+        .bind(filter.min_solves)
+}
+
+impl From<DailySolveRow> for DailySolve {
+    fn from(row: DailySolveRow) -> DailySolve {
         DailySolve {
-            username: self.username,
-            date: NaiveDate::from_num_days_from_ce_opt(self.date).expect("Db date should be valid"),
-            time: self.time
+            username: row.username,
+            date: NaiveDate::from_num_days_from_ce_opt(row.date).expect("Db date should be valid"),
+            time: row.time,
+            move_count: row.move_count,
+            verified: row.verified,
         }
     }
-}
\ No newline at end of file
+}
+impl From<UserStatsRow> for UserStats {
+    fn from(row: UserStatsRow) -> UserStats {
+        UserStats {
+            solve_count: row.solve_count,
+            best_single: row.best_single,
+            mean: row.mean,
+            best_ao5: row.best_ao5,
+            best_ao12: row.best_ao12,
+            current_streak: row.current_streak,
+            longest_streak: row.longest_streak,
+        }
+    }
+}
+
+impl From<DailyRankRow> for DailyRank {
+    fn from(row: DailyRankRow) -> DailyRank {
+        DailyRank {
+            date: NaiveDate::from_num_days_from_ce_opt(row.date).expect("Db date should be valid"),
+            rank: row.rank,
+            participants: row.participants,
+        }
+    }
+}
+
+impl From<RollingAverageRow> for RollingAverage {
+    fn from(row: RollingAverageRow) -> RollingAverage {
+        RollingAverage {
+            date: NaiveDate::from_num_days_from_ce_opt(row.date).expect("Db date should be valid"),
+            average: row.average,
+        }
+    }
+}
+
+impl From<LeaderboardEntryRow> for LeaderboardEntry {
+    fn from(row: LeaderboardEntryRow) -> LeaderboardEntry {
+        LeaderboardEntry {
+            rank: row.rank,
+            username: row.username,
+            solve_count: row.solve_count,
+            best_single: row.best_single,
+            best_average: row.best_average,
+        }
+    }
+}
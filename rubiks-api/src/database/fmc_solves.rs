@@ -0,0 +1,58 @@
+use chrono::{Datelike, NaiveDate};
+use sqlx::SqlitePool;
+use crate::{domain::models::FmcSolve, repositories::fmc_solve::FmcSolvesRepository};
+use crate::repositories::error::RepositoryError;
+use super::rows::FmcSolveRow;
+
+
+#[derive(Clone)]
+pub struct SqliteFmcSolvesRepository {
+    pool: SqlitePool
+}
+
+impl SqliteFmcSolvesRepository {
+    pub fn new(pool: SqlitePool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl FmcSolvesRepository for SqliteFmcSolvesRepository {
+    async fn insert(&self, fmc_solve: FmcSolve) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO daily_fmc_solves (username, date, solution, move_count) VALUES (?1, ?2, ?3, ?4);")
+            .bind(fmc_solve.username)
+            .bind(fmc_solve.date.num_days_from_ce())
+            .bind(fmc_solve.solution)
+            .bind(fmc_solve.move_count)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<FmcSolve>, RepositoryError> {
+        let result = sqlx::query_as::<_, FmcSolveRow>("SELECT username, date, solution, move_count FROM daily_fmc_solves WHERE date = ?1 ORDER BY move_count ASC, id ASC;")
+            .bind(date.num_days_from_ce())
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|solves| solves.into_iter().map(FmcSolveRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl From<FmcSolveRow> for FmcSolve {
+    fn from(row: FmcSolveRow) -> FmcSolve {
+        FmcSolve {
+            username: row.username,
+            date: NaiveDate::from_num_days_from_ce_opt(row.date).expect("Db date should be valid"),
+            solution: row.solution,
+            move_count: row.move_count,
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use chrono::{Datelike, NaiveDate};
+use crate::domain::leaderboards::{LeaderboardFilter, Ranking};
+use crate::{domain::models::{DailyRank, DailySolve, LeaderboardEntry, RollingAverage, UserStats}, repositories::daily_solve::DailySolvesRepository};
+use crate::repositories::error::RepositoryError;
+
+
+/// Daily solves kept in memory in insertion order, for tests and trying the API without a database.
+/// Clones share the same solves.
+#[derive(Clone, Default)]
+pub struct InMemoryDailySolvesRepository {
+    solves: Arc<Mutex<Vec<DailySolve>>>
+}
+
+impl InMemoryDailySolvesRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn solves(&self) -> MutexGuard<'_, Vec<DailySolve>> {
+        self.solves.lock().expect("Solves lock should not be poisoned")
+    }
+
+    /// Solves of `username` from oldest to most recent.
+    fn user_solves(&self, username: &str) -> Vec<DailySolve> {
+        let mut solves: Vec<DailySolve> = self.solves()
+            .iter()
+            .filter(|solve| solve.username == username)
+            .cloned()
+            .collect();
+        solves.sort_by_key(|solve| solve.date);
+        solves
+    }
+}
+
+#[async_trait::async_trait]
+impl DailySolvesRepository for InMemoryDailySolvesRepository {
+    async fn insert(&self, daily_solve: DailySolve) -> Result<(), RepositoryError> {
+        let mut solves = self.solves();
+        if solves.iter().any(|solve| solve.username == daily_solve.username && solve.date == daily_solve.date) {
+            return Err(RepositoryError::Conflict("UNIQUE constraint failed: daily_solves.username, daily_solves.date".into()));
+        }
+
+        solves.push(daily_solve);
+        Ok(())
+    }
+
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, RepositoryError> {
+        let mut solves: Vec<DailySolve> = self.solves()
+            .iter()
+            .filter(|solve| solve.date == date)
+            .cloned()
+            .collect();
+        solves.sort_by_key(|solve| solve.time);
+        Ok(solves)
+    }
+
+    async fn fetch_by_username(&self, username: &str, limit: u32, offset: u32) -> Result<Vec<DailySolve>, RepositoryError> {
+        Ok(self.user_solves(username)
+            .into_iter()
+            .rev()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count_by_username(&self, username: &str) -> Result<i64, RepositoryError> {
+        Ok(self.user_solves(username).len() as i64)
+    }
+
+    async fn fetch_user_stats(&self, username: &str, today: NaiveDate) -> Result<UserStats, RepositoryError> {
+        let solves = self.user_solves(username);
+        let times: Vec<i32> = solves.iter().map(|solve| solve.time).collect();
+
+        // Lengths of the runs of consecutive dates, with the last date of each
+        let mut streaks: Vec<(i64, i32)> = Vec::new();
+        for day in solves.iter().map(|solve| solve.date.num_days_from_ce()) {
+            match streaks.last_mut() {
+                Some((length, last_day)) if *last_day == day - 1 => {
+                    *length += 1;
+                    *last_day = day;
+                }
+                _ => streaks.push((1, day)),
+            }
+        }
+        let yesterday = today.num_days_from_ce() - 1;
+
+        Ok(UserStats {
+            solve_count: times.len() as i64,
+            best_single: times.iter().copied().min(),
+            mean: (!times.is_empty()).then(|| round(times.iter().map(|&time| f64::from(time)).sum::<f64>() / times.len() as f64)),
+            best_ao5: best_trimmed_mean(&times, 5),
+            best_ao12: best_trimmed_mean(&times, 12),
+            current_streak: streaks.iter().filter(|(_, last_day)| *last_day >= yesterday).map(|(length, _)| *length).max().unwrap_or(0),
+            longest_streak: streaks.iter().map(|(length, _)| *length).max().unwrap_or(0),
+        })
+    }
+
+    async fn fetch_rank_history(&self, username: &str, limit: u32) -> Result<Vec<DailyRank>, RepositoryError> {
+        let solves = self.solves();
+        let mut user_solves: Vec<&DailySolve> = solves.iter().filter(|solve| solve.username == username).collect();
+        user_solves.sort_by_key(|solve| std::cmp::Reverse(solve.date));
+
+        Ok(user_solves
+            .into_iter()
+            .take(limit as usize)
+            .map(|user_solve| {
+                let same_day = solves.iter().filter(|solve| solve.date == user_solve.date);
+                DailyRank {
+                    date: user_solve.date,
+                    rank: same_day.clone().filter(|solve| solve.time < user_solve.time).count() as i64 + 1,
+                    participants: same_day.count() as i64,
+                }
+            })
+            .collect())
+    }
+
+    async fn fetch_rolling_averages(&self, username: &str, size: u32, limit: u32) -> Result<Vec<RollingAverage>, RepositoryError> {
+        let solves = self.user_solves(username);
+        let size = size as usize;
+        let first = solves.len().saturating_sub(limit as usize).max(size - 1);
+
+        Ok((first..solves.len())
+            .map(|index| {
+                let times: Vec<i32> = solves[index + 1 - size..=index].iter().map(|solve| solve.time).collect();
+                RollingAverage {
+                    date: solves[index].date,
+                    average: round(trimmed_mean(&times)),
+                }
+            })
+            .collect())
+    }
+
+    async fn fetch_leaderboard(&self, filter: &LeaderboardFilter, limit: u32, offset: u32) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        Ok(self.leaderboard(filter)?
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count_leaderboard(&self, filter: &LeaderboardFilter) -> Result<i64, RepositoryError> {
+        Ok(self.leaderboard(filter)?.len() as i64)
+    }
+}
+
+impl InMemoryDailySolvesRepository {
+    /// Every ranked user, ties ordered by username like the SQL leaderboards.
+    fn leaderboard(&self, filter: &LeaderboardFilter) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        // Profiles are stored by the users repository, which this one can't join
+        if filter.country.is_some() || filter.club.is_some() {
+            return Err(RepositoryError::Database("country and club filters are not supported in memory.".into()));
+        }
+
+        let mut user_times: BTreeMap<String, Vec<(NaiveDate, i32)>> = BTreeMap::new();
+        for solve in self.solves().iter() {
+            if filter.from.is_none_or(|from| solve.date >= from) && solve.date <= filter.to {
+                user_times.entry(solve.username.clone()).or_default().push((solve.date, solve.time));
+            }
+        }
+
+        let mut ranked: Vec<(i32, LeaderboardEntry)> = user_times
+            .into_iter()
+            .filter(|(_, times)| times.len() >= filter.min_solves as usize)
+            .filter_map(|(username, mut times)| {
+                times.sort();
+                let times: Vec<i32> = times.into_iter().map(|(_, time)| time).collect();
+                let best_single = *times.iter().min().expect("Ranked users have solves");
+                let best_average = best_trimmed_mean(&times, filter.average_of as usize);
+                let result = match filter.ranking {
+                    Ranking::Single => Some(best_single),
+                    Ranking::Average => best_average,
+                };
+                result.map(|result| (result, LeaderboardEntry {
+                    rank: 0,
+                    username,
+                    solve_count: times.len() as i64,
+                    best_single,
+                    best_average,
+                }))
+            })
+            .collect();
+        ranked.sort_by(|(a, a_entry), (b, b_entry)| a.cmp(b).then_with(|| a_entry.username.cmp(&b_entry.username)));
+
+        let results: Vec<i32> = ranked.iter().map(|(result, _)| *result).collect();
+        let entries = ranked
+            .into_iter()
+            .map(|(result, entry)| LeaderboardEntry {
+                rank: results.partition_point(|&other| other < result) as i64 + 1,
+                ..entry
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+/// Mean without the best and worst times.
+fn trimmed_mean(times: &[i32]) -> f64 {
+    let sum: f64 = times.iter().map(|&time| f64::from(time)).sum();
+    let best = times.iter().copied().min().map_or(0.0, f64::from);
+    let worst = times.iter().copied().max().map_or(0.0, f64::from);
+    (sum - best - worst) / (times.len() as f64 - 2.0)
+}
+
+/// Best trimmed mean of `size` consecutive times, `None` if there are fewer times.
+fn best_trimmed_mean(times: &[i32], size: usize) -> Option<i32> {
+    times
+        .windows(size)
+        .map(trimmed_mean)
+        .min_by(f64::total_cmp)
+        .map(round)
+}
+
+/// Rounds half away from zero, like SQL `ROUND`.
+fn round(value: f64) -> i32 {
+    value.round() as i32
+}
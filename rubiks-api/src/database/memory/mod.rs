@@ -0,0 +1 @@
+pub mod daily_solves;
@@ -1,35 +1,121 @@
-use sqlx::{Sqlite, SqlitePool};
-use sqlx::migrate::MigrateDatabase;
+use sqlx::migrate::{AppliedMigration, Migrate, MigrateDatabase, Migrator};
+use sqlx::Sqlite;
+#[cfg(feature = "postgres")]
+use sqlx::Postgres;
+use super::pool::{Backend, DatabasePool};
 
-use crate::database::constants::DB_URL;
+/// SQLite migrations of the `migrations/sqlite` directory, embedded at compile time.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
 
-pub async fn ensure_db() {
-    if !Sqlite::database_exists(DB_URL).await.unwrap_or(false) {
-        println!("Creating database {}", DB_URL);
-        match Sqlite::create_database(DB_URL).await {
-            Ok(_) => println!("Create db success"),
-            Err(error) => panic!("error: {}", error),
-        }
-    } else {
-        println!("Database already exists");
+/// Postgres migrations of the `migrations/postgres` directory, kept in step with the SQLite ones.
+#[cfg(feature = "postgres")]
+pub static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// State of an embedded migration in a database.
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    /// The migration was applied but its file changed since
+    pub checksum_mismatch: bool,
+}
+
+pub fn migrator(backend: Backend) -> &'static Migrator {
+    match backend {
+        Backend::Sqlite => &MIGRATOR,
+        #[cfg(feature = "postgres")]
+        Backend::Postgres => &POSTGRES_MIGRATOR,
     }
+}
+
+pub async fn database_exists(url: &str) -> Result<bool, String> {
+    let exists = match Backend::from_url(url)? {
+        Backend::Sqlite => Sqlite::database_exists(url).await,
+        #[cfg(feature = "postgres")]
+        Backend::Postgres => Postgres::database_exists(url).await,
+    };
+    exists.map_err(|error| error.to_string())
+}
+
+/// Creates the database at `url` if it does not exist, returns whether it was created.
+pub async fn create_db_if_missing(url: &str) -> Result<bool, String> {
+    if database_exists(url).await? {
+        return Ok(false);
+    }
+
+    let created = match Backend::from_url(url)? {
+        Backend::Sqlite => Sqlite::create_database(url).await,
+        #[cfg(feature = "postgres")]
+        Backend::Postgres => Postgres::create_database(url).await,
+    };
+    created
+        .map(|_| true)
+        .map_err(|error| error.to_string())
+}
+
+/// Applies the pending migrations, each in its own transaction.
+pub async fn run_migrations(pool: &DatabasePool) -> Result<(), String> {
+    let result = match pool {
+        DatabasePool::Sqlite(pool) => MIGRATOR.run(pool).await,
+        #[cfg(feature = "postgres")]
+        DatabasePool::Postgres(pool) => POSTGRES_MIGRATOR.run(pool).await,
+    };
+    result.map_err(|error| error.to_string())
+}
+
+/// Every embedded migration, oldest first, with whether it was applied.
+/// Leaves the database untouched, unlike `run_migrations`.
+pub async fn migration_status(pool: &DatabasePool) -> Result<Vec<MigrationStatus>, String> {
+    let applied = match pool {
+        DatabasePool::Sqlite(pool) => {
+            let has_migrations_table = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations';")
+                .fetch_one(pool)
+                .await
+                .map_err(|error| error.to_string())? > 0;
+
+            if has_migrations_table {
+                let mut connection = pool.acquire().await.map_err(|error| error.to_string())?;
+                connection.list_applied_migrations().await.map_err(|error| error.to_string())?
+            } else {
+                Vec::new()
+            }
+        }
+        #[cfg(feature = "postgres")]
+        DatabasePool::Postgres(pool) => {
+            let has_migrations_table = sqlx::query_scalar::<_, bool>("SELECT to_regclass('_sqlx_migrations') IS NOT NULL;")
+                .fetch_one(pool)
+                .await
+                .map_err(|error| error.to_string())?;
+
+            if has_migrations_table {
+                let mut connection = pool.acquire().await.map_err(|error| error.to_string())?;
+                connection.list_applied_migrations().await.map_err(|error| error.to_string())?
+            } else {
+                Vec::new()
+            }
+        }
+    };
+
+    Ok(statuses(migrator(pool.backend()), &applied))
+}
+
+/// Every embedded migration of `backend` as pending, for a database that does not exist yet.
+pub fn pending_migrations(backend: Backend) -> Vec<MigrationStatus> {
+    statuses(migrator(backend), &[])
+}
 
-    let db = SqlitePool::connect(DB_URL)
-        .await
-        .expect("Cannot connect to database for table creation.");
-    
-    let result = sqlx::query(
-        "CREATE TABLE IF NOT EXISTS daily_solves (\
-            id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\
-            username VARCHAR(250) NOT NULL,\
-            date INTEGER NOT NULL,\
-            time INTEGER NOT NULL,\
-            CONSTRAINT one_solve_per_day_per_user UNIQUE (username, date)\
-        );"
-    )
-        .execute(&db)
-        .await
-        .expect("Could not create daily_solves table.");
-
-    println!("Create daily_solves table result: {:?}", result);
+fn statuses(migrator: &Migrator, applied: &[AppliedMigration]) -> Vec<MigrationStatus> {
+    migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| {
+            let applied_migration = applied.iter().find(|applied| applied.version == migration.version);
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                applied: applied_migration.is_some(),
+                checksum_mismatch: applied_migration.is_some_and(|applied| applied.checksum != migration.checksum),
+            }
+        })
+        .collect()
 }
@@ -1,5 +1,28 @@
 pub mod migration;
 pub mod rows;
-pub mod constants;
+pub mod daily_rounds;
+pub mod daily_scrambles;
 pub mod daily_solves;
-pub mod pool;
\ No newline at end of file
+pub mod events;
+pub mod fmc_solves;
+pub mod memory;
+pub mod pool;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod races;
+pub mod users;
+
+use crate::repositories::error::RepositoryError;
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(error: sqlx::Error) -> Self {
+        match error {
+            sqlx::Error::Database(error) if error.is_unique_violation() => RepositoryError::Conflict(error.message().to_string()),
+            sqlx::Error::Database(error) => RepositoryError::Database(error.message().to_string()),
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed => {
+                RepositoryError::Unavailable(error.to_string())
+            }
+            error => RepositoryError::Database(error.to_string()),
+        }
+    }
+}
@@ -1,7 +1,111 @@
+use std::sync::Arc;
 use sqlx::SqlitePool;
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+use crate::repositories::daily_round::DailyRoundsRepository;
+use crate::repositories::daily_scramble::DailyScramblesRepository;
+use crate::repositories::daily_solve::DailySolvesRepository;
+use crate::repositories::event::EventsRepository;
+use crate::repositories::fmc_solve::FmcSolvesRepository;
+use crate::repositories::race::RacesRepository;
+use crate::repositories::user::UsersRepository;
+use super::daily_rounds::SqliteDailyRoundsRepository;
+use super::daily_scrambles::SqliteDailyScramblesRepository;
+use super::daily_solves::SqliteDailySolvesRepository;
+use super::events::SqliteEventsRepository;
+use super::fmc_solves::SqliteFmcSolvesRepository;
+use super::races::SqliteRacesRepository;
+use super::users::SqliteUsersRepository;
 
-use crate::database::constants::DB_URL;
+/// Database engine behind a database URL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    #[cfg(feature = "postgres")]
+    Postgres,
+}
 
-pub fn build_pool() -> SqlitePool {
-    SqlitePool::connect_lazy(DB_URL).expect("Cannot create lazy pool to SQLite database.")
-}
\ No newline at end of file
+impl Backend {
+    pub fn from_url(database_url: &str) -> Result<Backend, String> {
+        let scheme = database_url.split_once(':').map_or("", |(scheme, _)| scheme);
+        match scheme {
+            "sqlite" => Ok(Backend::Sqlite),
+            #[cfg(feature = "postgres")]
+            "postgres" | "postgresql" => Ok(Backend::Postgres),
+            #[cfg(not(feature = "postgres"))]
+            "postgres" | "postgresql" => Err("Postgres support is not built in, rebuild with the `postgres` feature".to_string()),
+            _ => Err(format!("Unsupported database URL '{}', expected sqlite:// or postgres://", database_url)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DatabasePool {
+    Sqlite(SqlitePool),
+    #[cfg(feature = "postgres")]
+    Postgres(PgPool),
+}
+
+impl DatabasePool {
+    pub fn backend(&self) -> Backend {
+        match self {
+            DatabasePool::Sqlite(_) => Backend::Sqlite,
+            #[cfg(feature = "postgres")]
+            DatabasePool::Postgres(_) => Backend::Postgres,
+        }
+    }
+}
+
+/// Connections are opened on first use.
+pub fn build_pool(database_url: &str) -> Result<DatabasePool, String> {
+    match Backend::from_url(database_url)? {
+        Backend::Sqlite => SqlitePool::connect_lazy(database_url)
+            .map(DatabasePool::Sqlite)
+            .map_err(|error| error.to_string()),
+        #[cfg(feature = "postgres")]
+        Backend::Postgres => PgPool::connect_lazy(database_url)
+            .map(DatabasePool::Postgres)
+            .map_err(|error| error.to_string()),
+    }
+}
+
+/// Every repository, backed by the same pool.
+#[derive(Clone)]
+pub struct Repositories {
+    pub daily_scrambles: Arc<dyn DailyScramblesRepository>,
+    pub daily_solves: Arc<dyn DailySolvesRepository>,
+    pub daily_rounds: Arc<dyn DailyRoundsRepository>,
+    pub events: Arc<dyn EventsRepository>,
+    pub fmc_solves: Arc<dyn FmcSolvesRepository>,
+    pub races: Arc<dyn RacesRepository>,
+    pub users: Arc<dyn UsersRepository>,
+}
+
+impl Repositories {
+    pub fn new(pool: &DatabasePool) -> Self {
+        match pool {
+            DatabasePool::Sqlite(pool) => Self {
+                daily_scrambles: Arc::new(SqliteDailyScramblesRepository::new(pool.clone())),
+                daily_solves: Arc::new(SqliteDailySolvesRepository::new(pool.clone())),
+                daily_rounds: Arc::new(SqliteDailyRoundsRepository::new(pool.clone())),
+                events: Arc::new(SqliteEventsRepository::new(pool.clone())),
+                fmc_solves: Arc::new(SqliteFmcSolvesRepository::new(pool.clone())),
+                races: Arc::new(SqliteRacesRepository::new(pool.clone())),
+                users: Arc::new(SqliteUsersRepository::new(pool.clone())),
+            },
+            #[cfg(feature = "postgres")]
+            DatabasePool::Postgres(pool) => {
+                use super::postgres::*;
+                Self {
+                    daily_scrambles: Arc::new(daily_scrambles::PgDailyScramblesRepository::new(pool.clone())),
+                    daily_solves: Arc::new(daily_solves::PgDailySolvesRepository::new(pool.clone())),
+                    daily_rounds: Arc::new(daily_rounds::PgDailyRoundsRepository::new(pool.clone())),
+                    events: Arc::new(events::PgEventsRepository::new(pool.clone())),
+                    fmc_solves: Arc::new(fmc_solves::PgFmcSolvesRepository::new(pool.clone())),
+                    races: Arc::new(races::PgRacesRepository::new(pool.clone())),
+                    users: Arc::new(users::PgUsersRepository::new(pool.clone())),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{Datelike, NaiveDate};
+use sqlx::PgPool;
+use crate::domain::models::DailyRound;
+use crate::repositories::daily_round::DailyRoundsRepository;
+use crate::database::daily_rounds::attempts_to_string;
+use crate::repositories::error::RepositoryError;
+use crate::database::rows::DailyRoundRow;
+
+
+#[derive(Clone)]
+pub struct PgDailyRoundsRepository {
+    pool: PgPool
+}
+
+impl PgDailyRoundsRepository {
+    pub fn new(pool: PgPool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DailyRoundsRepository for PgDailyRoundsRepository {
+    async fn insert(&self, daily_round: DailyRound) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO daily_rounds (event, username, date, format, attempts, average, best) VALUES ($1, $2, $3, $4, $5, $6, $7);")
+            .bind(daily_round.event)
+            .bind(daily_round.username)
+            .bind(daily_round.date.num_days_from_ce())
+            .bind(daily_round.format.to_string())
+            .bind(attempts_to_string(&daily_round.attempts))
+            .bind(daily_round.average)
+            .bind(daily_round.best)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_all_by_date(&self, event: &str, date: NaiveDate) -> Result<Vec<DailyRound>, RepositoryError> {
+        // Postgres only orders by output columns when they are named alone, and sorts NULLs last by default
+        let result = sqlx::query_as::<_, DailyRoundRow>(
+            "SELECT event, username, date, format, attempts, average, best, \
+                CASE WHEN format IN ('single', 'bo3') THEN best ELSE average END AS primary_result, \
+                CASE WHEN format IN ('single', 'bo3') THEN average ELSE best END AS secondary_result \
+            FROM daily_rounds WHERE event = $1 AND date = $2 \
+            ORDER BY primary_result ASC, secondary_result ASC, id ASC;"
+        )
+            .bind(event)
+            .bind(date.num_days_from_ce())
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|rounds| rounds.into_iter().map(DailyRoundRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+}
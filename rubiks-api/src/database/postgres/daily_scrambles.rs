@@ -0,0 +1,121 @@
+use chrono::{Datelike, NaiveDate};
+use sqlx::PgPool;
+use crate::{domain::models::{DailyScramble, DailySummary}, repositories::daily_scramble::DailyScramblesRepository};
+use crate::repositories::error::RepositoryError;
+use crate::database::rows::{DailyScrambleRow, DailySummaryRow};
+
+
+#[derive(Clone)]
+pub struct PgDailyScramblesRepository {
+    pool: PgPool
+}
+
+impl PgDailyScramblesRepository {
+    pub fn new(pool: PgPool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl DailyScramblesRepository for PgDailyScramblesRepository {
+    async fn insert_if_absent(&self, daily_scramble: DailyScramble) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO daily_scrambles (date, scramble, version) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING;")
+            .bind(daily_scramble.date.num_days_from_ce())
+            .bind(daily_scramble.scramble)
+            .bind(daily_scramble.version)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_by_date(&self, date: NaiveDate) -> Result<Option<DailyScramble>, RepositoryError> {
+        let result = sqlx::query_as::<_, DailyScrambleRow>("SELECT date, scramble, version, fmc_best_length FROM daily_scrambles WHERE date = $1;")
+            .bind(date.num_days_from_ce())
+            .fetch_optional(&self.pool)
+            .await;
+
+        result
+            .map(|row| row.map(DailyScrambleRow::into))
+            .map_err(RepositoryError::from)
+    }
+
+    async fn update_fmc_best_length(&self, date: NaiveDate, length: i32) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE daily_scrambles SET fmc_best_length = $1 WHERE date = $2;")
+            .bind(length)
+            .bind(date.num_days_from_ce())
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_summaries_before(&self, date: NaiveDate, limit: u32, offset: u32) -> Result<Vec<DailySummary>, RepositoryError> {
+        let result = sqlx::query_as::<_, DailySummaryRow>(
+            "SELECT daily_scrambles.date AS date, \
+                (SELECT COUNT(*) FROM daily_solves WHERE daily_solves.date = daily_scrambles.date) AS participants, \
+                winner.username AS winner_username, \
+                winner.time AS winner_time, \
+                winner.move_count AS winner_move_count, \
+                winner.verified AS winner_verified \
+            FROM daily_scrambles \
+            LEFT JOIN daily_solves AS winner ON winner.id = (\
+                SELECT id FROM daily_solves WHERE daily_solves.date = daily_scrambles.date ORDER BY time ASC, id ASC LIMIT 1\
+            ) \
+            WHERE daily_scrambles.date < $1 \
+            ORDER BY daily_scrambles.date DESC \
+            LIMIT $2 OFFSET $3;"
+        )
+            .bind(date.num_days_from_ce())
+            .bind(i64::from(limit))
+            .bind(i64::from(offset))
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|rows| rows.into_iter().map(DailySummaryRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn count_before(&self, date: NaiveDate) -> Result<i64, RepositoryError> {
+        let result = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM daily_scrambles WHERE date < $1;")
+            .bind(date.num_days_from_ce())
+            .fetch_one(&self.pool)
+            .await;
+
+        result.map_err(RepositoryError::from)
+    }
+
+    async fn insert_round_scrambles_if_absent(&self, event: &str, date: NaiveDate, scrambles: Vec<String>, version: i32) -> Result<(), RepositoryError> {
+        let mut transaction = self.pool.begin().await.map_err(RepositoryError::from)?;
+        for (attempt, scramble) in scrambles.into_iter().enumerate() {
+            sqlx::query("INSERT INTO daily_round_scrambles (event, date, attempt, scramble, version) VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING;")
+                .bind(event)
+                .bind(date.num_days_from_ce())
+                .bind(attempt as i32)
+                .bind(scramble)
+                .bind(version)
+                .execute(&mut *transaction)
+                .await
+                .map_err(RepositoryError::from)?;
+        }
+
+        transaction.commit().await.map_err(RepositoryError::from)
+    }
+
+    async fn fetch_round_scrambles(&self, event: &str, date: NaiveDate) -> Result<Vec<String>, RepositoryError> {
+        let result = sqlx::query_scalar::<_, String>("SELECT scramble FROM daily_round_scrambles WHERE event = $1 AND date = $2 ORDER BY attempt ASC;")
+            .bind(event)
+            .bind(date.num_days_from_ce())
+            .fetch_all(&self.pool)
+            .await;
+
+        result.map_err(RepositoryError::from)
+    }
+}
@@ -0,0 +1,42 @@
+use sqlx::PgPool;
+use crate::{domain::events::Event, repositories::event::EventsRepository};
+use crate::repositories::error::RepositoryError;
+use crate::database::rows::EventRow;
+
+
+#[derive(Clone)]
+pub struct PgEventsRepository {
+    pool: PgPool
+}
+
+impl PgEventsRepository {
+    pub fn new(pool: PgPool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventsRepository for PgEventsRepository {
+    async fn fetch_all(&self) -> Result<Vec<Event>, RepositoryError> {
+        let result = sqlx::query_as::<_, EventRow>("SELECT id, name, format, scramble_kind FROM events ORDER BY position ASC;")
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|events| events.into_iter().map(EventRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_by_id(&self, id: &str) -> Result<Option<Event>, RepositoryError> {
+        let result = sqlx::query_as::<_, EventRow>("SELECT id, name, format, scramble_kind FROM events WHERE id = $1;")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await;
+
+        result
+            .map(|event| event.map(EventRow::into))
+            .map_err(RepositoryError::from)
+    }
+}
@@ -0,0 +1,47 @@
+use chrono::{Datelike, NaiveDate};
+use sqlx::PgPool;
+use crate::{domain::models::FmcSolve, repositories::fmc_solve::FmcSolvesRepository};
+use crate::repositories::error::RepositoryError;
+use crate::database::rows::FmcSolveRow;
+
+
+#[derive(Clone)]
+pub struct PgFmcSolvesRepository {
+    pool: PgPool
+}
+
+impl PgFmcSolvesRepository {
+    pub fn new(pool: PgPool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl FmcSolvesRepository for PgFmcSolvesRepository {
+    async fn insert(&self, fmc_solve: FmcSolve) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO daily_fmc_solves (username, date, solution, move_count) VALUES ($1, $2, $3, $4);")
+            .bind(fmc_solve.username)
+            .bind(fmc_solve.date.num_days_from_ce())
+            .bind(fmc_solve.solution)
+            .bind(fmc_solve.move_count)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<FmcSolve>, RepositoryError> {
+        let result = sqlx::query_as::<_, FmcSolveRow>("SELECT username, date, solution, move_count FROM daily_fmc_solves WHERE date = $1 ORDER BY move_count ASC, id ASC;")
+            .bind(date.num_days_from_ce())
+            .fetch_all(&self.pool)
+            .await;
+
+        result
+            .map(|solves| solves.into_iter().map(FmcSolveRow::into).collect())
+            .map_err(RepositoryError::from)
+    }
+}
@@ -0,0 +1,7 @@
+pub mod daily_rounds;
+pub mod daily_scrambles;
+pub mod daily_solves;
+pub mod events;
+pub mod fmc_solves;
+pub mod races;
+pub mod users;
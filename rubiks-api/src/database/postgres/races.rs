@@ -0,0 +1,69 @@
+use chrono::Datelike;
+use sqlx::PgPool;
+use crate::database::races::{finished_race_from_rows, standing_attempts_to_string};
+use crate::domain::races::FinishedRace;
+use crate::repositories::error::RepositoryError;
+use crate::repositories::race::RacesRepository;
+use crate::database::rows::{RaceResultRow, RaceRow};
+
+
+#[derive(Clone)]
+pub struct PgRacesRepository {
+    pool: PgPool
+}
+
+impl PgRacesRepository {
+    pub fn new(pool: PgPool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RacesRepository for PgRacesRepository {
+    async fn insert(&self, race: FinishedRace) -> Result<(), RepositoryError> {
+        let mut transaction = self.pool.begin().await.map_err(RepositoryError::from)?;
+        sqlx::query("INSERT INTO races (id, event, owner, date, round_count) VALUES ($1, $2, $3, $4, $5);")
+            .bind(&race.id)
+            .bind(race.event)
+            .bind(race.owner)
+            .bind(race.date.num_days_from_ce())
+            .bind(race.round_count as i32)
+            .execute(&mut *transaction)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        for standing in race.standings {
+            sqlx::query("INSERT INTO race_results (race_id, username, rank, attempts) VALUES ($1, $2, $3, $4);")
+                .bind(&race.id)
+                .bind(standing.username)
+                .bind(standing.rank as i32)
+                .bind(standing_attempts_to_string(&standing.attempts))
+                .execute(&mut *transaction)
+                .await
+                .map_err(RepositoryError::from)?;
+        }
+
+        transaction.commit().await.map_err(RepositoryError::from)
+    }
+
+    async fn fetch_by_id(&self, id: &str) -> Result<Option<FinishedRace>, RepositoryError> {
+        let race = sqlx::query_as::<_, RaceRow>("SELECT id, event, owner, date, round_count FROM races WHERE id = $1;")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+        let Some(race) = race else {
+            return Ok(None);
+        };
+
+        let results = sqlx::query_as::<_, RaceResultRow>("SELECT username, rank, attempts FROM race_results WHERE race_id = $1 ORDER BY rank ASC, username ASC;")
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(RepositoryError::from)?;
+
+        Ok(Some(finished_race_from_rows(race, results)))
+    }
+}
@@ -0,0 +1,59 @@
+use sqlx::PgPool;
+use crate::{domain::models::User, repositories::user::UsersRepository};
+use crate::repositories::error::RepositoryError;
+use crate::database::rows::UserRow;
+
+
+#[derive(Clone)]
+pub struct PgUsersRepository {
+    pool: PgPool
+}
+
+impl PgUsersRepository {
+    pub fn new(pool: PgPool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl UsersRepository for PgUsersRepository {
+    async fn insert(&self, user: User) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO users (username, password_hash, country, club) VALUES ($1, $2, $3, $4);")
+            .bind(user.username)
+            .bind(user.password_hash)
+            .bind(user.country)
+            .bind(user.club)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        let result = sqlx::query_as::<_, UserRow>("SELECT username, password_hash, country, club FROM users WHERE username = $1;")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await;
+
+        result
+            .map(|row| row.map(UserRow::into))
+            .map_err(RepositoryError::from)
+    }
+
+    async fn update_profile(&self, username: &str, country: Option<String>, club: Option<String>) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE users SET country = $1, club = $2 WHERE username = $3;")
+            .bind(country)
+            .bind(club)
+            .bind(username)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+}
@@ -5,4 +5,109 @@ pub struct DailySolveRow {
     pub username: String,
     pub date: i32,
     pub time: i32,
-}
\ No newline at end of file
+    pub move_count: Option<i32>,
+    pub verified: bool,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct DailyScrambleRow {
+    pub date: i32,
+    pub scramble: String,
+    pub version: i32,
+    pub fmc_best_length: Option<i32>,
+}
+
+
+#[derive(Clone, FromRow, Debug)]
+pub struct DailySummaryRow {
+    pub date: i32,
+    pub participants: i64,
+    pub winner_username: Option<String>,
+    pub winner_time: Option<i32>,
+    pub winner_move_count: Option<i32>,
+    pub winner_verified: Option<bool>,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct UserRow {
+    pub username: String,
+    pub password_hash: String,
+    pub country: Option<String>,
+    pub club: Option<String>,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct FmcSolveRow {
+    pub username: String,
+    pub date: i32,
+    pub solution: String,
+    pub move_count: i32,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct DailyRoundRow {
+    pub event: String,
+    pub username: String,
+    pub date: i32,
+    pub format: String,
+    pub attempts: String,
+    pub average: Option<i32>,
+    pub best: Option<i32>,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct EventRow {
+    pub id: String,
+    pub name: String,
+    pub format: String,
+    pub scramble_kind: String,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct UserStatsRow {
+    pub solve_count: i64,
+    pub best_single: Option<i32>,
+    pub mean: Option<i32>,
+    pub best_ao5: Option<i32>,
+    pub best_ao12: Option<i32>,
+    pub current_streak: i64,
+    pub longest_streak: i64,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct DailyRankRow {
+    pub date: i32,
+    pub rank: i64,
+    pub participants: i64,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct RollingAverageRow {
+    pub date: i32,
+    pub average: i32,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct LeaderboardEntryRow {
+    pub rank: i64,
+    pub username: String,
+    pub solve_count: i64,
+    pub best_single: i32,
+    pub best_average: Option<i32>,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct RaceRow {
+    pub id: String,
+    pub event: String,
+    pub owner: String,
+    pub date: i32,
+    pub round_count: i32,
+}
+
+#[derive(Clone, FromRow, Debug)]
+pub struct RaceResultRow {
+    pub username: String,
+    pub rank: i32,
+    pub attempts: String,
+}
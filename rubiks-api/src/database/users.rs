@@ -0,0 +1,70 @@
+use sqlx::SqlitePool;
+use crate::{domain::models::User, repositories::user::UsersRepository};
+use crate::repositories::error::RepositoryError;
+use super::rows::UserRow;
+
+
+#[derive(Clone)]
+pub struct SqliteUsersRepository {
+    pool: SqlitePool
+}
+
+impl SqliteUsersRepository {
+    pub fn new(pool: SqlitePool) -> Self{
+        Self {
+            pool
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl UsersRepository for SqliteUsersRepository {
+    async fn insert(&self, user: User) -> Result<(), RepositoryError> {
+        let result = sqlx::query("INSERT INTO users (username, password_hash, country, club) VALUES (?1, ?2, ?3, ?4);")
+            .bind(user.username)
+            .bind(user.password_hash)
+            .bind(user.country)
+            .bind(user.club)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+
+    async fn fetch_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        let result = sqlx::query_as::<_, UserRow>("SELECT username, password_hash, country, club FROM users WHERE username = ?1;")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await;
+
+        result
+            .map(|row| row.map(UserRow::into))
+            .map_err(RepositoryError::from)
+    }
+
+    async fn update_profile(&self, username: &str, country: Option<String>, club: Option<String>) -> Result<(), RepositoryError> {
+        let result = sqlx::query("UPDATE users SET country = ?1, club = ?2 WHERE username = ?3;")
+            .bind(country)
+            .bind(club)
+            .bind(username)
+            .execute(&self.pool)
+            .await;
+
+        result
+            .map(|_| ())
+            .map_err(RepositoryError::from)
+    }
+}
+
+impl From<UserRow> for User {
+    fn from(row: UserRow) -> User {
+        User {
+            username: row.username,
+            password_hash: row.password_hash,
+            country: row.country,
+            club: row.club,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// Daily challenge running from midnight to midnight in the reset timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChallengeWindow {
+    pub date: NaiveDate,
+    pub start: DateTime<Utc>,
+    /// Start of the next challenge
+    pub end: DateTime<Utc>,
+}
+
+impl ChallengeWindow {
+    /// Challenge running at `now`.
+    pub fn containing(now: DateTime<Utc>, timezone: Tz) -> Self {
+        let date = now.with_timezone(&timezone).date_naive();
+        let next_date = date.succ_opt().expect("Date should not be the last representable one");
+        Self {
+            date,
+            start: day_start(date, timezone),
+            end: day_start(next_date, timezone),
+        }
+    }
+
+    /// Time left until the next challenge, zero once it started.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Duration {
+        (self.end - now).max(Duration::zero())
+    }
+}
+
+/// First instant of `date` in `timezone`, after midnight when a DST change skips it.
+fn day_start(date: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    let mut local = date.and_time(NaiveTime::MIN);
+    loop {
+        // Ambiguous midnights start at their first occurrence
+        if let Some(start) = timezone.from_local_datetime(&local).earliest() {
+            return start.with_timezone(&Utc);
+        }
+        // Offsets change by multiples of 15 minutes
+        local += Duration::minutes(15);
+    }
+}
@@ -0,0 +1,64 @@
+use rubiks::cube::Cube;
+use rubiks::faces::Face;
+use rubiks::solvers::pochmann::{BldSummary, PochmannSolver};
+
+/// Piece found at a location of the cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceState {
+    /// Location on the cube, like `URF` or `UR`
+    pub location: String,
+    /// Location the piece belongs to when solved
+    pub piece: String,
+    /// Clockwise twists of a corner, or 1 for a flipped edge
+    pub twist: u8,
+    pub solved: bool,
+}
+
+/// Everything a client needs to display or analyse a cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeState {
+    pub facelets: String,
+    /// Corners then edges, in the order of the facelet model
+    pub pieces: Vec<PieceState>,
+    pub solved: bool,
+    /// Faces whose stickers all have the color of their center
+    pub solved_faces: Vec<String>,
+    /// Corners out of their location, whatever their twist
+    pub misplaced_corners: usize,
+    /// Edges out of their location, whatever their flip
+    pub misplaced_edges: usize,
+    /// Pochmann summary, buffers at ULB and UR
+    pub bld: BldSummary,
+}
+
+impl CubeState {
+    pub fn of(cube: &Cube) -> Self {
+        let pieces: Vec<PieceState> = cube
+            .iter_pieces_in_order()
+            .map(|(location, piece)| PieceState {
+                location: location.to_string(),
+                piece: piece.get_original_location().to_string(),
+                twist: piece.get_twist().number_of_twists(),
+                solved: piece.is_solved(&location),
+            })
+            .collect();
+        let misplaced = |corners: bool| cube
+            .iter_pieces_in_order()
+            .filter(|(location, piece)| location.is_corner() == corners && piece.get_original_location() != *location)
+            .count();
+
+        Self {
+            facelets: cube.to_facelets(),
+            solved: cube.is_solved(),
+            solved_faces: [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B]
+                .into_iter()
+                .filter(|face| cube.is_face_solved(*face))
+                .map(|face| face.to_string())
+                .collect(),
+            misplaced_corners: misplaced(true),
+            misplaced_edges: misplaced(false),
+            bld: PochmannSolver::default().summarize(cube),
+            pieces,
+        }
+    }
+}
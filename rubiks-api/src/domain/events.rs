@@ -0,0 +1,67 @@
+use core::fmt;
+use std::str::FromStr;
+use crate::domain::results::SolveFormat;
+
+/// How the scrambles of an event are generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrambleKind {
+    /// 3x3 random state
+    Cube3x3,
+    /// 2x2 random state, written as moves of the 3x3 corners
+    Cube2x2,
+    /// 3x3 random state in a random orientation
+    Blindfolded,
+    /// 3x3 random state with F2L solved
+    LastLayer,
+    /// 3x3 random state with the cross solved
+    CrossSolved,
+}
+
+impl ScrambleKind {
+    /// Puzzle the scrambles are applied to.
+    pub fn puzzle(&self) -> &'static str {
+        match self {
+            ScrambleKind::Cube2x2 => "2x2x2",
+            ScrambleKind::Cube3x3 | ScrambleKind::Blindfolded | ScrambleKind::LastLayer | ScrambleKind::CrossSolved => "3x3x3",
+        }
+    }
+}
+
+impl fmt::Display for ScrambleKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            ScrambleKind::Cube3x3 => "3x3",
+            ScrambleKind::Cube2x2 => "2x2",
+            ScrambleKind::Blindfolded => "bld",
+            ScrambleKind::LastLayer => "ll",
+            ScrambleKind::CrossSolved => "cross_solved",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for ScrambleKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "3x3" => Ok(ScrambleKind::Cube3x3),
+            "2x2" => Ok(ScrambleKind::Cube2x2),
+            "bld" => Ok(ScrambleKind::Blindfolded),
+            "ll" => Ok(ScrambleKind::LastLayer),
+            "cross_solved" => Ok(ScrambleKind::CrossSolved),
+            _ => Err(format!("scramble kind '{}' is invalid.", s)),
+        }
+    }
+}
+
+/// Daily challenge with its own scrambles, format and leaderboard.
+/// The catalog is stored in the `events` table, seeded by the migrations.
+#[derive(Debug, Clone)]
+pub struct Event {
+    /// Short identifier used in routes, never a date
+    pub id: String,
+    pub name: String,
+    pub format: SolveFormat,
+    pub scramble_kind: ScrambleKind,
+}
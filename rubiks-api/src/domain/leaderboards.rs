@@ -0,0 +1,85 @@
+use core::fmt;
+use std::str::FromStr;
+use chrono::{Datelike, Days, NaiveDate};
+
+/// Dates covered by a leaderboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// Current week, from monday
+    Week,
+    /// Current month
+    Month,
+    AllTime,
+}
+
+impl Period {
+    /// First date of the period containing `today`, `None` for all time.
+    pub fn start(&self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Period::Week => today.checked_sub_days(Days::new(today.weekday().num_days_from_monday() as u64)),
+            Period::Month => today.with_day(1),
+            Period::AllTime => None,
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::AllTime => "all",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            "all" => Ok(Period::AllTime),
+            _ => Err(format!("period '{}' is invalid.", s)),
+        }
+    }
+}
+
+/// Result a leaderboard ranks by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ranking {
+    /// Best daily time
+    #[default]
+    Single,
+    /// Best average of consecutive participations, best and worst dropped
+    Average,
+}
+
+impl FromStr for Ranking {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(Ranking::Single),
+            "average" => Ok(Ranking::Average),
+            _ => Err(format!("ranking '{}' is invalid.", s)),
+        }
+    }
+}
+
+/// Which solves a period leaderboard is computed from and how they rank.
+#[derive(Debug, Clone)]
+pub struct LeaderboardFilter {
+    /// `None` from the first daily
+    pub from: Option<NaiveDate>,
+    pub to: NaiveDate,
+    pub ranking: Ranking,
+    /// Number of solves in an average
+    pub average_of: u32,
+    /// Users with fewer solves in the period are left out
+    pub min_solves: u32,
+    pub country: Option<String>,
+    pub club: Option<String>,
+}
@@ -1 +1,8 @@
-pub mod models;
\ No newline at end of file
+pub mod challenge;
+pub mod cube_state;
+pub mod events;
+pub mod leaderboards;
+pub mod models;
+pub mod races;
+pub mod results;
+pub mod solutions;
\ No newline at end of file
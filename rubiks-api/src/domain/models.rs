@@ -1,7 +1,99 @@
 use chrono::NaiveDate;
+use crate::domain::results::{Attempt, SolveFormat};
 
+#[derive(Clone)]
 pub struct DailySolve {
     pub username: String,
     pub date: NaiveDate,
     pub time: i32,
-}
\ No newline at end of file
+    /// Move count of the submitted solution
+    pub move_count: Option<i32>,
+    /// Whether a solution was submitted and solved the daily scramble
+    pub verified: bool,
+}
+
+/// Scramble served for a date, stored once generated so it never changes afterwards.
+pub struct DailyScramble {
+    pub date: NaiveDate,
+    pub scramble: String,
+    /// Version of the derivation used to generate the scramble
+    pub version: i32,
+    /// Shortest solution length found by the solver, computed once the day is over
+    pub fmc_best_length: Option<i32>,
+}
+
+
+/// Results of a past daily scramble.
+pub struct DailySummary {
+    pub date: NaiveDate,
+    pub participants: i64,
+    /// Fastest solve, `None` when nobody participated
+    pub winner: Option<DailySolve>,
+}
+
+pub struct User {
+    pub username: String,
+    /// Argon2 hash in PHC string format
+    pub password_hash: String,
+    /// ISO 3166-1 alpha-2 code
+    pub country: Option<String>,
+    pub club: Option<String>,
+}
+
+/// Fewest moves submission for a daily scramble.
+pub struct FmcSolve {
+    pub username: String,
+    pub date: NaiveDate,
+    pub solution: String,
+    /// HTM count of the simplified solution
+    pub move_count: i32,
+}
+
+/// Attempts of a user on the daily round scrambles of an event.
+pub struct DailyRound {
+    pub event: String,
+    pub username: String,
+    pub date: NaiveDate,
+    pub format: SolveFormat,
+    pub attempts: Vec<Attempt>,
+    /// Average of the round in milliseconds, `None` for a DNF or a single
+    pub average: Option<i32>,
+    /// Best single in milliseconds, `None` if every attempt is a DNF
+    pub best: Option<i32>,
+}
+
+/// Aggregates of all the daily solves of a user.
+pub struct UserStats {
+    pub solve_count: i64,
+    pub best_single: Option<i32>,
+    pub mean: Option<i32>,
+    /// Best average of 5 consecutive daily solves, best and worst dropped
+    pub best_ao5: Option<i32>,
+    /// Best average of 12 consecutive daily solves, best and worst dropped
+    pub best_ao12: Option<i32>,
+    /// Consecutive days of participation up to today, or yesterday if today is not solved yet
+    pub current_streak: i64,
+    pub longest_streak: i64,
+}
+
+/// Position of a user on a daily leaderboard, tied times sharing a rank.
+pub struct DailyRank {
+    pub date: NaiveDate,
+    pub rank: i64,
+    pub participants: i64,
+}
+
+/// Average of the last solves of a user at a date.
+pub struct RollingAverage {
+    pub date: NaiveDate,
+    pub average: i32,
+}
+
+/// Standing of a user on a period leaderboard, equal results sharing a rank.
+pub struct LeaderboardEntry {
+    pub rank: i64,
+    pub username: String,
+    pub solve_count: i64,
+    pub best_single: i32,
+    pub best_average: Option<i32>,
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use chrono::NaiveDate;
+use crate::domain::events::Event;
+use crate::domain::results::{Attempt, Penalty};
+
+/// Stage of a race room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaceStatus {
+    /// Users can join until the owner starts the first round
+    Waiting,
+    Running,
+    Finished,
+}
+
+/// Race room, kept in memory until its last round ends.
+#[derive(Debug, Clone)]
+pub struct Race {
+    /// Short code shared to invite other users
+    pub id: String,
+    pub owner: String,
+    pub event: Event,
+    /// Scramble of every round, generated with the room but only revealed once their round starts
+    pub scrambles: Vec<String>,
+    /// In joining order, the owner first
+    pub participants: Vec<String>,
+    /// Attempts of the started rounds by username
+    pub rounds: Vec<HashMap<String, Attempt>>,
+    pub finished: bool,
+}
+
+impl Race {
+    pub fn new(id: String, owner: String, event: Event, scrambles: Vec<String>) -> Self {
+        Self {
+            id,
+            participants: vec![owner.clone()],
+            owner,
+            event,
+            scrambles,
+            rounds: Vec::new(),
+            finished: false,
+        }
+    }
+
+    pub fn status(&self) -> RaceStatus {
+        if self.finished {
+            RaceStatus::Finished
+        } else if self.rounds.is_empty() {
+            RaceStatus::Waiting
+        } else {
+            RaceStatus::Running
+        }
+    }
+
+    /// Index of the running round.
+    pub fn current_round(&self) -> Option<usize> {
+        (self.status() == RaceStatus::Running).then(|| self.rounds.len() - 1)
+    }
+
+    pub fn is_participant(&self, username: &str) -> bool {
+        self.participants.iter().any(|participant| participant == username)
+    }
+
+    /// Whether every participant submitted the running round.
+    pub fn is_round_complete(&self) -> bool {
+        self.current_round()
+            .is_some_and(|round| self.participants.iter().all(|username| self.rounds[round].contains_key(username)))
+    }
+
+    /// Closes the running round, giving a DNF to the participants who did not submit,
+    /// then starts the next round or finishes the race after the last one.
+    pub fn advance(&mut self) {
+        if let Some(round) = self.current_round() {
+            for username in &self.participants {
+                self.rounds[round]
+                    .entry(username.clone())
+                    .or_insert(Attempt { time: 0, penalty: Penalty::Dnf });
+            }
+        }
+
+        if self.rounds.len() < self.scrambles.len() {
+            self.rounds.push(HashMap::new());
+        } else {
+            self.finished = true;
+        }
+    }
+
+    /// Standings counting the closed rounds only, attempts of the running round being listed as they come.
+    pub fn standings(&self) -> Vec<RaceStanding> {
+        let closed_rounds = match self.current_round() {
+            Some(round) => round,
+            None => self.rounds.len(),
+        };
+        let standings = self.participants
+            .iter()
+            .map(|username| {
+                let attempts = self.rounds.iter().map(|round| round.get(username).copied()).collect();
+                RaceStanding::new(username.clone(), attempts, closed_rounds)
+            })
+            .collect();
+
+        rank_standings(standings)
+    }
+
+    /// Results to store once the race is finished.
+    pub fn results(&self, date: NaiveDate) -> FinishedRace {
+        FinishedRace {
+            id: self.id.clone(),
+            event: self.event.id.clone(),
+            owner: self.owner.clone(),
+            date,
+            round_count: self.scrambles.len(),
+            standings: self.standings(),
+        }
+    }
+}
+
+/// Results of a participant of a race.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceStanding {
+    /// Participants with as many DNFs and the same total share a rank
+    pub rank: usize,
+    pub username: String,
+    /// Attempt of each started round, `None` until submitted
+    pub attempts: Vec<Option<Attempt>>,
+    /// DNFs over the counted rounds
+    pub dnfs: usize,
+    /// Sum of the results of the counted rounds in milliseconds, DNFs excluded
+    pub total: i32,
+}
+
+impl RaceStanding {
+    /// Standing counting the first `counted_rounds` attempts, unranked.
+    pub fn new(username: String, attempts: Vec<Option<Attempt>>, counted_rounds: usize) -> Self {
+        let results: Vec<Option<i32>> = attempts
+            .iter()
+            .take(counted_rounds)
+            .map(|attempt| attempt.and_then(|attempt| attempt.result()))
+            .collect();
+
+        Self {
+            rank: 0,
+            username,
+            dnfs: results.iter().filter(|result| result.is_none()).count(),
+            total: results.iter().flatten().sum(),
+            attempts,
+        }
+    }
+}
+
+/// Orders standings by DNF count then total time, keeping the given order on ties, and numbers their ranks.
+fn rank_standings(mut standings: Vec<RaceStanding>) -> Vec<RaceStanding> {
+    standings.sort_by_key(|standing| (standing.dnfs, standing.total));
+
+    let mut previous: Option<(usize, usize, i32)> = None;
+    for (i, standing) in standings.iter_mut().enumerate() {
+        standing.rank = match previous {
+            Some((rank, dnfs, total)) if (dnfs, total) == (standing.dnfs, standing.total) => rank,
+            _ => i + 1,
+        };
+        previous = Some((standing.rank, standing.dnfs, standing.total));
+    }
+
+    standings
+}
+
+/// Race as stored once its last round ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinishedRace {
+    pub id: String,
+    /// Id of the event the scrambles were generated for
+    pub event: String,
+    pub owner: String,
+    /// Day the race ended on, in the reset timezone
+    pub date: NaiveDate,
+    pub round_count: usize,
+    /// Best first, every attempt present
+    pub standings: Vec<RaceStanding>,
+}
+
@@ -0,0 +1,134 @@
+use core::fmt;
+use std::str::FromStr;
+
+/// Penalty given to an attempt by the WCA regulations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Penalty {
+    #[default]
+    None,
+    PlusTwo,
+    Dnf,
+}
+
+impl fmt::Display for Penalty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Penalty::None => "none",
+            Penalty::PlusTwo => "+2",
+            Penalty::Dnf => "DNF",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for Penalty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Penalty::None),
+            "+2" => Ok(Penalty::PlusTwo),
+            "DNF" => Ok(Penalty::Dnf),
+            _ => Err(format!("penalty '{}' is invalid.", s)),
+        }
+    }
+}
+
+/// A timed attempt, times are in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt {
+    pub time: i32,
+    pub penalty: Penalty,
+}
+
+impl Attempt {
+    const PLUS_TWO_MS: i32 = 2000;
+
+    /// Time counting the penalty, `None` for a DNF.
+    pub fn result(&self) -> Option<i32> {
+        match self.penalty {
+            Penalty::None => Some(self.time),
+            Penalty::PlusTwo => Some(self.time + Self::PLUS_TWO_MS),
+            Penalty::Dnf => None,
+        }
+    }
+}
+
+/// How the attempts of a round are combined into a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveFormat {
+    /// A single attempt
+    Single,
+    /// Mean of 3, a DNF makes the mean a DNF
+    Mo3,
+    /// Average of 5, the best and worst attempts are dropped
+    Ao5,
+    /// Best of 3, ranked by single with the mean as tiebreaker
+    Bo3,
+}
+
+impl SolveFormat {
+    pub fn attempt_count(&self) -> usize {
+        match self {
+            SolveFormat::Single => 1,
+            SolveFormat::Mo3 | SolveFormat::Bo3 => 3,
+            SolveFormat::Ao5 => 5,
+        }
+    }
+
+    /// Whether results rank by best single before the average.
+    pub fn ranks_by_single(&self) -> bool {
+        matches!(self, SolveFormat::Single | SolveFormat::Bo3)
+    }
+
+    /// Average of the round following the WCA regulations, `None` for a DNF average or a single.
+    /// Averages are rounded to the nearest hundredth of a second.
+    pub fn average(&self, attempts: &[Attempt]) -> Option<i32> {
+        if *self == SolveFormat::Single || attempts.len() != self.attempt_count() {
+            return None;
+        }
+
+        // DNFs sort last
+        let mut results: Vec<Option<i32>> = attempts.iter().map(Attempt::result).collect();
+        results.sort_by_key(|result| result.unwrap_or(i32::MAX));
+
+        let counting = match self {
+            SolveFormat::Ao5 => &results[1..4],
+            _ => &results[..],
+        };
+        let sum: i64 = counting.iter().map(|result| result.map(i64::from)).sum::<Option<i64>>()?;
+        let mean_ms = sum as f64 / counting.len() as f64;
+        Some(((mean_ms / 10.0).round() * 10.0) as i32)
+    }
+
+    /// Best single of the round, `None` when every attempt is a DNF.
+    pub fn best(&self, attempts: &[Attempt]) -> Option<i32> {
+        attempts.iter().filter_map(Attempt::result).min()
+    }
+}
+
+impl fmt::Display for SolveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            SolveFormat::Single => "single",
+            SolveFormat::Mo3 => "mo3",
+            SolveFormat::Ao5 => "ao5",
+            SolveFormat::Bo3 => "bo3",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for SolveFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(SolveFormat::Single),
+            "mo3" => Ok(SolveFormat::Mo3),
+            "ao5" => Ok(SolveFormat::Ao5),
+            "bo3" => Ok(SolveFormat::Bo3),
+            _ => Err(format!("format '{}' is invalid.", s)),
+        }
+    }
+}
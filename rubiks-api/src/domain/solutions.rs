@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+/// Solver answering a solve request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverKind {
+    /// Two-phase search, fast but not optimal
+    Kociemba,
+    /// Two-phase searches for shorter and shorter solutions until the timeout, never aiming below
+    /// `MIN_MAX_SIZE` moves, so short but not optimal
+    Shortest,
+    /// Blindfolded targets, buffers at ULB and UR
+    Pochmann,
+}
+
+/// Steps found by a solver, moves or for Pochmann the stickers to memorize, corners then edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub steps: Vec<String>,
+    /// Time spent searching, waiting for a free solver excluded
+    pub time: Duration,
+}
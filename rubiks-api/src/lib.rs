@@ -2,4 +2,5 @@ pub mod database;
 pub mod repositories;
 pub mod services;
 pub mod domain;
-pub mod api;
\ No newline at end of file
+pub mod api;
+pub mod config;
\ No newline at end of file
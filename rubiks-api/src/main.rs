@@ -1,23 +1,88 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use chrono::Duration;
+use rubiks_api::api::clock::SystemClock;
 use rubiks_api::api::state::AppState;
 use rubiks_api::api::routes::build_router;
-use rubiks_api::database::pool::build_pool;
-use rubiks_api::database::daily_solves::SqliteDailySolvesRepository;
+use rubiks_api::config::settings::Settings;
+use rubiks_api::database::migration::{create_db_if_missing, run_migrations};
+use rubiks_api::database::pool::{build_pool, Repositories};
+use rubiks_api::services::daily_rounds::DailyRoundsService;
+use rubiks_api::services::daily_scrambles::DailyScramblesService;
 use rubiks_api::services::daily_solves::DailySolvesService;
+use rubiks_api::services::events::EventsService;
+use rubiks_api::services::fmc_solves::FmcSolvesService;
+use rubiks_api::services::races::RacesService;
+use rubiks_api::services::scramble_pool::ScramblePool;
 use rubiks_api::services::scramble_service::ScrambleService;
+use rubiks_api::services::solver::SolverService;
+use rubiks_api::services::users::UsersService;
+use tokio::sync::Semaphore;
+
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() {
-    // create the connection pool
-    let pool = build_pool();
+    // read and validate the configuration before anything else
+    let settings = Settings::load().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    });
+
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(&settings.log_level))
+        .init();
+
+    // create the database and bring its schema up to date
+    create_db_if_missing(&settings.database_url).await.expect("Could not create database.");
+    let pool = build_pool(&settings.database_url).expect("Could not create database pool.");
+    run_migrations(&pool).await.expect("Could not apply database migrations.");
+    let repositories = Repositories::new(&pool);
+
+    // secret salt of daily scrambles
+    let daily_salt = settings.daily_scramble_salt.clone().unwrap_or_else(|| {
+        tracing::warn!("DAILY_SCRAMBLE_SALT is not set, daily scrambles can be predicted");
+        String::new()
+    });
+
+    // secret signing authentication tokens
+    let jwt_secret = settings.jwt_secret.clone().map(String::into_bytes).unwrap_or_else(|| {
+        tracing::warn!("JWT_SECRET is not set, tokens will be invalid after a restart");
+        rand::random::<[u8; 32]>().to_vec()
+    });
 
-    // build application
+    // build application, scrambles and solves sharing the solver threads
+    let solver_permits = Arc::new(Semaphore::new(settings.max_concurrent_solves));
+    let scramble_service = ScrambleService::new(settings.solver_timeout_seconds, solver_permits.clone());
+    let clock = Arc::new(SystemClock);
     let app_state = AppState {
-        scramble_service: ScrambleService::new(),
-        daily_solve_service: DailySolvesService::new(SqliteDailySolvesRepository::new(pool))
+        scramble_service: scramble_service.clone(),
+        scramble_pool: ScramblePool::start(scramble_service.clone(), settings.scramble_pool_size),
+        daily_scramble_service: DailyScramblesService::new(scramble_service.clone(), repositories.daily_scrambles, daily_salt),
+        daily_solve_service: DailySolvesService::new(repositories.daily_solves, settings.require_daily_solution),
+        daily_round_service: DailyRoundsService::new(repositories.daily_rounds),
+        events_service: EventsService::new(repositories.events),
+        fmc_solve_service: FmcSolvesService::new(repositories.fmc_solves),
+        users_service: UsersService::new(repositories.users, jwt_secret),
+        solver_service: SolverService::new(solver_permits, settings.solver_timeout_seconds),
+        races_service: RacesService::start(
+            repositories.races,
+            scramble_service,
+            clock.clone(),
+            Duration::minutes(settings.race_idle_minutes.into()),
+        ),
+        clock,
+        reset_timezone: settings.reset_timezone,
+        scramble_svg_url: settings.scramble_svg_url.clone(),
     };
-    let app = build_router(app_state);
+    let (app, rate_limit_cleanup) = build_router(app_state, &settings.cors_origins, settings.rate_limit);
+    // started once, like the scramble pool refill and the race room expiry above
+    let rate_limit_cleanup = tokio::spawn(rate_limit_cleanup);
 
-    // run our app with hyper, listening globally on port 3000
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // run our app with hyper on the configured address
+    let listener = tokio::net::TcpListener::bind(settings.bind_address).await.unwrap();
+    tracing::info!("Listening on {}", settings.bind_address);
+    // the rate limit keys clients by their address
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    rate_limit_cleanup.abort();
 }
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use chrono::NaiveDate;
+use crate::domain::models::DailyRound;
+use crate::repositories::error::RepositoryError;
+
+#[async_trait::async_trait]
+pub trait DailyRoundsRepository: Send + Sync {
+    async fn insert(&self, daily_round: DailyRound) -> Result<(), RepositoryError>;
+    /// Rounds of `event` ordered by average then best single, or the reverse for formats ranking by single.
+    /// DNFs come last and the first submitted first on ties.
+    async fn fetch_all_by_date(&self, event: &str, date: NaiveDate) -> Result<Vec<DailyRound>, RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: DailyRoundsRepository + ?Sized> DailyRoundsRepository for Arc<R> {
+    async fn insert(&self, daily_round: DailyRound) -> Result<(), RepositoryError> {
+        (**self).insert(daily_round).await
+    }
+
+    async fn fetch_all_by_date(&self, event: &str, date: NaiveDate) -> Result<Vec<DailyRound>, RepositoryError> {
+        (**self).fetch_all_by_date(event, date).await
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use chrono::NaiveDate;
+use crate::domain::models::{DailyScramble, DailySummary};
+use crate::repositories::error::RepositoryError;
+
+#[async_trait::async_trait]
+pub trait DailyScramblesRepository: Send + Sync {
+    /// Does nothing if a scramble is already stored for this date.
+    async fn insert_if_absent(&self, daily_scramble: DailyScramble) -> Result<(), RepositoryError>;
+    async fn fetch_by_date(&self, date: NaiveDate) -> Result<Option<DailyScramble>, RepositoryError>;
+    async fn update_fmc_best_length(&self, date: NaiveDate, length: i32) -> Result<(), RepositoryError>;
+    /// Summaries of dailies before `date`, most recent first.
+    async fn fetch_summaries_before(&self, date: NaiveDate, limit: u32, offset: u32) -> Result<Vec<DailySummary>, RepositoryError>;
+    async fn count_before(&self, date: NaiveDate) -> Result<i64, RepositoryError>;
+    /// Does nothing if round scrambles are already stored for this event and date.
+    async fn insert_round_scrambles_if_absent(&self, event: &str, date: NaiveDate, scrambles: Vec<String>, version: i32) -> Result<(), RepositoryError>;
+    /// Round scrambles of `event` on `date` in attempt order, empty if none are stored.
+    async fn fetch_round_scrambles(&self, event: &str, date: NaiveDate) -> Result<Vec<String>, RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: DailyScramblesRepository + ?Sized> DailyScramblesRepository for Arc<R> {
+    async fn insert_if_absent(&self, daily_scramble: DailyScramble) -> Result<(), RepositoryError> {
+        (**self).insert_if_absent(daily_scramble).await
+    }
+
+    async fn fetch_by_date(&self, date: NaiveDate) -> Result<Option<DailyScramble>, RepositoryError> {
+        (**self).fetch_by_date(date).await
+    }
+
+    async fn update_fmc_best_length(&self, date: NaiveDate, length: i32) -> Result<(), RepositoryError> {
+        (**self).update_fmc_best_length(date, length).await
+    }
+
+    async fn fetch_summaries_before(&self, date: NaiveDate, limit: u32, offset: u32) -> Result<Vec<DailySummary>, RepositoryError> {
+        (**self).fetch_summaries_before(date, limit, offset).await
+    }
+
+    async fn count_before(&self, date: NaiveDate) -> Result<i64, RepositoryError> {
+        (**self).count_before(date).await
+    }
+
+    async fn insert_round_scrambles_if_absent(&self, event: &str, date: NaiveDate, scrambles: Vec<String>, version: i32) -> Result<(), RepositoryError> {
+        (**self).insert_round_scrambles_if_absent(event, date, scrambles, version).await
+    }
+
+    async fn fetch_round_scrambles(&self, event: &str, date: NaiveDate) -> Result<Vec<String>, RepositoryError> {
+        (**self).fetch_round_scrambles(event, date).await
+    }
+}
@@ -1,8 +1,63 @@
+use std::sync::Arc;
 use chrono::NaiveDate;
-use crate::domain::models::DailySolve;
+use crate::domain::leaderboards::LeaderboardFilter;
+use crate::domain::models::{DailyRank, DailySolve, LeaderboardEntry, RollingAverage, UserStats};
+use crate::repositories::error::RepositoryError;
 
 #[async_trait::async_trait]
 pub trait DailySolvesRepository: Send + Sync {
-    async fn insert(&self, daily_solve: DailySolve) -> Result<(), String>;
-    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, String>;
-}
\ No newline at end of file
+    async fn insert(&self, daily_solve: DailySolve) -> Result<(), RepositoryError>;
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, RepositoryError>;
+    /// Solves of `username`, most recent first.
+    async fn fetch_by_username(&self, username: &str, limit: u32, offset: u32) -> Result<Vec<DailySolve>, RepositoryError>;
+    async fn count_by_username(&self, username: &str) -> Result<i64, RepositoryError>;
+    /// Streaks are counted relative to `today`.
+    async fn fetch_user_stats(&self, username: &str, today: NaiveDate) -> Result<UserStats, RepositoryError>;
+    /// Ranks of the `limit` most recent solves of `username`, most recent first.
+    async fn fetch_rank_history(&self, username: &str, limit: u32) -> Result<Vec<DailyRank>, RepositoryError>;
+    /// Averages of `size` consecutive solves, best and worst dropped, for the `limit` most recent solves, oldest first.
+    async fn fetch_rolling_averages(&self, username: &str, size: u32, limit: u32) -> Result<Vec<RollingAverage>, RepositoryError>;
+    /// Users ranked over the dates of `filter`, ties ordered by username.
+    async fn fetch_leaderboard(&self, filter: &LeaderboardFilter, limit: u32, offset: u32) -> Result<Vec<LeaderboardEntry>, RepositoryError>;
+    async fn count_leaderboard(&self, filter: &LeaderboardFilter) -> Result<i64, RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: DailySolvesRepository + ?Sized> DailySolvesRepository for Arc<R> {
+    async fn insert(&self, daily_solve: DailySolve) -> Result<(), RepositoryError> {
+        (**self).insert(daily_solve).await
+    }
+
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, RepositoryError> {
+        (**self).fetch_all_by_date(date).await
+    }
+
+    async fn fetch_by_username(&self, username: &str, limit: u32, offset: u32) -> Result<Vec<DailySolve>, RepositoryError> {
+        (**self).fetch_by_username(username, limit, offset).await
+    }
+
+    async fn count_by_username(&self, username: &str) -> Result<i64, RepositoryError> {
+        (**self).count_by_username(username).await
+    }
+
+    async fn fetch_user_stats(&self, username: &str, today: NaiveDate) -> Result<UserStats, RepositoryError> {
+        (**self).fetch_user_stats(username, today).await
+    }
+
+    async fn fetch_rank_history(&self, username: &str, limit: u32) -> Result<Vec<DailyRank>, RepositoryError> {
+        (**self).fetch_rank_history(username, limit).await
+    }
+
+    async fn fetch_rolling_averages(&self, username: &str, size: u32, limit: u32) -> Result<Vec<RollingAverage>, RepositoryError> {
+        (**self).fetch_rolling_averages(username, size, limit).await
+    }
+
+    async fn fetch_leaderboard(&self, filter: &LeaderboardFilter, limit: u32, offset: u32) -> Result<Vec<LeaderboardEntry>, RepositoryError> {
+        (**self).fetch_leaderboard(filter, limit, offset).await
+    }
+
+    async fn count_leaderboard(&self, filter: &LeaderboardFilter) -> Result<i64, RepositoryError> {
+        (**self).count_leaderboard(filter).await
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// Failure of a repository, whatever the backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryError {
+    /// A unique constraint failed, e.g. a second solve of the same daily by a user
+    Conflict(String),
+    /// The database could not be reached
+    Unavailable(String),
+    /// Any other failure of the database
+    Database(String),
+}
+
+impl fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepositoryError::Conflict(message) => write!(f, "conflict: {}", message),
+            RepositoryError::Unavailable(message) => write!(f, "database unavailable: {}", message),
+            RepositoryError::Database(message) => write!(f, "database error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RepositoryError {}
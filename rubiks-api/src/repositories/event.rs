@@ -0,0 +1,21 @@
+use std::sync::Arc;
+use crate::domain::events::Event;
+use crate::repositories::error::RepositoryError;
+
+#[async_trait::async_trait]
+pub trait EventsRepository: Send + Sync {
+    async fn fetch_all(&self) -> Result<Vec<Event>, RepositoryError>;
+    async fn fetch_by_id(&self, id: &str) -> Result<Option<Event>, RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: EventsRepository + ?Sized> EventsRepository for Arc<R> {
+    async fn fetch_all(&self) -> Result<Vec<Event>, RepositoryError> {
+        (**self).fetch_all().await
+    }
+
+    async fn fetch_by_id(&self, id: &str) -> Result<Option<Event>, RepositoryError> {
+        (**self).fetch_by_id(id).await
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+use chrono::NaiveDate;
+use crate::domain::models::FmcSolve;
+use crate::repositories::error::RepositoryError;
+
+#[async_trait::async_trait]
+pub trait FmcSolvesRepository: Send + Sync {
+    async fn insert(&self, fmc_solve: FmcSolve) -> Result<(), RepositoryError>;
+    /// Solves ordered by move count, first submitted first on ties.
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<FmcSolve>, RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: FmcSolvesRepository + ?Sized> FmcSolvesRepository for Arc<R> {
+    async fn insert(&self, fmc_solve: FmcSolve) -> Result<(), RepositoryError> {
+        (**self).insert(fmc_solve).await
+    }
+
+    async fn fetch_all_by_date(&self, date: NaiveDate) -> Result<Vec<FmcSolve>, RepositoryError> {
+        (**self).fetch_all_by_date(date).await
+    }
+}
@@ -1 +1,8 @@
-pub mod daily_solve;
\ No newline at end of file
+pub mod daily_round;
+pub mod daily_scramble;
+pub mod daily_solve;
+pub mod error;
+pub mod event;
+pub mod fmc_solve;
+pub mod race;
+pub mod user;
\ No newline at end of file
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+use crate::domain::races::FinishedRace;
+use crate::repositories::error::RepositoryError;
+
+#[async_trait::async_trait]
+pub trait RacesRepository: Send + Sync {
+    /// Stores the race with the results of every participant, all or nothing.
+    async fn insert(&self, race: FinishedRace) -> Result<(), RepositoryError>;
+    /// Standings come best first.
+    async fn fetch_by_id(&self, id: &str) -> Result<Option<FinishedRace>, RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: RacesRepository + ?Sized> RacesRepository for Arc<R> {
+    async fn insert(&self, race: FinishedRace) -> Result<(), RepositoryError> {
+        (**self).insert(race).await
+    }
+
+    async fn fetch_by_id(&self, id: &str) -> Result<Option<FinishedRace>, RepositoryError> {
+        (**self).fetch_by_id(id).await
+    }
+}
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+use crate::domain::models::User;
+use crate::repositories::error::RepositoryError;
+
+#[async_trait::async_trait]
+pub trait UsersRepository: Send + Sync {
+    async fn insert(&self, user: User) -> Result<(), RepositoryError>;
+    async fn fetch_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError>;
+    async fn update_profile(&self, username: &str, country: Option<String>, club: Option<String>) -> Result<(), RepositoryError>;
+}
+
+/// Lets services hold a repository chosen at runtime.
+#[async_trait::async_trait]
+impl<R: UsersRepository + ?Sized> UsersRepository for Arc<R> {
+    async fn insert(&self, user: User) -> Result<(), RepositoryError> {
+        (**self).insert(user).await
+    }
+
+    async fn fetch_by_username(&self, username: &str) -> Result<Option<User>, RepositoryError> {
+        (**self).fetch_by_username(username).await
+    }
+
+    async fn update_profile(&self, username: &str, country: Option<String>, club: Option<String>) -> Result<(), RepositoryError> {
+        (**self).update_profile(username, country, club).await
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use crate::domain::models::DailyRound;
+use crate::domain::events::Event;
+use crate::domain::results::{Attempt, Penalty};
+use crate::repositories::daily_round::DailyRoundsRepository;
+use crate::services::error::ServiceError;
+
+#[derive(Clone, Copy)]
+pub struct DailyRoundsService<R: DailyRoundsRepository> {
+    rounds_repository: R
+}
+
+impl<R: DailyRoundsRepository> DailyRoundsService<R> {
+    pub fn new(rounds_repository: R) -> Self {
+        Self {
+            rounds_repository,
+        }
+    }
+
+    /// Computes the average and best single of `attempts` in the `event` format then stores the round.
+    pub async fn add_daily_round(&self, event: &Event, username: String, date: NaiveDate, attempts: Vec<Attempt>) -> Result<(), ServiceError> {
+        let format = event.format;
+        if attempts.len() != format.attempt_count() {
+            return Err(ServiceError::Invalid(format!("{} requires {} attempts.", format, format.attempt_count())));
+        }
+
+        // A DNF may be reported without a time
+        if attempts.iter().any(|attempt| attempt.time < 0 || (attempt.time == 0 && attempt.penalty != Penalty::Dnf)) {
+            return Err(ServiceError::Invalid("time is invalid.".into()));
+        }
+
+        self.rounds_repository.insert(DailyRound {
+            event: event.id.clone(),
+            username,
+            date,
+            format,
+            average: format.average(&attempts),
+            best: format.best(&attempts),
+            attempts,
+        })
+        .await
+        .map_err(|error| ServiceError::with_conflict(error, "round is already submitted."))
+    }
+
+    pub async fn fetch_rounds_by_date(&self, event: &Event, date: NaiveDate) -> Result<Vec<DailyRound>, ServiceError> {
+        self.rounds_repository.fetch_all_by_date(&event.id, date).await.map_err(ServiceError::from)
+    }
+}
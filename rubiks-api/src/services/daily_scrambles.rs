@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use crate::domain::events::Event;
+use crate::domain::models::{DailyScramble, DailySummary};
+use crate::repositories::daily_scramble::DailyScramblesRepository;
+use crate::services::error::ServiceError;
+use crate::services::scramble_service::ScrambleService;
+
+/// Version of the seed derivation, to bump whenever new scrambles would differ for the same seed.
+pub const DAILY_SCRAMBLE_VERSION: i32 = 1;
+
+pub const MAX_PER_PAGE: u32 = 100;
+
+#[derive(Clone)]
+pub struct DailyScramblesService<R: DailyScramblesRepository> {
+    scramble_service: ScrambleService,
+    daily_repository: R,
+    salt: String,
+    /// Dates whose best fewest moves length is being searched
+    searching: Arc<Mutex<HashSet<NaiveDate>>>,
+}
+
+impl<R: DailyScramblesRepository + Clone + 'static> DailyScramblesService<R> {
+    /// `salt` is kept secret so scrambles of future dates can't be computed in advance.
+    pub fn new(scramble_service: ScrambleService, daily_repository: R, salt: String) -> Self {
+        Self {
+            scramble_service,
+            daily_repository,
+            salt,
+            searching: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Returns the stored scramble of `date`, generating and storing it the first time.
+    /// The shortest solution the solver finds is then searched in the background and stored once found,
+    /// so the fewest moves leaderboard only reads it.
+    pub async fn get_daily_scramble(&self, date: NaiveDate) -> Result<DailyScramble, ServiceError> {
+        let daily_scramble = match self.daily_repository.fetch_by_date(date).await? {
+            Some(daily_scramble) => daily_scramble,
+            None => {
+                let scramble = self.scramble_service.get_seeded_scramble(self.seed(date)).await?;
+                self.daily_repository.insert_if_absent(DailyScramble {
+                    date,
+                    scramble,
+                    version: DAILY_SCRAMBLE_VERSION,
+                    fmc_best_length: None,
+                }).await?;
+
+                // Another request may have stored it first
+                self.daily_repository
+                    .fetch_by_date(date)
+                    .await?
+                    .ok_or_else(|| ServiceError::Internal("daily scramble was not stored.".into()))?
+            }
+        };
+
+        if daily_scramble.fmc_best_length.is_none() {
+            self.search_fmc_best_length(&daily_scramble);
+        }
+        Ok(daily_scramble)
+    }
+
+    /// Starts searching the best fewest moves length of `daily_scramble`, unless it is already searched.
+    /// A failed search is logged and left for the next request of the scramble.
+    fn search_fmc_best_length(&self, daily_scramble: &DailyScramble) {
+        let date = daily_scramble.date;
+        if !self.searching.lock().expect("Searched dates should not be poisoned").insert(date) {
+            return;
+        }
+
+        let service = self.clone();
+        let scramble = daily_scramble.scramble.clone();
+        tokio::spawn(async move {
+            let result = match service.scramble_service.get_best_solution_length(&scramble).await {
+                Ok(length) => service.daily_repository.update_fmc_best_length(date, length as i32).await.map_err(ServiceError::from),
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                tracing::warn!("Could not store the best fewest moves length of {}: {}", date, error);
+            }
+            service.searching.lock().expect("Searched dates should not be poisoned").remove(&date);
+        });
+    }
+
+    /// Scramble that was served on a past `date`, never generated after the day.
+    /// Its best fewest moves length is searched again if the server stopped before storing it.
+    pub async fn fetch_served_scramble(&self, date: NaiveDate) -> Result<DailyScramble, ServiceError> {
+        let daily_scramble = self.daily_repository
+            .fetch_by_date(date)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("no daily scramble was served on {}.", date)))?;
+
+        if daily_scramble.fmc_best_length.is_none() {
+            self.search_fmc_best_length(&daily_scramble);
+        }
+        Ok(daily_scramble)
+    }
+
+    /// Returns the stored scrambles of the `event` round on `date`, generating and storing them the first time.
+    pub async fn get_round_scrambles(&self, event: &Event, date: NaiveDate) -> Result<Vec<String>, ServiceError> {
+        let count = event.format.attempt_count();
+        let scrambles = self.daily_repository.fetch_round_scrambles(&event.id, date).await?;
+        if scrambles.len() >= count {
+            return Ok(scrambles);
+        }
+
+        let mut scrambles = Vec::with_capacity(count);
+        for attempt in 0..count {
+            let seed = self.round_seed(&event.id, date, attempt);
+            scrambles.push(self.scramble_service.get_seeded_event_scramble(event.scramble_kind, seed).await?);
+        }
+        self.daily_repository.insert_round_scrambles_if_absent(&event.id, date, scrambles, DAILY_SCRAMBLE_VERSION).await?;
+
+        // Another request may have stored them first
+        let scrambles = self.daily_repository.fetch_round_scrambles(&event.id, date).await?;
+        if scrambles.len() < count {
+            return Err(ServiceError::Internal("round scrambles were not stored.".into()));
+        }
+        Ok(scrambles)
+    }
+
+    /// Scrambles of the `event` round that were served on a past `date`, never generated after the day.
+    pub async fn fetch_served_round_scrambles(&self, event: &Event, date: NaiveDate) -> Result<Vec<String>, ServiceError> {
+        let scrambles = self.daily_repository.fetch_round_scrambles(&event.id, date).await?;
+        if scrambles.len() < event.format.attempt_count() {
+            return Err(ServiceError::NotFound(format!("no {} round was served on {}.", event.id, date)));
+        }
+        Ok(scrambles)
+    }
+
+    /// Page of the dailies before `today`, most recent first, with the total number of dailies.
+    pub async fn fetch_history(&self, today: NaiveDate, page: u32, per_page: u32) -> Result<(Vec<DailySummary>, i64), ServiceError> {
+        check_pagination(page, per_page)?;
+
+        let summaries = self.daily_repository.fetch_summaries_before(today, per_page, (page - 1) * per_page).await?;
+        let total = self.daily_repository.count_before(today).await?;
+        Ok((summaries, total))
+    }
+
+    fn seed(&self, date: NaiveDate) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update(DAILY_SCRAMBLE_VERSION.to_le_bytes());
+        hasher.update(date.format("%Y-%m-%d").to_string().as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Round scrambles derive from the daily seed, the event and the attempt index.
+    fn round_seed(&self, event: &str, date: NaiveDate, attempt: usize) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.seed(date));
+        hasher.update(event.as_bytes());
+        hasher.update((attempt as u32).to_le_bytes());
+        hasher.finalize().into()
+    }
+}
+
+pub(crate) fn check_pagination(page: u32, per_page: u32) -> Result<(), ServiceError> {
+    if page == 0 {
+        return Err(ServiceError::Invalid("page starts at 1.".into()));
+    }
+    if per_page == 0 || per_page > MAX_PER_PAGE {
+        return Err(ServiceError::Invalid(format!("per_page must be between 1 and {}.", MAX_PER_PAGE)));
+    }
+    Ok(())
+}
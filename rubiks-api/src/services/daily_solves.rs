@@ -1,32 +1,94 @@
 use chrono::NaiveDate;
-use crate::domain::models::DailySolve;
+use crate::domain::leaderboards::LeaderboardFilter;
+use crate::domain::models::{DailyRank, DailySolve, LeaderboardEntry, RollingAverage, UserStats};
 use crate::repositories::daily_solve::DailySolvesRepository;
+use crate::services::daily_scrambles::check_pagination;
+use crate::services::error::ServiceError;
+
+/// Number of recent dailies in the rank history of a user.
+pub const RANK_HISTORY_LENGTH: u32 = 30;
+
+/// Bounds of the rolling average sizes, at least 3 solves are needed to drop the best and worst.
+pub const MIN_ROLLING_SIZE: u32 = 3;
+pub const MAX_ROLLING_SIZE: u32 = 100;
+pub const MAX_ROLLING_POINTS: u32 = 365;
 
 #[derive(Clone, Copy)]
 pub struct DailySolvesService<R: DailySolvesRepository> {
-    daily_repository: R
+    daily_repository: R,
+    /// Rejects solves without a verified solution
+    require_solution: bool,
 }
 
 impl<R: DailySolvesRepository> DailySolvesService<R> {
-    pub fn new(daily_repository: R) -> Self {
+    pub fn new(daily_repository: R, require_solution: bool) -> Self {
         Self {
             daily_repository,
+            require_solution,
         }
     }
 
-    pub async fn add_daily_solve(&self, daily_solve: DailySolve) -> Result<(), String> {
+    pub async fn add_daily_solve(&self, daily_solve: DailySolve) -> Result<(), ServiceError> {
         if daily_solve.time <= 0 {
-            return Err("time is invalid.".into());
+            return Err(ServiceError::Invalid("time is invalid.".into()));
         }
 
         if daily_solve.username.is_empty() || daily_solve.username.chars().all(|c| c == ' ') {
-            return Err("username is empty.".into());
+            return Err(ServiceError::Invalid("username is empty.".into()));
+        }
+
+        if self.require_solution && !daily_solve.verified {
+            return Err(ServiceError::Invalid("solution is required.".into()));
+        }
+
+        self.daily_repository
+            .insert(daily_solve)
+            .await
+            .map_err(|error| ServiceError::with_conflict(error, "daily scramble is already solved."))
+    }
+
+    pub async fn fetch_solves_by_date(&self, date: NaiveDate) -> Result<Vec<DailySolve>, ServiceError> {
+        self.daily_repository.fetch_all_by_date(date).await.map_err(ServiceError::from)
+    }
+
+    /// Page of the solves of `username`, most recent first, with their total number.
+    pub async fn fetch_user_solves(&self, username: &str, page: u32, per_page: u32) -> Result<(Vec<DailySolve>, i64), ServiceError> {
+        check_pagination(page, per_page)?;
+
+        let solves = self.daily_repository.fetch_by_username(username, per_page, (page - 1) * per_page).await?;
+        let total = self.daily_repository.count_by_username(username).await?;
+        Ok((solves, total))
+    }
+
+    /// Statistics of `username` with the ranks of their `RANK_HISTORY_LENGTH` last dailies.
+    pub async fn fetch_user_stats(&self, username: &str, today: NaiveDate) -> Result<(UserStats, Vec<DailyRank>), ServiceError> {
+        let stats = self.daily_repository.fetch_user_stats(username, today).await?;
+        let ranks = self.daily_repository.fetch_rank_history(username, RANK_HISTORY_LENGTH).await?;
+        Ok((stats, ranks))
+    }
+
+    /// Averages of `size` for the `points` last solves of `username`, oldest first.
+    pub async fn fetch_rolling_averages(&self, username: &str, size: u32, points: u32) -> Result<Vec<RollingAverage>, ServiceError> {
+        if !(MIN_ROLLING_SIZE..=MAX_ROLLING_SIZE).contains(&size) {
+            return Err(ServiceError::Invalid(format!("size must be between {} and {}.", MIN_ROLLING_SIZE, MAX_ROLLING_SIZE)));
+        }
+        if points == 0 || points > MAX_ROLLING_POINTS {
+            return Err(ServiceError::Invalid(format!("points must be between 1 and {}.", MAX_ROLLING_POINTS)));
         }
 
-        self.daily_repository.insert(daily_solve).await
+        self.daily_repository.fetch_rolling_averages(username, size, points).await.map_err(ServiceError::from)
     }
 
-    pub async fn fetch_today_solves(&self, today: NaiveDate) -> Result<Vec<DailySolve>, String> {
-        self.daily_repository.fetch_all_by_date(today).await
+    /// Page of the leaderboard over the dates of `filter`, with the number of ranked users.
+    pub async fn fetch_leaderboard(&self, mut filter: LeaderboardFilter, page: u32, per_page: u32) -> Result<(Vec<LeaderboardEntry>, i64), ServiceError> {
+        check_pagination(page, per_page)?;
+        if !(MIN_ROLLING_SIZE..=MAX_ROLLING_SIZE).contains(&filter.average_of) {
+            return Err(ServiceError::Invalid(format!("average_of must be between {} and {}.", MIN_ROLLING_SIZE, MAX_ROLLING_SIZE)));
+        }
+        filter.country = filter.country.map(|country| country.to_ascii_uppercase());
+
+        let entries = self.daily_repository.fetch_leaderboard(&filter, per_page, (page - 1) * per_page).await?;
+        let total = self.daily_repository.count_leaderboard(&filter).await?;
+        Ok((entries, total))
     }
 }
\ No newline at end of file
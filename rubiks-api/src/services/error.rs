@@ -0,0 +1,62 @@
+use std::fmt;
+use crate::repositories::error::RepositoryError;
+
+/// Failure of a service, turned into a response by `api::error`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceError {
+    /// Values given to the service are not accepted
+    Invalid(String),
+    /// Credentials are missing, wrong or expired
+    Unauthorized(String),
+    /// The user is known but may not do this, e.g. start the race of another owner
+    Forbidden(String),
+    NotFound(String),
+    /// The change conflicts with stored data, e.g. a second solve of the same daily
+    Conflict(String),
+    /// Nothing is ready to be served yet, the call may be retried shortly
+    Busy(String),
+    /// A solver search ran out of time
+    TimedOut(String),
+    /// The database could not be reached, the call may be retried
+    Unavailable(String),
+    /// Any other failure, a bug rather than a wrong call
+    Internal(String),
+}
+
+impl ServiceError {
+    /// Repository error where a conflict is explained by `message` rather than the failed constraint.
+    pub fn with_conflict(error: RepositoryError, message: &str) -> Self {
+        match error {
+            RepositoryError::Conflict(_) => ServiceError::Conflict(message.into()),
+            error => error.into(),
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::Invalid(message)
+            | ServiceError::Unauthorized(message)
+            | ServiceError::Forbidden(message)
+            | ServiceError::NotFound(message)
+            | ServiceError::Conflict(message)
+            | ServiceError::Busy(message)
+            | ServiceError::TimedOut(message) => write!(f, "{}", message),
+            ServiceError::Unavailable(message) => write!(f, "database unavailable: {}", message),
+            ServiceError::Internal(message) => write!(f, "internal error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+impl From<RepositoryError> for ServiceError {
+    fn from(error: RepositoryError) -> Self {
+        match error {
+            RepositoryError::Conflict(message) => ServiceError::Conflict(message),
+            RepositoryError::Unavailable(message) => ServiceError::Unavailable(message),
+            RepositoryError::Database(message) => ServiceError::Internal(message),
+        }
+    }
+}
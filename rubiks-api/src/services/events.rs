@@ -0,0 +1,24 @@
+use crate::domain::events::Event;
+use crate::repositories::event::EventsRepository;
+use crate::services::error::ServiceError;
+
+#[derive(Clone, Copy)]
+pub struct EventsService<R: EventsRepository> {
+    events_repository: R
+}
+
+impl<R: EventsRepository> EventsService<R> {
+    pub fn new(events_repository: R) -> Self {
+        Self {
+            events_repository,
+        }
+    }
+
+    pub async fn fetch_events(&self) -> Result<Vec<Event>, ServiceError> {
+        self.events_repository.fetch_all().await.map_err(ServiceError::from)
+    }
+
+    pub async fn fetch_event(&self, id: &str) -> Result<Option<Event>, ServiceError> {
+        self.events_repository.fetch_by_id(id).await.map_err(ServiceError::from)
+    }
+}
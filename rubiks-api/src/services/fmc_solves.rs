@@ -0,0 +1,32 @@
+use chrono::NaiveDate;
+use crate::domain::models::FmcSolve;
+use crate::repositories::fmc_solve::FmcSolvesRepository;
+use crate::services::error::ServiceError;
+
+/// WCA limit on the length of a fewest moves solution.
+pub const FMC_MAX_MOVES: i32 = 80;
+
+#[derive(Clone, Copy)]
+pub struct FmcSolvesService<R: FmcSolvesRepository> {
+    fmc_repository: R
+}
+
+impl<R: FmcSolvesRepository> FmcSolvesService<R> {
+    pub fn new(fmc_repository: R) -> Self {
+        Self {
+            fmc_repository,
+        }
+    }
+
+    /// `fmc_solve` must have been checked by `ScrambleService::verify_fmc_solution`.
+    pub async fn add_fmc_solve(&self, fmc_solve: FmcSolve) -> Result<(), ServiceError> {
+        self.fmc_repository
+            .insert(fmc_solve)
+            .await
+            .map_err(|error| ServiceError::with_conflict(error, "fewest moves solution is already submitted."))
+    }
+
+    pub async fn fetch_solves_by_date(&self, date: NaiveDate) -> Result<Vec<FmcSolve>, ServiceError> {
+        self.fmc_repository.fetch_all_by_date(date).await.map_err(ServiceError::from)
+    }
+}
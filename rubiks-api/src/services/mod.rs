@@ -1,2 +1,11 @@
 pub mod scramble_service;
-pub mod daily_solves;
\ No newline at end of file
+pub mod scramble_pool;
+pub mod daily_rounds;
+pub mod daily_scrambles;
+pub mod daily_solves;
+pub mod error;
+pub mod events;
+pub mod fmc_solves;
+pub mod races;
+pub mod solver;
+pub mod users;
\ No newline at end of file
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rand::Rng;
+use tokio::sync::broadcast;
+use crate::api::clock::Clock;
+use crate::domain::events::Event;
+use crate::domain::races::{FinishedRace, Race, RaceStatus};
+use crate::domain::results::{Attempt, Penalty};
+use crate::repositories::race::RacesRepository;
+use crate::services::error::ServiceError;
+use crate::services::scramble_service::ScrambleService;
+
+pub const MAX_RACE_ROUNDS: usize = 12;
+pub const MAX_RACE_PARTICIPANTS: usize = 16;
+/// States a slow connection can fall behind by before skipping to the latest ones
+const UPDATES_CAPACITY: usize = 16;
+/// Letters and digits that are not mistaken for one another
+const RACE_ID_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const RACE_ID_LENGTH: usize = 6;
+/// How often rooms are checked for inactivity
+const EXPIRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Race running in memory, or stored once finished.
+pub enum RaceView {
+    Live(Race),
+    Finished(FinishedRace),
+}
+
+struct Room {
+    race: Race,
+    /// Every new state of the race, for its live connections
+    updates: broadcast::Sender<Race>,
+    /// When the race was created or last changed
+    last_activity: DateTime<Utc>,
+}
+
+/// Rooms are kept in memory until their last round ends or they stay idle too long, only the results
+/// of finished races are stored.
+#[derive(Clone)]
+pub struct RacesService<R: RacesRepository> {
+    races_repository: R,
+    scramble_service: ScrambleService,
+    rooms: Arc<Mutex<HashMap<String, Room>>>,
+    clock: Arc<dyn Clock>,
+    idle_timeout: Duration,
+}
+
+impl<R: RacesRepository + Clone + Send + Sync + 'static> RacesService<R> {
+    /// Starts a background task closing the rooms without activity for `idle_timeout`.
+    pub fn start(races_repository: R, scramble_service: ScrambleService, clock: Arc<dyn Clock>, idle_timeout: Duration) -> Self {
+        let service = Self {
+            races_repository,
+            scramble_service,
+            rooms: Arc::new(Mutex::new(HashMap::new())),
+            clock,
+            idle_timeout,
+        };
+
+        let expiry = service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EXPIRY_INTERVAL);
+            loop {
+                interval.tick().await;
+                expiry.expire_idle_rooms();
+            }
+        });
+        service
+    }
+}
+
+impl<R: RacesRepository> RacesService<R> {
+    /// Opens a room with a scramble of `event` for each round, `owner` being its first participant.
+    pub async fn create_race(&self, owner: String, event: Event, round_count: usize) -> Result<Race, ServiceError> {
+        if !(1..=MAX_RACE_ROUNDS).contains(&round_count) {
+            return Err(ServiceError::Invalid(format!("round count must be between 1 and {}.", MAX_RACE_ROUNDS)));
+        }
+
+        let mut scrambles = Vec::with_capacity(round_count);
+        for _ in 0..round_count {
+            scrambles.push(self.scramble_service.get_seeded_event_scramble(event.scramble_kind, rand::random()).await?);
+        }
+
+        loop {
+            let id = new_race_id();
+            if self.races_repository.fetch_by_id(&id).await?.is_some() {
+                continue;
+            }
+
+            let mut rooms = self.rooms.lock().expect("Race rooms should not be poisoned");
+            if rooms.contains_key(&id) {
+                continue;
+            }
+            let race = Race::new(id.clone(), owner, event, scrambles);
+            let (updates, _) = broadcast::channel(UPDATES_CAPACITY);
+            rooms.insert(id, Room { race: race.clone(), updates, last_activity: self.clock.now() });
+            return Ok(race);
+        }
+    }
+
+    /// Adds `username` to a race that has not started, joining twice does nothing.
+    pub fn join_race(&self, id: &str, username: &str) -> Result<Race, ServiceError> {
+        self.update(id, |race| {
+            if race.is_participant(username) {
+                return Ok(());
+            }
+            if race.status() != RaceStatus::Waiting {
+                return Err(ServiceError::Conflict("race has already started.".into()));
+            }
+            if race.participants.len() >= MAX_RACE_PARTICIPANTS {
+                return Err(ServiceError::Conflict("race is full.".into()));
+            }
+
+            race.participants.push(username.to_string());
+            Ok(())
+        })
+    }
+
+    /// Starts the first round, only the owner can.
+    pub fn start_race(&self, id: &str, username: &str) -> Result<(), ServiceError> {
+        self.update(id, |race| {
+            if race.owner != username {
+                return Err(ServiceError::Forbidden("only the owner can start the race.".into()));
+            }
+            if race.status() != RaceStatus::Waiting {
+                return Err(ServiceError::Conflict("race has already started.".into()));
+            }
+
+            race.advance();
+            Ok(())
+        })
+        .map(|_| ())
+    }
+
+    /// Records the attempt of `username` on the running round, the next round starting once everyone submitted.
+    pub async fn submit_attempt(&self, id: &str, username: &str, attempt: Attempt, date: NaiveDate) -> Result<(), ServiceError> {
+        // A DNF may be reported without a time
+        if attempt.time < 0 || (attempt.time == 0 && attempt.penalty != Penalty::Dnf) {
+            return Err(ServiceError::Invalid("time is invalid.".into()));
+        }
+
+        let race = self.update(id, |race| {
+            if !race.is_participant(username) {
+                return Err(ServiceError::Forbidden("only participants can submit a time.".into()));
+            }
+            let round = race.current_round().ok_or_else(|| ServiceError::Conflict("race is not running.".into()))?;
+            if race.rounds[round].contains_key(username) {
+                return Err(ServiceError::Conflict("round is already submitted.".into()));
+            }
+
+            race.rounds[round].insert(username.to_string(), attempt);
+            if race.is_round_complete() {
+                race.advance();
+            }
+            Ok(())
+        })?;
+
+        self.store_if_finished(race, date).await
+    }
+
+    /// Closes the running round without waiting for everyone, only the owner can.
+    pub async fn next_round(&self, id: &str, username: &str, date: NaiveDate) -> Result<(), ServiceError> {
+        let race = self.update(id, |race| {
+            if race.owner != username {
+                return Err(ServiceError::Forbidden("only the owner can close a round.".into()));
+            }
+            if race.status() != RaceStatus::Running {
+                return Err(ServiceError::Conflict("race is not running.".into()));
+            }
+
+            race.advance();
+            Ok(())
+        })?;
+
+        self.store_if_finished(race, date).await
+    }
+
+    /// Current state of a live race and its following states.
+    pub fn subscribe(&self, id: &str) -> Result<(Race, broadcast::Receiver<Race>), ServiceError> {
+        let rooms = self.rooms.lock().expect("Race rooms should not be poisoned");
+        let room = rooms.get(id).ok_or_else(|| race_not_found(id))?;
+
+        Ok((room.race.clone(), room.updates.subscribe()))
+    }
+
+    pub async fn fetch_race(&self, id: &str) -> Result<RaceView, ServiceError> {
+        let live = self.rooms
+            .lock()
+            .expect("Race rooms should not be poisoned")
+            .get(id)
+            .map(|room| room.race.clone());
+        if let Some(race) = live {
+            return Ok(RaceView::Live(race));
+        }
+
+        match self.races_repository.fetch_by_id(id).await? {
+            Some(race) => Ok(RaceView::Finished(race)),
+            None => Err(race_not_found(id)),
+        }
+    }
+
+    /// Closes the rooms without activity for the idle timeout, which ends their live connections.
+    /// Their races are dropped without being stored, returns how many were.
+    pub fn expire_idle_rooms(&self) -> usize {
+        let expired_before = self.clock.now() - self.idle_timeout;
+        let mut rooms = self.rooms.lock().expect("Race rooms should not be poisoned");
+        let count = rooms.len();
+        rooms.retain(|_, room| room.last_activity > expired_before);
+
+        let expired = count - rooms.len();
+        if expired > 0 {
+            tracing::info!("Closed {} idle race rooms", expired);
+        }
+        expired
+    }
+
+    /// Applies `change` to a live race and sends its new state to the connections.
+    fn update(&self, id: &str, change: impl FnOnce(&mut Race) -> Result<(), ServiceError>) -> Result<Race, ServiceError> {
+        let mut rooms = self.rooms.lock().expect("Race rooms should not be poisoned");
+        let room = rooms.get_mut(id).ok_or_else(|| race_not_found(id))?;
+        change(&mut room.race)?;
+        room.last_activity = self.clock.now();
+
+        // Nobody may be connected
+        let _ = room.updates.send(room.race.clone());
+        Ok(room.race.clone())
+    }
+
+    /// Stores the results of a finished race then closes its room, which ends the live connections.
+    /// The room stays open if the results could not be stored.
+    async fn store_if_finished(&self, race: Race, date: NaiveDate) -> Result<(), ServiceError> {
+        if !race.finished {
+            return Ok(());
+        }
+
+        self.races_repository
+            .insert(race.results(date))
+            .await
+            .map_err(|error| ServiceError::with_conflict(error, "race is already stored."))?;
+        self.rooms.lock().expect("Race rooms should not be poisoned").remove(&race.id);
+        Ok(())
+    }
+}
+
+fn new_race_id() -> String {
+    let mut rng = rand::rng();
+    (0..RACE_ID_LENGTH)
+        .map(|_| char::from(RACE_ID_ALPHABET[rng.random_range(0..RACE_ID_ALPHABET.len())]))
+        .collect()
+}
+
+fn race_not_found(id: &str) -> ServiceError {
+    ServiceError::NotFound(format!("race '{}' does not exist.", id))
+}
@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use crate::services::error::ServiceError;
+use crate::services::scramble_service::ScrambleService;
+
+/// Wait before generating again after a failure, so a failing solver does not spin
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Longest a request waits for the refill once the pool ran out
+const MAX_TAKE_WAIT: Duration = Duration::from_millis(500);
+
+/// Random 3x3x3 scrambles generated ahead of the requests, so they are served without waiting for the solver.
+#[derive(Clone)]
+pub struct ScramblePool {
+    scramble_service: ScrambleService,
+    scrambles: Arc<Mutex<VecDeque<String>>>,
+    size: usize,
+    /// Wakes the refill task when a scramble is taken
+    taken: Arc<Notify>,
+    /// Wakes the requests waiting for a scramble when one is added
+    added: Arc<Notify>,
+}
+
+impl ScramblePool {
+    /// Starts a background task filling the pool with `size` scrambles, then refilling it as they are taken.
+    pub fn start(scramble_service: ScrambleService, size: usize) -> Self {
+        let pool = Self {
+            scramble_service,
+            scrambles: Arc::new(Mutex::new(VecDeque::with_capacity(size))),
+            size,
+            taken: Arc::new(Notify::new()),
+            added: Arc::new(Notify::new()),
+        };
+        tokio::spawn(pool.clone().refill());
+        pool
+    }
+
+    /// A pooled scramble. Requests never search one themselves, so once the pool ran out they wait
+    /// a little for the refill, then fail as busy.
+    pub async fn take(&self) -> Result<String, ServiceError> {
+        let pooled = tokio::time::timeout(MAX_TAKE_WAIT, async {
+            loop {
+                // Created before looking, so an addition in between still wakes it
+                let added = self.added.notified();
+                if let Some(scramble) = self.scrambles.lock().expect("Scramble pool should not be poisoned").pop_front() {
+                    return scramble;
+                }
+                added.await;
+            }
+        });
+
+        let scramble = pooled.await.map_err(|_| ServiceError::Busy("no scramble is ready, try again shortly.".into()))?;
+        self.taken.notify_one();
+        Ok(scramble)
+    }
+
+    /// Scrambles ready to be served.
+    pub fn available(&self) -> usize {
+        self.scrambles.lock().expect("Scramble pool should not be poisoned").len()
+    }
+
+    /// Runs as long as the server, a failed generation being logged and tried again.
+    async fn refill(self) {
+        loop {
+            while self.available() < self.size {
+                match self.scramble_service.get_random_scramble().await {
+                    Ok(scramble) => {
+                        self.scrambles.lock().expect("Scramble pool should not be poisoned").push_back(scramble);
+                        self.added.notify_waiters();
+                    },
+                    Err(error) => {
+                        tracing::error!("Could not refill the scramble pool: {}", error);
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
+            // A take while generating leaves a permit, so the pool is checked again
+            self.taken.notified().await;
+        }
+    }
+}
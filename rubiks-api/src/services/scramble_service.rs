@@ -1,44 +1,125 @@
 use std::sync::Arc;
-use chrono::{Datelike, NaiveDate};
-use rand::{rngs::StdRng, SeedableRng};
-use rubiks::{cube::{Cube, CubeMove}, generators::scramble::ScrambleGenerator};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use core::fmt;
+use rubiks::{cube::{Cube, CubeMove}, generators::scramble::{ScrambleGenerator, ScrambleSubset}, notation::{simplify, NotationMove, Orientation}};
+use rubiks::solvers::kociemba::{KociembaError, KociembaSolver};
+use tokio::sync::Semaphore;
+use crate::domain::events::ScrambleKind;
+use crate::services::error::ServiceError;
+use crate::services::fmc_solves::FMC_MAX_MOVES;
 
+/// Version of the scramble generators, to bump whenever they would draw other scrambles from the same random numbers.
+/// Daily scrambles also have their own `DAILY_SCRAMBLE_VERSION` for how their seeds are derived.
+pub const SCRAMBLE_GENERATOR_VERSION: i32 = 1;
+
+/// Generates scrambles on blocking threads, each solver search waiting for one of the permits it shares with `SolverService`.
 #[derive(Clone)]
 pub struct ScrambleService {
-    generator: Arc<ScrambleGenerator>
+    generator: Arc<ScrambleGenerator>,
+    /// Time given to each solver search
+    solver_timeout: f32,
+    solver_permits: Arc<Semaphore>,
 }
 
 impl ScrambleService {
-    pub fn new() -> Self {
+    pub fn new(solver_timeout: f32, solver_permits: Arc<Semaphore>) -> Self {
         Self {
-            generator: Arc::new(ScrambleGenerator::default()),
+            generator: Arc::new(ScrambleGenerator::new(KociembaSolver { max_size: 20, timeout: solver_timeout })),
+            solver_timeout,
+            solver_permits,
         }
     }
 
-    pub async fn get_daily_scramble(&self, date: NaiveDate) -> String {
-        let seed = (date.year() as u64) * 10_000u64 + (date.month() as u64) * 100u64 + date.day() as u64;
+    /// ChaCha20 output is stable across versions and platforms, so a seed always draws the same cube.
+    pub async fn get_seeded_scramble(&self, seed: [u8; 32]) -> Result<String, ServiceError> {
         self.generate_scramble_with_seed(Some(seed)).await
     }
 
-    pub async fn get_random_scramble(&self) -> String {
+    /// Scramble of an event drawn from `seed`, stable like `get_seeded_scramble`.
+    pub async fn get_seeded_event_scramble(&self, kind: ScrambleKind, seed: [u8; 32]) -> Result<String, ServiceError> {
+        let generator = self.generator.clone();
+        self.run_solver(move || {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            let scramble = match kind {
+                ScrambleKind::Cube3x3 => generator.generate(&mut rng).map(|moves| moves_to_string(&moves)),
+                ScrambleKind::Cube2x2 => Ok(moves_to_string(&generator.generate_pocket(&mut rng))),
+                ScrambleKind::Blindfolded => generator.generate_blindfolded(&mut rng).map(|moves| moves_to_string(&moves)),
+                ScrambleKind::LastLayer => generator.generate_subset(ScrambleSubset::LastLayer, &mut rng).map(|moves| moves_to_string(&moves)),
+                ScrambleKind::CrossSolved => generator.generate_subset(ScrambleSubset::CrossSolved, &mut rng).map(|moves| moves_to_string(&moves)),
+            };
+            scramble.map_err(scramble_failed)
+        })
+        .await
+    }
+
+    pub async fn get_random_scramble(&self) -> Result<String, ServiceError> {
         self.generate_scramble_with_seed(None).await
     }
 
-    pub fn is_cube_solved(&self, moves_str: &str) -> Option<bool> {   
-        CubeMove::parse_array(&moves_str)
-            .map(|moves| {
-                let mut cube = Cube::solved();
-                cube.apply_moves(&moves);
-                cube.is_solved()
-            })
+    /// Checks that `solution` solves `scramble` and returns its move count, rotations excluded.
+    /// The solution may use wide moves, slices and rotations.
+    pub fn verify_solution(&self, scramble_str: &str, solution_str: &str) -> Result<usize, ServiceError> {
+        let solution = NotationMove::parse_array(solution_str).ok_or_else(|| ServiceError::Invalid("solution is invalid.".into()))?;
+        Self::check_solves(scramble_str, &solution)?;
+
+        Ok(solution.iter().filter(|m| !m.is_rotation()).count())
     }
 
-    async fn generate_scramble_with_seed(&self, seed: Option<u64>) -> String {
+    /// Checks a fewest moves solution and returns its HTM count once simplified, rotations excluded.
+    /// Slice moves are not allowed, and the solution as written is capped at `FMC_MAX_MOVES`, as in WCA competitions.
+    pub fn verify_fmc_solution(&self, scramble_str: &str, solution_str: &str) -> Result<usize, ServiceError> {
+        let solution = NotationMove::parse_array(solution_str).ok_or_else(|| ServiceError::Invalid("solution is invalid.".into()))?;
+        if solution.iter().any(|m| matches!(m, NotationMove::Slice(_, _))) {
+            return Err(ServiceError::Invalid("slice moves are not allowed.".into()));
+        }
+        if solution.iter().filter(|m| !m.is_rotation()).count() > FMC_MAX_MOVES as usize {
+            return Err(ServiceError::Invalid(format!("solution is longer than {} moves.", FMC_MAX_MOVES)));
+        }
+
+        let cube_moves = Self::check_solves(scramble_str, &solution)?;
+        Ok(simplify(&cube_moves).len())
+    }
+
+    /// Length of the shortest solution the solver finds.
+    pub async fn get_best_solution_length(&self, scramble_str: &str) -> Result<usize, ServiceError> {
+        let scramble = CubeMove::parse_array(scramble_str).ok_or_else(|| ServiceError::Internal(format!("scramble '{}' is invalid.", scramble_str)))?;
+
+        let timeout = self.solver_timeout;
+        self.run_solver(move || {
+            let mut cube = Cube::solved();
+            cube.apply_moves(&scramble);
+
+            let solver = KociembaSolver { max_size: 20, timeout };
+            solver
+                .solve_shortest(&cube)
+                .map(|solution| solution.len())
+                .map_err(|error| ServiceError::Internal(format!("scramble could not be solved: {}.", error)))
+        })
+        .await
+    }
+
+    /// Returns the lowered solution if it solves the scramble.
+    fn check_solves(scramble_str: &str, solution: &[NotationMove]) -> Result<Vec<CubeMove>, ServiceError> {
+        let scramble = CubeMove::parse_array(scramble_str).ok_or_else(|| ServiceError::Internal(format!("scramble '{}' is invalid.", scramble_str)))?;
+        let cube_moves = Orientation::default().lower_all(solution);
+
+        let mut cube = Cube::solved();
+        cube.apply_moves(&scramble);
+        cube.apply_moves(&cube_moves);
+        if !cube.is_solved() {
+            return Err(ServiceError::Invalid("solution does not solve the scramble.".into()));
+        }
+
+        Ok(cube_moves)
+    }
+
+    async fn generate_scramble_with_seed(&self, seed: Option<[u8; 32]>) -> Result<String, ServiceError> {
         let generator = self.generator.clone();
-        let scramble = tokio::task::spawn_blocking(move || {
+        self.run_solver(move || {
             let scramble_moves = match seed {
                 Some(seed) => {
-                    let mut rng = StdRng::seed_from_u64(seed);
+                    let mut rng = ChaCha20Rng::from_seed(seed);
                     generator.generate(&mut rng)
                 }
                 None => {
@@ -46,11 +127,32 @@ impl ScrambleService {
                     generator.generate(&mut rng)
                 },
             };
-            scramble_moves.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" ")
+            scramble_moves.map(|moves| moves_to_string(&moves)).map_err(scramble_failed)
         })
         .await
-        .expect("Scramble generation panicked.");
+    }
 
-        scramble
+    /// Runs `search` on a blocking thread once a solver permit is free, holding it until the search ends.
+    async fn run_solver<T: Send + 'static>(&self, search: impl FnOnce() -> Result<T, ServiceError> + Send + 'static) -> Result<T, ServiceError> {
+        let permit = self.solver_permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Solver permits should never be closed");
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            search()
+        })
+        .await
+        .map_err(|error| ServiceError::Internal(format!("solver search failed: {}.", error)))?
     }
-}
\ No newline at end of file
+}
+
+fn scramble_failed(error: KociembaError) -> ServiceError {
+    ServiceError::Internal(format!("scramble could not be generated: {}.", error))
+}
+
+fn moves_to_string(moves: &[impl fmt::Display]) -> String {
+    moves.iter().map(|m| m.to_string()).collect::<Vec<String>>().join(" ")
+}
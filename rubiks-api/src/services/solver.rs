@@ -0,0 +1,92 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use rubiks::cube::{Cube, CubeMove};
+use rubiks::solvers::kociemba::{KociembaError, KociembaSolver, MIN_MAX_SIZE};
+use rubiks::solvers::pochmann::PochmannSolver;
+use tokio::sync::Semaphore;
+use crate::domain::solutions::{Solution, SolverKind};
+use crate::services::error::ServiceError;
+
+/// Searches get slow past this length, and no cube needs more than 20 moves.
+pub const MAX_SOLUTION_LENGTH: usize = 30;
+
+/// Runs solves on blocking threads, a few at a time so heavy searches can't starve the runtime.
+#[derive(Clone)]
+pub struct SolverService {
+    /// Shared with `ScrambleService`, so scrambles and solves take turns
+    permits: Arc<Semaphore>,
+    /// Longest search a request can ask for, in seconds
+    max_timeout: f32,
+}
+
+impl SolverService {
+    pub fn new(permits: Arc<Semaphore>, max_timeout: f32) -> Self {
+        Self {
+            permits,
+            max_timeout,
+        }
+    }
+
+    pub fn max_timeout(&self) -> f32 {
+        self.max_timeout
+    }
+
+    /// Cube described by a facelet string like `UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB`.
+    pub fn cube_from_facelets(&self, facelets: &str) -> Result<Cube, ServiceError> {
+        Cube::from_facelets(facelets).ok_or_else(|| ServiceError::Invalid("'facelets' do not describe a solvable cube.".into()))
+    }
+
+    /// Solves `cube` in at most `max_length` moves, searching for `timeout` seconds at most.
+    /// A Kociemba search keeps going until it finds a first solution, even past the timeout,
+    /// and can't aim below `MIN_MAX_SIZE` moves, the shortest `max_length` of move solvers.
+    /// Pochmann solutions are not moves and ignore both limits.
+    pub async fn solve(&self, cube: Cube, kind: SolverKind, max_length: usize, timeout: f32) -> Result<Solution, ServiceError> {
+        let min_length = if kind == SolverKind::Pochmann { 1 } else { MIN_MAX_SIZE };
+        if !(min_length..=MAX_SOLUTION_LENGTH).contains(&max_length) {
+            return Err(ServiceError::Invalid(format!("max length must be between {} and {}.", min_length, MAX_SOLUTION_LENGTH)));
+        }
+        if !timeout.is_finite() || timeout <= 0.0 {
+            return Err(ServiceError::Invalid("timeout must be positive.".into()));
+        }
+
+        let timeout = timeout.min(self.max_timeout);
+        let solver = KociembaSolver { max_size: max_length, timeout };
+        let permit = self.permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ServiceError::Internal("solver permits are closed.".into()))?;
+
+        let solution = tokio::task::spawn_blocking(move || {
+            // Held until the search ends, even if the request is dropped before
+            let _permit = permit;
+            let start = Instant::now();
+            let steps = match kind {
+                SolverKind::Kociemba => moves(solver.solve(&cube)),
+                SolverKind::Shortest => {
+                    let deadline = start + Duration::from_secs_f32(timeout);
+                    moves(solver.solve_shortest_before(&cube, deadline))
+                },
+                SolverKind::Pochmann => {
+                    let (corners, edges) = PochmannSolver::default().targets(&cube);
+                    Ok(corners.iter().chain(&edges).map(|sticker| format!("{:?}", sticker)).collect())
+                },
+            };
+            steps.map(|steps| Solution { steps, time: start.elapsed() })
+        })
+        .await
+        .map_err(|error| ServiceError::Internal(format!("solution search failed: {}.", error)))??;
+
+        if kind != SolverKind::Pochmann && solution.steps.len() > max_length {
+            return Err(ServiceError::TimedOut(format!("no solution of at most {} moves was found in time.", max_length)));
+        }
+        Ok(solution)
+    }
+}
+
+/// Kociemba moves as strings, the cube being valid any failure is ours.
+fn moves(solution: Result<Vec<CubeMove>, KociembaError>) -> Result<Vec<String>, ServiceError> {
+    solution
+        .map(|moves| moves.iter().map(CubeMove::to_string).collect())
+        .map_err(|error| ServiceError::Internal(format!("solver failed: {}.", error)))
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use crate::domain::models::User;
+use crate::repositories::user::UsersRepository;
+use crate::services::error::ServiceError;
+
+/// Tokens stay valid for a week.
+const TOKEN_LIFETIME_SECONDS: u64 = 7 * 24 * 60 * 60;
+const MIN_PASSWORD_LENGTH: usize = 8;
+const MAX_USERNAME_LENGTH: usize = 250;
+const MAX_CLUB_LENGTH: usize = 100;
+const USERNAME_TAKEN: &str = "username is already taken.";
+const WRONG_CREDENTIALS: &str = "username or password is wrong.";
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    /// Username
+    sub: String,
+    exp: u64,
+}
+
+#[derive(Clone)]
+pub struct UsersService<R: UsersRepository> {
+    users_repository: R,
+    jwt_secret: Arc<Vec<u8>>,
+}
+
+impl<R: UsersRepository> UsersService<R> {
+    pub fn new(users_repository: R, jwt_secret: Vec<u8>) -> Self {
+        Self {
+            users_repository,
+            jwt_secret: Arc::new(jwt_secret),
+        }
+    }
+
+    pub async fn register(&self, username: String, password: String) -> Result<(), ServiceError> {
+        if username.trim().is_empty() {
+            return Err(ServiceError::Invalid("username is empty.".into()));
+        }
+        if username.len() > MAX_USERNAME_LENGTH {
+            return Err(ServiceError::Invalid(format!("username is longer than {} characters.", MAX_USERNAME_LENGTH)));
+        }
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(ServiceError::Invalid(format!("password is shorter than {} characters.", MIN_PASSWORD_LENGTH)));
+        }
+        if self.users_repository.fetch_by_username(&username).await?.is_some() {
+            return Err(ServiceError::Conflict(USERNAME_TAKEN.into()));
+        }
+
+        // Hashing is slow on purpose, keep it off the async workers
+        let password_hash = tokio::task::spawn_blocking(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map(|hash| hash.to_string())
+                .map_err(|error| ServiceError::Internal(error.to_string()))
+        })
+        .await
+        .expect("Password hashing panicked.")?;
+
+        // Another registration may have taken the username since
+        self.users_repository
+            .insert(User { username, password_hash, country: None, club: None })
+            .await
+            .map_err(|error| ServiceError::with_conflict(error, USERNAME_TAKEN))
+    }
+
+    /// Returns a token for the user if the password is right.
+    pub async fn login(&self, username: String, password: String) -> Result<String, ServiceError> {
+        let user = self.users_repository
+            .fetch_by_username(&username)
+            .await?
+            .ok_or_else(|| ServiceError::Unauthorized(WRONG_CREDENTIALS.into()))?;
+
+        let is_valid = tokio::task::spawn_blocking(move || {
+            PasswordHash::new(&user.password_hash)
+                .is_ok_and(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+        })
+        .await
+        .expect("Password verification panicked.");
+
+        if !is_valid {
+            return Err(ServiceError::Unauthorized(WRONG_CREDENTIALS.into()));
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time should be after epoch.").as_secs();
+        let claims = Claims {
+            sub: username,
+            exp: now + TOKEN_LIFETIME_SECONDS,
+        };
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(&self.jwt_secret))
+            .map_err(|error| ServiceError::Internal(error.to_string()))
+    }
+
+    /// `country` is an ISO 3166-1 alpha-2 code, `None` clears a field.
+    pub async fn update_profile(&self, username: &str, country: Option<String>, club: Option<String>) -> Result<(), ServiceError> {
+        let country = country.map(|country| country.to_ascii_uppercase());
+        if country.as_ref().is_some_and(|country| country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase())) {
+            return Err(ServiceError::Invalid("country must be a two letter code.".into()));
+        }
+
+        let club = club.map(|club| club.trim().to_string());
+        if club.as_ref().is_some_and(|club| club.is_empty() || club.chars().count() > MAX_CLUB_LENGTH) {
+            return Err(ServiceError::Invalid(format!("club must be between 1 and {} characters.", MAX_CLUB_LENGTH)));
+        }
+
+        self.users_repository.update_profile(username, country, club).await.map_err(ServiceError::from)
+    }
+
+    /// Returns the username the token was issued to.
+    pub fn authenticate(&self, token: &str) -> Result<String, ServiceError> {
+        decode::<Claims>(token, &DecodingKey::from_secret(&self.jwt_secret), &Validation::default())
+            .map(|data| data.claims.sub)
+            .map_err(|_| ServiceError::Unauthorized("token is invalid or expired.".into()))
+    }
+}
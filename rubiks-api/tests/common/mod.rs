@@ -0,0 +1,152 @@
+//! Databases for the integration tests, every test getting an empty and migrated one.
+//!
+//! Postgres tests start a throwaway cluster with `initdb` and `postgres` from `PATH` (or `PG_BIN`),
+//! once per test binary. Set `TEST_POSTGRES_URL` to an admin connection to use a running server instead.
+
+// Each test binary uses part of the helpers
+#![allow(dead_code)]
+
+use rubiks_api::database::migration::run_migrations;
+use rubiks_api::database::pool::{DatabasePool, Repositories};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+
+/// Repositories over a private in-memory SQLite database.
+pub async fn sqlite_repositories() -> Repositories {
+    migrated_repositories(DatabasePool::Sqlite(sqlite_pool().await)).await
+}
+
+/// Private in-memory SQLite database, left empty.
+pub async fn sqlite_pool() -> SqlitePool {
+    // An in-memory database lives as long as its connection, so the pool keeps exactly one
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Cannot open in-memory SQLite database.")
+}
+
+/// Repositories over a new database of the test Postgres cluster.
+#[cfg(feature = "postgres")]
+pub async fn postgres_repositories() -> Repositories {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use sqlx::postgres::PgPoolOptions;
+
+    static NEXT_DATABASE: AtomicU32 = AtomicU32::new(0);
+
+    let admin_url = cluster::admin_url();
+    let database = format!("rubiks_test_{}_{}", std::process::id(), NEXT_DATABASE.fetch_add(1, Ordering::SeqCst));
+    // The server accepts connections a little before it accepts logins
+    let mut attempts = 0;
+    let admin = loop {
+        match PgPoolOptions::new().max_connections(1).connect(&admin_url).await {
+            Ok(admin) => break admin,
+            Err(_) if attempts < 50 => attempts += 1,
+            Err(error) => panic!("Cannot connect to the test Postgres server: {}", error),
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    };
+    sqlx::query(&format!("CREATE DATABASE {};", database))
+        .execute(&admin)
+        .await
+        .expect("Cannot create test database.");
+    admin.close().await;
+
+    let (server, _) = admin_url.rsplit_once('/').expect("Postgres URL should name a database");
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&format!("{}/{}", server, database))
+        .await
+        .expect("Cannot connect to test database.");
+
+    migrated_repositories(DatabasePool::Postgres(pool)).await
+}
+
+async fn migrated_repositories(pool: DatabasePool) -> Repositories {
+    run_migrations(&pool).await.expect("Migrations should apply to an empty database");
+    Repositories::new(&pool)
+}
+
+#[cfg(feature = "postgres")]
+mod cluster {
+    use std::io::ErrorKind;
+    use std::net::{TcpListener, TcpStream};
+    use std::path::Path;
+    use std::process::{Child, Command, Stdio};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+
+    const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Server process, kept so its stdin stays open until the test binary exits.
+    static SERVER: Mutex<Option<Child>> = Mutex::new(None);
+    static ADMIN_URL: OnceLock<String> = OnceLock::new();
+
+    pub fn admin_url() -> String {
+        ADMIN_URL
+            .get_or_init(|| std::env::var("TEST_POSTGRES_URL").unwrap_or_else(|_| start()))
+            .clone()
+    }
+
+    /// Starts a cluster in a temporary directory on a free port, trusting local connections.
+    fn start() -> String {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Cannot find a free port")
+            .port();
+        let data_dir = std::env::temp_dir().join(format!("rubiks-api-postgres-{}", std::process::id()));
+        let bin = |name: &str| match std::env::var("PG_BIN") {
+            Ok(dir) => Path::new(&dir).join(name).display().to_string(),
+            Err(_) => name.to_string(),
+        };
+
+        // The shell stops the server and removes the cluster once stdin closes, that is when the tests exit
+        let script = format!(
+            "rm -rf {dir} && {initdb} -D {dir} -U rubiks --auth=trust > /dev/null && \
+            {{ {postgres} -D {dir} -p {port} -k {dir} -c listen_addresses=127.0.0.1 -c fsync=off > {dir}.log 2>&1 & }} ; \
+            pid=$! ; read _ ; kill $pid ; wait $pid ; rm -rf {dir} {dir}.log",
+            dir = data_dir.display(),
+            initdb = bin("initdb"),
+            postgres = bin("postgres"),
+            port = port,
+        );
+
+        // Postgres refuses to run as root, the cluster then belongs to the `postgres` user
+        let mut command = if is_root() {
+            let mut command = Command::new("runuser");
+            command.args(["-u", "postgres", "--", "sh", "-c", &script]);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.args(["-c", &script]);
+            command
+        };
+        let server = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("Cannot start the test Postgres cluster, install Postgres or set TEST_POSTGRES_URL");
+        *SERVER.lock().unwrap() = Some(server);
+
+        let started = Instant::now();
+        loop {
+            match TcpStream::connect(("127.0.0.1", port)) {
+                Ok(_) => break,
+                Err(error) if error.kind() == ErrorKind::ConnectionRefused && started.elapsed() < STARTUP_TIMEOUT => {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(error) => panic!("Test Postgres cluster did not start: {}", error),
+            }
+        }
+
+        format!("postgres://rubiks@127.0.0.1:{}/postgres", port)
+    }
+
+    fn is_root() -> bool {
+        Command::new("id")
+            .arg("-u")
+            .output()
+            .is_ok_and(|output| output.stdout.trim_ascii() == b"0")
+    }
+}
@@ -0,0 +1,406 @@
+mod common;
+
+use std::sync::Arc;
+use chrono::{Datelike, Days, NaiveDate};
+use rubiks_api::database::memory::daily_solves::InMemoryDailySolvesRepository;
+use rubiks_api::database::migration::run_migrations;
+use rubiks_api::database::pool::{DatabasePool, Repositories};
+use rubiks_api::domain::leaderboards::{LeaderboardFilter, Ranking};
+use rubiks_api::domain::models::{DailyRound, DailyScramble, DailySolve, FmcSolve, User};
+use rubiks_api::domain::races::{FinishedRace, RaceStanding};
+use rubiks_api::domain::results::{Attempt, Penalty, SolveFormat};
+use rubiks_api::repositories::error::RepositoryError;
+
+/// Runs every listed test against SQLite, and against Postgres when the feature is enabled.
+macro_rules! backend_tests {
+    ($($test:ident),* $(,)?) => {
+        mod sqlite {
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test(crate::common::sqlite_repositories().await).await;
+                }
+            )*
+        }
+
+        #[cfg(feature = "postgres")]
+        mod postgres {
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test(crate::common::postgres_repositories().await).await;
+                }
+            )*
+        }
+    };
+}
+
+backend_tests!(
+    daily_solves_are_ordered_by_time,
+    duplicate_daily_solve_is_rejected,
+    user_history_and_stats,
+    rolling_averages_drop_best_and_worst,
+    leaderboard_shares_ranks_on_ties,
+    leaderboard_filters_by_participation_and_country,
+    daily_scramble_is_kept_once_stored,
+    daily_summaries_name_the_winner,
+    round_scrambles_are_kept_in_attempt_order,
+    daily_rounds_rank_dnf_last,
+    default_events_are_migrated,
+    users_update_their_profile,
+    fmc_solves_are_ordered_by_move_count,
+    finished_races_keep_their_standings,
+);
+
+/// The in-memory daily solves rank like the SQL ones, profile filters aside.
+mod memory {
+    use super::*;
+
+    async fn repositories() -> Repositories {
+        Repositories {
+            daily_solves: Arc::new(InMemoryDailySolvesRepository::new()),
+            ..crate::common::sqlite_repositories().await
+        }
+    }
+
+    #[tokio::test]
+    async fn daily_solves_are_ordered_by_time() {
+        super::daily_solves_are_ordered_by_time(repositories().await).await;
+    }
+
+    #[tokio::test]
+    async fn duplicate_daily_solve_is_rejected() {
+        super::duplicate_daily_solve_is_rejected(repositories().await).await;
+    }
+
+    #[tokio::test]
+    async fn user_history_and_stats() {
+        super::user_history_and_stats(repositories().await).await;
+    }
+
+    #[tokio::test]
+    async fn rolling_averages_drop_best_and_worst() {
+        super::rolling_averages_drop_best_and_worst(repositories().await).await;
+    }
+
+    #[tokio::test]
+    async fn leaderboard_shares_ranks_on_ties() {
+        super::leaderboard_shares_ranks_on_ties(repositories().await).await;
+    }
+}
+
+/// Databases created by `ensure_db` before versioned migrations only have the released table.
+#[tokio::test]
+async fn released_sqlite_database_is_migrated() {
+    let pool = crate::common::sqlite_pool().await;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS daily_solves (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,\
+            username VARCHAR(250) NOT NULL,\
+            date INTEGER NOT NULL,\
+            time INTEGER NOT NULL,\
+            CONSTRAINT one_solve_per_day_per_user UNIQUE (username, date)\
+        );"
+    )
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO daily_solves (username, date, time) VALUES ('alice', ?1, 9000);")
+        .bind(day(0).num_days_from_ce())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let pool = DatabasePool::Sqlite(pool);
+    run_migrations(&pool).await.expect("Migrations should upgrade the released schema");
+    let repositories = Repositories::new(&pool);
+
+    repositories.daily_solves.insert(DailySolve { move_count: Some(42), verified: true, ..solve("bob", day(0), 8000) }).await.unwrap();
+    let solves = repositories.daily_solves.fetch_all_by_date(day(0)).await.unwrap();
+    let stored: Vec<_> = solves.iter().map(|solve| (solve.username.as_str(), solve.move_count, solve.verified)).collect();
+    assert_eq!(stored, [("bob", Some(42), true), ("alice", None, false)]);
+
+    default_events_are_migrated(repositories).await;
+}
+
+fn day(offset: u64) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, 1).unwrap() + Days::new(offset)
+}
+
+fn solve(username: &str, date: NaiveDate, time: i32) -> DailySolve {
+    DailySolve {
+        username: username.to_string(),
+        date,
+        time,
+        move_count: None,
+        verified: false,
+    }
+}
+
+fn filter(to: NaiveDate) -> LeaderboardFilter {
+    LeaderboardFilter {
+        from: None,
+        to,
+        ranking: Ranking::Single,
+        average_of: 5,
+        min_solves: 1,
+        country: None,
+        club: None,
+    }
+}
+
+async fn daily_solves_are_ordered_by_time(repositories: Repositories) {
+    for (username, time) in [("bob", 12000), ("alice", 9000), ("carol", 15000)] {
+        repositories.daily_solves.insert(solve(username, day(0), time)).await.unwrap();
+    }
+    repositories.daily_solves.insert(solve("alice", day(1), 1000)).await.unwrap();
+
+    let solves = repositories.daily_solves.fetch_all_by_date(day(0)).await.unwrap();
+    let order: Vec<_> = solves.iter().map(|solve| (solve.username.as_str(), solve.time)).collect();
+    assert_eq!(order, [("alice", 9000), ("bob", 12000), ("carol", 15000)]);
+}
+
+async fn duplicate_daily_solve_is_rejected(repositories: Repositories) {
+    repositories.daily_solves.insert(solve("alice", day(0), 9000)).await.unwrap();
+
+    let error = repositories.daily_solves.insert(solve("alice", day(0), 8000)).await.unwrap_err();
+    assert!(matches!(error, RepositoryError::Conflict(_)), "{}", error);
+    let solves = repositories.daily_solves.fetch_all_by_date(day(0)).await.unwrap();
+    assert_eq!(solves.len(), 1);
+    assert_eq!(solves[0].time, 9000);
+}
+
+async fn user_history_and_stats(repositories: Repositories) {
+    // Days 0 to 4 then 6 and 7, the current streak counting from yesterday
+    let times = [(0, 10000), (1, 11000), (2, 9000), (3, 12000), (4, 10000), (6, 8000), (7, 13000)];
+    for (offset, time) in times {
+        repositories.daily_solves.insert(solve("alice", day(offset), time)).await.unwrap();
+    }
+    repositories.daily_solves.insert(solve("bob", day(6), 7000)).await.unwrap();
+
+    let history = repositories.daily_solves.fetch_by_username("alice", 3, 1).await.unwrap();
+    let dates: Vec<_> = history.iter().map(|solve| solve.date).collect();
+    assert_eq!(dates, [day(6), day(4), day(3)]);
+    assert_eq!(repositories.daily_solves.count_by_username("alice").await.unwrap(), 7);
+
+    let stats = repositories.daily_solves.fetch_user_stats("alice", day(8)).await.unwrap();
+    assert_eq!(stats.solve_count, 7);
+    assert_eq!(stats.best_single, Some(8000));
+    assert_eq!(stats.mean, Some(10429));
+    // Second to sixth solves, dropping 8000 and 12000
+    assert_eq!(stats.best_ao5, Some(10000));
+    assert_eq!(stats.best_ao12, None);
+    assert_eq!(stats.current_streak, 2);
+    assert_eq!(stats.longest_streak, 5);
+
+    let ranks = repositories.daily_solves.fetch_rank_history("alice", 2).await.unwrap();
+    let ranks: Vec<_> = ranks.iter().map(|rank| (rank.date, rank.rank, rank.participants)).collect();
+    assert_eq!(ranks, [(day(7), 1, 1), (day(6), 2, 2)]);
+}
+
+async fn rolling_averages_drop_best_and_worst(repositories: Repositories) {
+    for (offset, time) in [(0, 10000), (1, 20000), (2, 30000), (3, 40000)] {
+        repositories.daily_solves.insert(solve("alice", day(offset), time)).await.unwrap();
+    }
+
+    let averages = repositories.daily_solves.fetch_rolling_averages("alice", 3, 10).await.unwrap();
+    let averages: Vec<_> = averages.iter().map(|average| (average.date, average.average)).collect();
+    assert_eq!(averages, [(day(2), 20000), (day(3), 30000)]);
+
+    let latest = repositories.daily_solves.fetch_rolling_averages("alice", 3, 1).await.unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].date, day(3));
+}
+
+async fn leaderboard_shares_ranks_on_ties(repositories: Repositories) {
+    for (username, time) in [("carol", 9000), ("alice", 9000), ("bob", 8000), ("dave", 12000)] {
+        repositories.daily_solves.insert(solve(username, day(0), time)).await.unwrap();
+    }
+
+    let entries = repositories.daily_solves.fetch_leaderboard(&filter(day(0)), 10, 0).await.unwrap();
+    let ranks: Vec<_> = entries.iter().map(|entry| (entry.rank, entry.username.as_str(), entry.best_single)).collect();
+    assert_eq!(ranks, [(1, "bob", 8000), (2, "alice", 9000), (2, "carol", 9000), (4, "dave", 12000)]);
+
+    let page = repositories.daily_solves.fetch_leaderboard(&filter(day(0)), 2, 2).await.unwrap();
+    let usernames: Vec<_> = page.iter().map(|entry| entry.username.as_str()).collect();
+    assert_eq!(usernames, ["carol", "dave"]);
+    assert_eq!(repositories.daily_solves.count_leaderboard(&filter(day(0))).await.unwrap(), 4);
+}
+
+async fn leaderboard_filters_by_participation_and_country(repositories: Repositories) {
+    for username in ["alice", "bob"] {
+        repositories.users.insert(User {
+            username: username.to_string(),
+            password_hash: String::new(),
+            country: None,
+            club: None,
+        }).await.unwrap();
+    }
+    repositories.users.update_profile("alice", Some("FR".to_string()), None).await.unwrap();
+    for (offset, time) in [(0, 10000), (1, 11000), (2, 12000)] {
+        repositories.daily_solves.insert(solve("alice", day(offset), time)).await.unwrap();
+    }
+    repositories.daily_solves.insert(solve("bob", day(2), 5000)).await.unwrap();
+
+    let regulars = LeaderboardFilter { min_solves: 2, ..filter(day(2)) };
+    let entries = repositories.daily_solves.fetch_leaderboard(&regulars, 10, 0).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].username.as_str(), entries[0].solve_count), ("alice", 3));
+
+    let french = LeaderboardFilter { country: Some("FR".to_string()), ..filter(day(2)) };
+    assert_eq!(repositories.daily_solves.count_leaderboard(&french).await.unwrap(), 1);
+
+    let averages = LeaderboardFilter { ranking: Ranking::Average, average_of: 3, ..filter(day(2)) };
+    let entries = repositories.daily_solves.fetch_leaderboard(&averages, 10, 0).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].best_average, Some(11000));
+
+    let recent = LeaderboardFilter { from: Some(day(1)), to: day(1), ..filter(day(2)) };
+    let entries = repositories.daily_solves.fetch_leaderboard(&recent, 10, 0).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].best_single, 11000);
+}
+
+async fn daily_scramble_is_kept_once_stored(repositories: Repositories) {
+    for scramble in ["R U R' U'", "F B"] {
+        repositories.daily_scrambles.insert_if_absent(DailyScramble {
+            date: day(0),
+            scramble: scramble.to_string(),
+            version: 1,
+            fmc_best_length: None,
+        }).await.unwrap();
+    }
+    repositories.daily_scrambles.update_fmc_best_length(day(0), 18).await.unwrap();
+
+    let stored = repositories.daily_scrambles.fetch_by_date(day(0)).await.unwrap().unwrap();
+    assert_eq!(stored.scramble, "R U R' U'");
+    assert_eq!(stored.fmc_best_length, Some(18));
+    assert!(repositories.daily_scrambles.fetch_by_date(day(1)).await.unwrap().is_none());
+}
+
+async fn daily_summaries_name_the_winner(repositories: Repositories) {
+    for offset in 0..3 {
+        repositories.daily_scrambles.insert_if_absent(DailyScramble {
+            date: day(offset),
+            scramble: "R".to_string(),
+            version: 1,
+            fmc_best_length: None,
+        }).await.unwrap();
+    }
+    repositories.daily_solves.insert(solve("alice", day(0), 9000)).await.unwrap();
+    repositories.daily_solves.insert(solve("bob", day(0), 8000)).await.unwrap();
+
+    let summaries = repositories.daily_scrambles.fetch_summaries_before(day(2), 10, 0).await.unwrap();
+    let dates: Vec<_> = summaries.iter().map(|summary| summary.date).collect();
+    assert_eq!(dates, [day(1), day(0)]);
+    assert!(summaries[0].winner.is_none());
+    assert_eq!(summaries[1].participants, 2);
+    assert_eq!(summaries[1].winner.as_ref().map(|winner| winner.username.as_str()), Some("bob"));
+    assert_eq!(repositories.daily_scrambles.count_before(day(2)).await.unwrap(), 2);
+}
+
+async fn round_scrambles_are_kept_in_attempt_order(repositories: Repositories) {
+    let first: Vec<String> = ["R", "U", "F"].map(String::from).to_vec();
+    let second: Vec<String> = ["L", "D", "B"].map(String::from).to_vec();
+    repositories.daily_scrambles.insert_round_scrambles_if_absent("333", day(0), first.clone(), 1).await.unwrap();
+    repositories.daily_scrambles.insert_round_scrambles_if_absent("333", day(0), second, 1).await.unwrap();
+
+    assert_eq!(repositories.daily_scrambles.fetch_round_scrambles("333", day(0)).await.unwrap(), first);
+    assert!(repositories.daily_scrambles.fetch_round_scrambles("222", day(0)).await.unwrap().is_empty());
+}
+
+async fn daily_rounds_rank_dnf_last(repositories: Repositories) {
+    let round = |username: &str, times: [i32; 5], dnf: bool| {
+        let mut attempts: Vec<Attempt> = times.iter().map(|&time| Attempt { time, penalty: Penalty::None }).collect();
+        if dnf {
+            attempts[0].penalty = Penalty::Dnf;
+            attempts[1].penalty = Penalty::Dnf;
+        }
+        DailyRound {
+            event: "333".to_string(),
+            username: username.to_string(),
+            date: day(0),
+            format: SolveFormat::Ao5,
+            average: SolveFormat::Ao5.average(&attempts),
+            best: SolveFormat::Ao5.best(&attempts),
+            attempts,
+        }
+    };
+    repositories.daily_rounds.insert(round("dnf", [5000, 5000, 5000, 5000, 5000], true)).await.unwrap();
+    repositories.daily_rounds.insert(round("slow", [12000, 12000, 12000, 12000, 12000], false)).await.unwrap();
+    repositories.daily_rounds.insert(round("fast", [9000, 10000, 11000, 12000, 8000], false)).await.unwrap();
+
+    let rounds = repositories.daily_rounds.fetch_all_by_date("333", day(0)).await.unwrap();
+    let usernames: Vec<_> = rounds.iter().map(|round| round.username.as_str()).collect();
+    assert_eq!(usernames, ["fast", "slow", "dnf"]);
+    assert_eq!(rounds[0].average, Some(10000));
+    assert_eq!(rounds[2].attempts[0].penalty, Penalty::Dnf);
+}
+
+async fn default_events_are_migrated(repositories: Repositories) {
+    let events = repositories.events.fetch_all().await.unwrap();
+    let ids: Vec<_> = events.iter().map(|event| event.id.as_str()).collect();
+    assert_eq!(ids, ["333", "222", "333oh", "333bf", "333ll", "333f2l"]);
+
+    let blindfolded = repositories.events.fetch_by_id("333bf").await.unwrap().unwrap();
+    assert_eq!(blindfolded.format, SolveFormat::Bo3);
+    assert!(repositories.events.fetch_by_id("444").await.unwrap().is_none());
+}
+
+async fn users_update_their_profile(repositories: Repositories) {
+    let user = User {
+        username: "alice".to_string(),
+        password_hash: "hash".to_string(),
+        country: None,
+        club: None,
+    };
+    repositories.users.insert(user).await.unwrap();
+    repositories.users.update_profile("alice", Some("NZ".to_string()), Some("Cubers".to_string())).await.unwrap();
+
+    let stored = repositories.users.fetch_by_username("alice").await.unwrap().unwrap();
+    assert_eq!(stored.password_hash, "hash");
+    assert_eq!(stored.country.as_deref(), Some("NZ"));
+    assert_eq!(stored.club.as_deref(), Some("Cubers"));
+    assert!(repositories.users.fetch_by_username("bob").await.unwrap().is_none());
+}
+
+async fn fmc_solves_are_ordered_by_move_count(repositories: Repositories) {
+    for (username, move_count) in [("alice", 30), ("bob", 25), ("carol", 30)] {
+        repositories.fmc_solves.insert(FmcSolve {
+            username: username.to_string(),
+            date: day(0),
+            solution: "R".to_string(),
+            move_count,
+        }).await.unwrap();
+    }
+
+    let solves = repositories.fmc_solves.fetch_all_by_date(day(0)).await.unwrap();
+    let usernames: Vec<_> = solves.iter().map(|solve| solve.username.as_str()).collect();
+    assert_eq!(usernames, ["bob", "alice", "carol"]);
+}
+
+async fn finished_races_keep_their_standings(repositories: Repositories) {
+    let attempt = |time, penalty| Some(Attempt { time, penalty });
+    let dnf = Attempt { time: 0, penalty: Penalty::Dnf };
+    let race = FinishedRace {
+        id: "ABC234".to_string(),
+        event: "333".to_string(),
+        owner: "bob".to_string(),
+        date: day(0),
+        round_count: 2,
+        standings: vec![
+            RaceStanding { rank: 1, ..RaceStanding::new("alice".to_string(), vec![attempt(9000, Penalty::None), attempt(8000, Penalty::PlusTwo)], 2) },
+            RaceStanding { rank: 2, ..RaceStanding::new("bob".to_string(), vec![attempt(12000, Penalty::None), None], 2) },
+        ],
+    };
+    repositories.races.insert(race.clone()).await.unwrap();
+
+    let stored = repositories.races.fetch_by_id("ABC234").await.unwrap().unwrap();
+    assert_eq!(stored.standings[0], race.standings[0]);
+    // A missing attempt is stored as a DNF
+    assert_eq!(stored.standings[1].attempts, [attempt(12000, Penalty::None), Some(dnf)]);
+    assert_eq!((stored.owner.as_str(), stored.date, stored.round_count), ("bob", day(0), 2));
+
+    assert!(matches!(repositories.races.insert(race).await, Err(RepositoryError::Conflict(_))));
+    assert!(repositories.races.fetch_by_id("XYZ789").await.unwrap().is_none());
+}
@@ -0,0 +1,1013 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use axum::body::{to_bytes, Body};
+use axum::extract::ConnectInfo;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use futures_util::{SinkExt, StreamExt};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use rubiks::cube::{Cube, CubeMove};
+use rubiks::notation::NotationMove;
+use rubiks_api::api::clock::Clock;
+use rubiks_api::api::routes::build_router;
+use rubiks_api::api::state::AppState;
+use rubiks_api::config::settings::{CorsOrigins, RateLimit};
+use rubiks_api::database::events::SqliteEventsRepository;
+use rubiks_api::database::memory::daily_solves::InMemoryDailySolvesRepository;
+use rubiks_api::services::daily_rounds::DailyRoundsService;
+use rubiks_api::services::daily_scrambles::DailyScramblesService;
+use rubiks_api::services::daily_solves::DailySolvesService;
+use rubiks_api::services::events::EventsService;
+use rubiks_api::services::fmc_solves::FmcSolvesService;
+use rubiks_api::services::races::RacesService;
+use rubiks_api::services::scramble_pool::ScramblePool;
+use rubiks_api::services::scramble_service::ScrambleService;
+use rubiks_api::services::solver::SolverService;
+use rubiks_api::services::users::UsersService;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tokio::sync::Semaphore;
+use tokio_tungstenite::tungstenite::Message;
+use tower::ServiceExt;
+
+/// Clock standing still until moved by the test.
+struct FakeClock(Mutex<DateTime<Utc>>);
+
+impl FakeClock {
+    fn advance(&self, duration: Duration) {
+        *self.0.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+struct TestApp {
+    router: Router,
+    clock: Arc<FakeClock>,
+    reset_timezone: Tz,
+    scramble_pool: ScramblePool,
+}
+
+/// Router over in-memory daily solves and SQLite for the rest, at noon UTC on 2026-10-19.
+async fn test_app() -> TestApp {
+    test_app_at(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap(), Tz::UTC).await
+}
+
+async fn test_app_at(now: DateTime<Utc>, reset_timezone: Tz) -> TestApp {
+    let clock = Arc::new(FakeClock(Mutex::new(now)));
+    let state = test_state(clock.clone(), reset_timezone).await;
+    TestApp::new(state, clock)
+}
+
+/// Inactivity after which test race rooms are closed.
+const RACE_IDLE_TIMEOUT: Duration = Duration::minutes(30);
+
+async fn test_state(clock: Arc<FakeClock>, reset_timezone: Tz) -> AppState {
+    let repositories = common::sqlite_repositories().await;
+    let solver_permits = Arc::new(Semaphore::new(2));
+    let scramble_service = ScrambleService::new(5.0, solver_permits.clone());
+    AppState {
+        scramble_service: scramble_service.clone(),
+        scramble_pool: ScramblePool::start(scramble_service.clone(), 2),
+        daily_scramble_service: DailyScramblesService::new(scramble_service.clone(), repositories.daily_scrambles, "salt".to_string()),
+        daily_solve_service: DailySolvesService::new(Arc::new(InMemoryDailySolvesRepository::new()), false),
+        daily_round_service: DailyRoundsService::new(repositories.daily_rounds),
+        events_service: EventsService::new(repositories.events),
+        fmc_solve_service: FmcSolvesService::new(repositories.fmc_solves),
+        users_service: UsersService::new(repositories.users, b"secret".to_vec()),
+        solver_service: SolverService::new(solver_permits, 5.0),
+        races_service: RacesService::start(repositories.races, scramble_service, clock.clone(), RACE_IDLE_TIMEOUT),
+        clock,
+        reset_timezone,
+        scramble_svg_url: None,
+    }
+}
+
+/// Address of the client of every test request.
+const TEST_PEER: ([u8; 4], u16) = ([127, 0, 0, 1], 40000);
+
+impl TestApp {
+    /// App with a rate limit no test reaches.
+    fn new(state: AppState, clock: Arc<FakeClock>) -> Self {
+        Self::with_rate_limit(state, clock, RateLimit { per_minute: 6000, burst: 1000 })
+    }
+
+    fn with_rate_limit(state: AppState, clock: Arc<FakeClock>, rate_limit: RateLimit) -> Self {
+        Self {
+            reset_timezone: state.reset_timezone,
+            scramble_pool: state.scramble_pool.clone(),
+            // Nothing is served long enough to need the rate limit cleanup
+            router: build_router(state, &CorsOrigins::Any, rate_limit).0,
+            clock,
+        }
+    }
+
+    async fn request(&self, method: Method, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, String) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .extension(ConnectInfo(SocketAddr::from(TEST_PEER)));
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let body = match body {
+            Some(body) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+
+        let response = self.router.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    async fn get(&self, uri: &str) -> (StatusCode, String) {
+        self.request(Method::GET, uri, None, None).await
+    }
+
+    async fn get_json(&self, uri: &str) -> Value {
+        let (status, body) = self.get(uri).await;
+        assert_eq!(status, StatusCode::OK, "GET {} failed: {}", uri, body);
+        serde_json::from_str(&body).unwrap()
+    }
+
+    async fn post(&self, uri: &str, token: Option<&str>, body: Value) -> (StatusCode, String) {
+        self.request(Method::POST, uri, token, Some(body)).await
+    }
+
+    /// Registers `username` and returns a token for them.
+    async fn login(&self, username: &str) -> String {
+        let credentials = json!({ "username": username, "password": "correct horse" });
+        let (status, body) = self.post("/users", None, credentials.clone()).await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+        let (status, body) = self.post("/auth/login", None, credentials).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        serde_json::from_str::<Value>(&body).unwrap()["token"].as_str().unwrap().to_string()
+    }
+
+    async fn post_daily(&self, token: &str, time: i32) -> (StatusCode, String) {
+        self.post("/daily/scramble", Some(token), json!({ "time": time })).await
+    }
+
+    /// Moves of the scramble served at `uri`.
+    async fn get_scramble(&self, uri: &str) -> String {
+        self.get_json(uri).await["scramble"].as_str().unwrap().to_string()
+    }
+
+    /// Status and problem body of a failed request.
+    async fn request_problem(&self, method: Method, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, Value) {
+        let (status, body) = self.request(method, uri, token, body).await;
+        let problem: Value = serde_json::from_str(&body).unwrap_or_else(|_| panic!("{} is not a problem body", body));
+        assert_eq!(problem["status"], json!(status.as_u16()));
+        (status, problem)
+    }
+
+    fn today(&self) -> NaiveDate {
+        self.clock.now().with_timezone(&self.reset_timezone).date_naive()
+    }
+
+    /// Serves the router on a free local port, for the WebSocket routes, and returns its address.
+    async fn serve(&self) -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let service = self.router.clone().into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(axum::serve(listener, service).into_future());
+        address
+    }
+}
+
+type RaceSocket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn connect_race(address: SocketAddr, id: &str, token: Option<&str>) -> RaceSocket {
+    let mut url = format!("ws://{}/races/{}/live", address, id);
+    if let Some(token) = token {
+        url.push_str(&format!("?token={}", token));
+    }
+    tokio_tungstenite::connect_async(url).await.unwrap().0
+}
+
+/// Next JSON message of the race, `None` once the server closed the connection.
+async fn next_race_message(socket: &mut RaceSocket) -> Option<Value> {
+    loop {
+        match socket.next().await? {
+            Ok(Message::Text(text)) => return Some(serde_json::from_str(text.as_str()).unwrap()),
+            Ok(Message::Close(_)) | Err(_) => return None,
+            Ok(_) => {},
+        }
+    }
+}
+
+async fn send_race_command(socket: &mut RaceSocket, command: Value) {
+    socket.send(Message::Text(command.to_string().into())).await.unwrap();
+}
+
+/// Moves undoing `scramble`.
+fn inverse(scramble: &str) -> String {
+    NotationMove::parse_array(scramble)
+        .unwrap()
+        .iter()
+        .rev()
+        .map(|notation_move| notation_move.inverted().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn usernames(leaderboard: &Value) -> Vec<&str> {
+    leaderboard["solves"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|solve| solve["username"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn random_scramble_does_not_leave_the_cube_solved() {
+    let app = test_app().await;
+    wait_until_full(&app.scramble_pool, 2).await;
+
+    let scramble = app.get_json("/cube/scramble").await;
+    let moves = scramble["scramble"].as_str().unwrap();
+    assert!(NotationMove::parse_array(moves).is_some_and(|moves| !moves.is_empty()), "{}", scramble);
+    assert_eq!(scramble["moves"].as_array().unwrap().len(), moves.split(' ').count());
+    assert_eq!(scramble["puzzle"], json!("3x3x3"));
+    assert_eq!(scramble["generator_version"], json!(1));
+    assert!(scramble.get("svg_url").is_none());
+
+    let solved = app.get_json(&format!("/cube/solved?moves={}", moves.replace(' ', "%20").replace('\'', "%27"))).await;
+    assert_eq!(solved, json!({ "solved": false }));
+}
+
+#[tokio::test]
+async fn solved_check_applies_the_moves() {
+    let app = test_app().await;
+
+    assert_eq!(app.get_json("/cube/solved?moves=R%20U%20R%27%20U%27").await, json!({ "solved": false }));
+    assert_eq!(app.get_json("/cube/solved?moves=R%20U%20U%27%20R%27").await, json!({ "solved": true }));
+    assert_eq!(app.get("/cube/solved?moves=Q").await.0, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(app.get("/cube/solved").await.0, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn cube_state_describes_pieces_and_blindfolded_features() {
+    let app = test_app().await;
+    let t_perm = "R U R' U' R' F R2 U' R' U' R U R' F'";
+
+    let state = app.get_json(&format!("/cube/state?moves={}", t_perm.replace(' ', "%20").replace('\'', "%27"))).await;
+    let mut cube = Cube::solved();
+    cube.apply_moves(&CubeMove::parse_array(t_perm).unwrap());
+    assert_eq!(state["facelets"], json!(cube.to_facelets()));
+    assert_eq!(state["solved"], json!(false));
+    assert_eq!(state["solved_faces"], json!(["U", "D"]));
+
+    let pieces = state["pieces"].as_array().unwrap();
+    assert_eq!(pieces.len(), 20);
+    assert_eq!(pieces[0], json!({ "location": "URF", "piece": "UBR", "twist": 0, "solved": false }));
+    assert_eq!(pieces[8], json!({ "location": "UR", "piece": "UL", "twist": 0, "solved": false }));
+    assert_eq!(pieces[19], json!({ "location": "BR", "piece": "BR", "twist": 0, "solved": true }));
+
+    let bld = &state["bld"];
+    assert_eq!((bld["misplaced_corners"].clone(), bld["misplaced_edges"].clone()), (json!(2), json!(2)));
+    assert_eq!((bld["corner_targets"].clone(), bld["edge_targets"].clone()), (json!(3), json!(1)));
+    assert_eq!(bld["corner_cycle_breaks"], json!(1));
+    assert_eq!(bld["twisted_corners"], json!(0));
+    assert_eq!(bld["parity"], json!(true));
+
+    // Long sequences go in the body, and rotations or wide moves are allowed
+    let (status, body) = app.post("/cube/state", None, json!({ "moves": t_perm })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(serde_json::from_str::<Value>(&body).unwrap(), state);
+    let (status, body) = app.post("/cube/state", None, json!({ "moves": "x r M R' x'" })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["solved"], json!(true));
+}
+
+#[tokio::test]
+async fn cube_state_rejects_invalid_moves() {
+    let app = test_app().await;
+
+    for (method, uri, body) in [
+        (Method::GET, "/cube/state", None),
+        (Method::GET, "/cube/state?moves=R%20Q", None),
+        (Method::POST, "/cube/state", Some(json!({}))),
+        (Method::POST, "/cube/state", Some(json!({ "moves": "R Q" }))),
+    ] {
+        let (status, problem) = app.request_problem(method.clone(), uri, None, body).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{} {} was accepted", method, uri);
+        assert!(problem["detail"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn daily_scramble_changes_at_midnight() {
+    let app = test_app().await;
+    let first_day = app.today();
+
+    let scramble = app.get_scramble("/daily/scramble").await;
+    app.clock.advance(Duration::hours(11) + Duration::minutes(59));
+    assert_eq!(app.get_scramble("/daily/scramble").await, scramble);
+
+    app.clock.advance(Duration::minutes(1));
+    assert_ne!(app.get_scramble("/daily/scramble").await, scramble);
+    assert_eq!(app.get_scramble(&format!("/daily/{}/scramble", first_day)).await, scramble);
+    let tomorrow = app.today() + Duration::days(1);
+    assert_eq!(app.get(&format!("/daily/{}/scramble", tomorrow)).await.0, StatusCode::NOT_FOUND);
+    assert_eq!(app.get("/daily/19-10-2026/scramble").await.0, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn past_dailies_are_never_generated_after_the_day() {
+    let app = test_app().await;
+
+    for uri in ["/daily/0001-01-01/scramble", "/daily/2026-10-18/scramble", "/daily/events/222/2026-10-18/scrambles"] {
+        let (status, _) = app.request_problem(Method::GET, uri, None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{} was generated", uri);
+    }
+    app.clock.advance(Duration::days(1));
+    let history = app.get_json("/daily/history").await;
+    assert_eq!(history["total"], json!(0));
+}
+
+#[tokio::test]
+async fn challenge_counts_down_to_midnight() {
+    let app = test_app().await;
+
+    let challenge = app.get_json("/daily/challenge").await;
+    assert_eq!(challenge, json!({
+        "date": "2026-10-19",
+        "timezone": "UTC",
+        "starts_at": "2026-10-19T00:00:00Z",
+        "ends_at": "2026-10-20T00:00:00Z",
+        "remaining_seconds": 12 * 3600,
+    }));
+
+    app.clock.advance(Duration::hours(12));
+    let challenge = app.get_json("/daily/challenge").await;
+    assert_eq!(challenge["date"], json!("2026-10-20"));
+    assert_eq!(challenge["remaining_seconds"], json!(24 * 3600));
+}
+
+#[tokio::test]
+async fn daily_rolls_over_at_midnight_in_the_reset_timezone() {
+    // 01:00 on the 20th in Auckland, daylight saving time being in effect
+    let app = test_app_at(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap(), chrono_tz::Pacific::Auckland).await;
+
+    let challenge = app.get_json("/daily/challenge").await;
+    assert_eq!(challenge["date"], json!("2026-10-20"));
+    assert_eq!(challenge["starts_at"], json!("2026-10-19T11:00:00Z"));
+    assert_eq!(challenge["remaining_seconds"], json!(23 * 3600));
+
+    let token = app.login("alice").await;
+    assert_eq!(app.post_daily(&token, 9000).await.0, StatusCode::OK);
+    let leaderboard = app.get_json("/daily/2026-10-20/leaderboard").await;
+    assert_eq!(usernames(&leaderboard), ["alice"]);
+    assert!(usernames(&app.get_json("/daily/2026-10-19/leaderboard").await).is_empty());
+}
+
+#[tokio::test]
+async fn challenge_lasts_longer_when_clocks_go_back() {
+    // New York leaves daylight saving time on 2026-11-01 at 2:00
+    let app = test_app_at(Utc.with_ymd_and_hms(2026, 11, 1, 12, 0, 0).unwrap(), chrono_tz::America::New_York).await;
+
+    let challenge = app.get_json("/daily/challenge").await;
+    assert_eq!(challenge["starts_at"], json!("2026-11-01T04:00:00Z"));
+    assert_eq!(challenge["ends_at"], json!("2026-11-02T05:00:00Z"));
+    assert_eq!(challenge["remaining_seconds"], json!(17 * 3600));
+}
+
+#[tokio::test]
+async fn challenge_starts_after_a_skipped_midnight() {
+    // Santiago moves from midnight straight to 1:00 on 2026-09-06
+    let app = test_app_at(Utc.with_ymd_and_hms(2026, 9, 6, 12, 0, 0).unwrap(), chrono_tz::America::Santiago).await;
+
+    let challenge = app.get_json("/daily/challenge").await;
+    assert_eq!(challenge["date"], json!("2026-09-06"));
+    assert_eq!(challenge["starts_at"], json!("2026-09-06T04:00:00Z"));
+    assert_eq!(challenge["ends_at"], json!("2026-09-07T03:00:00Z"));
+}
+
+#[tokio::test]
+async fn daily_solve_requires_a_token() {
+    let app = test_app().await;
+
+    let (status, _) = app.post("/daily/scramble", None, json!({ "time": 9000 })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.post("/daily/scramble", Some("forged"), json!({ "time": 9000 })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn daily_solve_is_validated() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+
+    let (status, problem) = app.request_problem(Method::POST, "/daily/scramble", Some(&token), Some(json!({ "time": 0 }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(problem["detail"], json!("time is invalid."));
+    let (status, _) = app.post("/daily/scramble", Some(&token), json!({ "time": "fast" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, body) = app.post("/daily/scramble", Some(&token), json!({ "time": 9000, "solution": "R U" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+
+    let leaderboard = app.get_json("/daily/leaderboard").await;
+    assert!(usernames(&leaderboard).is_empty());
+}
+
+#[tokio::test]
+async fn daily_solve_with_solution_is_verified() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+    let scramble = app.get_scramble("/daily/scramble").await;
+    let solution = inverse(&scramble);
+
+    let (status, body) = app.post("/daily/scramble", Some(&token), json!({ "time": 9000, "solution": solution })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let leaderboard = app.get_json("/daily/leaderboard").await;
+    assert_eq!(leaderboard["solves"][0]["verified"], json!(true));
+    assert_eq!(leaderboard["solves"][0]["move_count"], json!(NotationMove::parse_array(&scramble).unwrap().len()));
+}
+
+#[tokio::test]
+async fn duplicate_daily_solve_is_rejected() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+
+    assert_eq!(app.post_daily(&token, 9000).await.0, StatusCode::OK);
+    let (status, problem) = app.request_problem(Method::POST, "/daily/scramble", Some(&token), Some(json!({ "time": 7000 }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(problem["title"], json!("Conflict"));
+    assert_eq!(problem["detail"], json!("daily scramble is already solved."));
+
+    let leaderboard = app.get_json("/daily/leaderboard").await;
+    assert_eq!(leaderboard["solves"].as_array().unwrap().len(), 1);
+    assert_eq!(leaderboard["solves"][0]["time"], json!(9000));
+}
+
+#[tokio::test]
+async fn daily_leaderboard_is_ordered_by_time() {
+    let app = test_app().await;
+    for (username, time) in [("alice", 12000), ("bob", 8000), ("carol", 10000)] {
+        let token = app.login(username).await;
+        assert_eq!(app.post_daily(&token, time).await.0, StatusCode::OK);
+    }
+
+    let leaderboard = app.get_json("/daily/leaderboard").await;
+    assert_eq!(usernames(&leaderboard), ["bob", "carol", "alice"]);
+    let dated = app.get_json(&format!("/daily/{}/leaderboard", app.today())).await;
+    assert_eq!(dated, leaderboard);
+}
+
+#[tokio::test]
+async fn daily_solves_move_to_history_at_midnight() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+    let first_day = app.today();
+
+    app.clock.advance(Duration::hours(11) + Duration::minutes(59));
+    assert_eq!(app.post_daily(&token, 9000).await.0, StatusCode::OK);
+    app.clock.advance(Duration::minutes(2));
+    assert_eq!(app.post_daily(&token, 8000).await.0, StatusCode::OK);
+
+    let leaderboard = app.get_json("/daily/leaderboard").await;
+    assert_eq!(leaderboard["solves"][0]["time"], json!(8000));
+    let yesterday = app.get_json(&format!("/daily/{}/leaderboard", first_day)).await;
+    assert_eq!(yesterday["solves"][0]["time"], json!(9000));
+    let tomorrow = app.today() + Duration::days(1);
+    assert_eq!(app.get(&format!("/daily/{}/leaderboard", tomorrow)).await.0, StatusCode::NOT_FOUND);
+
+    let history = app.get_json("/daily/history").await;
+    assert_eq!(history["total"], json!(0));
+    app.get("/daily/scramble").await;
+    app.clock.advance(Duration::days(1));
+    let history = app.get_json("/daily/history").await;
+    assert_eq!(history["dailies"][0]["date"], json!(first_day.succ_opt().unwrap().to_string()));
+}
+
+#[tokio::test]
+async fn user_statistics_follow_their_solves() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+    for time in [10000, 20000, 30000] {
+        assert_eq!(app.post_daily(&token, time).await.0, StatusCode::OK);
+        app.clock.advance(Duration::days(1));
+    }
+
+    let solves = app.get_json("/users/alice/solves?per_page=2").await;
+    assert_eq!(solves["total"], json!(3));
+    assert_eq!(solves["solves"][0]["time"], json!(30000));
+
+    let stats = app.get_json("/users/alice/stats").await;
+    assert_eq!(stats["mean"], json!(20000));
+    assert_eq!(stats["current_streak"], json!(3));
+
+    let averages = app.get_json("/users/alice/averages?size=3").await;
+    assert_eq!(averages["averages"][0]["average"], json!(20000));
+    assert_eq!(app.get("/users/alice/averages?size=1").await.0, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn period_leaderboards_rank_ties_together() {
+    let app = test_app().await;
+    for (username, time) in [("alice", 9000), ("bob", 8000), ("carol", 9000)] {
+        let token = app.login(username).await;
+        assert_eq!(app.post_daily(&token, time).await.0, StatusCode::OK);
+    }
+
+    let leaderboard = app.get_json("/leaderboards/week").await;
+    let ranks: Vec<_> = leaderboard["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| (entry["rank"].as_i64().unwrap(), entry["username"].as_str().unwrap()))
+        .collect();
+    assert_eq!(ranks, [(1, "bob"), (2, "alice"), (2, "carol")]);
+    assert_eq!(app.get("/leaderboards/decade").await.0, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn profile_and_login_require_the_right_credentials() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+
+    let (status, _) = app.post("/users", None, json!({ "username": "alice", "password": "correct horse" })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = app.post("/auth/login", None, json!({ "username": "alice", "password": "wrong horse" })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let profile = json!({ "country": "fr", "club": "Cubers" });
+    let (status, body) = app.request(Method::PUT, "/users/me/profile", Some(&token), Some(profile)).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app.request(Method::PUT, "/users/me/profile", Some(&token), Some(json!({ "country": "France" }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn fmc_solutions_are_revealed_the_next_day() {
+    let app = test_app().await;
+    let token = app.login("alice").await;
+    let scramble = app.get_scramble("/daily/scramble").await;
+    let first_day = app.today();
+
+    // Cancelling moves still count against the cap before being simplified away
+    let padded = format!("{}{}", inverse(&scramble), " R R'".repeat(41));
+    let (status, _) = app.post("/daily/fmc", Some(&token), json!({ "solution": padded })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, body) = app.post("/daily/fmc", Some(&token), json!({ "solution": inverse(&scramble) })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let (status, _) = app.post("/daily/fmc", Some(&token), json!({ "solution": "R U" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let leaderboard = app.get_json("/daily/fmc/leaderboard").await;
+    assert_eq!(leaderboard["solves"][0]["solution"], Value::Null);
+
+    app.clock.advance(Duration::days(1));
+    let uri = format!("/daily/{}/fmc/leaderboard", first_day);
+    let leaderboard = app.get_json(&uri).await;
+    assert_eq!(leaderboard["solves"][0]["solution"], json!(inverse(&scramble)));
+
+    // The solver searches the upper bound in the background since the scramble was served
+    let searched = async {
+        loop {
+            let leaderboard = app.get_json(&uri).await;
+            if let Some(upper_bound) = leaderboard["best_known_upper_bound"].as_i64() {
+                return upper_bound;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    };
+    let upper_bound = tokio::time::timeout(std::time::Duration::from_secs(60), searched).await.expect("upper bound was not searched");
+    assert!((1..=20).contains(&upper_bound));
+}
+
+#[tokio::test]
+async fn event_rounds_are_ranked_by_average() {
+    let app = test_app().await;
+    let events = app.get_json("/events").await;
+    assert_eq!(events["events"][0]["id"], json!("333"));
+
+    let scrambles = app.get_json("/daily/events/222/scrambles").await;
+    assert_eq!(scrambles["scrambles"].as_array().unwrap().len(), 5);
+    assert_eq!(scrambles["scrambles"][0]["puzzle"], json!("2x2x2"));
+    let scramble = scrambles["scrambles"][0]["scramble"].as_str().unwrap();
+    assert!(scramble.split_whitespace().all(|m| m.starts_with(['U', 'R', 'F'])), "{}", scramble);
+    let dated = app.get_json(&format!("/daily/events/222/{}/scrambles", app.today())).await;
+    assert_eq!(dated, scrambles);
+    assert_eq!(app.get("/daily/events/444/scrambles").await.0, StatusCode::NOT_FOUND);
+
+    for (username, times) in [("alice", [5000, 6000, 7000, 8000, 9000]), ("bob", [4000, 5000, 6000, 7000, 8000])] {
+        let token = app.login(username).await;
+        let attempts: Vec<Value> = times.iter().map(|time| json!({ "time": time, "penalty": "none" })).collect();
+        let (status, body) = app.post("/daily/events/222", Some(&token), json!({ "attempts": attempts })).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+
+    let leaderboard = app.get_json(&format!("/daily/events/222/{}/leaderboard", app.today())).await;
+    assert_eq!(leaderboard["rounds"][0]["username"], json!("bob"));
+    assert_eq!(leaderboard["rounds"][0]["average"], json!(6000));
+    let undated = app.get_json("/daily/events/222/leaderboard").await;
+    assert_eq!(undated, leaderboard);
+}
+
+#[tokio::test]
+async fn unreachable_database_is_reported_as_unavailable() {
+    let clock = Arc::new(FakeClock(Mutex::new(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())));
+    let mut state = test_state(clock.clone(), Tz::UTC).await;
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    pool.close().await;
+    state.events_service = EventsService::new(Arc::new(SqliteEventsRepository::new(pool)));
+    let app = TestApp::new(state, clock);
+
+    let (status, problem) = app.request_problem(Method::GET, "/events", None, None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(problem["title"], json!("Service Unavailable"));
+}
+
+#[tokio::test]
+async fn scrambles_link_to_their_image() {
+    let clock = Arc::new(FakeClock(Mutex::new(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())));
+    let mut state = test_state(clock.clone(), Tz::UTC).await;
+    state.scramble_svg_url = Some("https://images.example/cube.svg?alg={moves}".to_string());
+    let app = TestApp::new(state, clock);
+
+    let scramble = app.get_json("/daily/scramble").await;
+    let moves = scramble["scramble"].as_str().unwrap();
+    let expected = format!("https://images.example/cube.svg?alg={}", moves.replace(' ', "%20").replace('\'', "%27"));
+    assert_eq!(scramble["svg_url"], json!(expected));
+}
+
+#[tokio::test]
+async fn solver_routes_are_rate_limited_per_client() {
+    let clock = Arc::new(FakeClock(Mutex::new(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())));
+    let state = test_state(clock.clone(), Tz::UTC).await;
+    let app = TestApp::with_rate_limit(state, clock, RateLimit { per_minute: 1, burst: 2 });
+    wait_until_full(&app.scramble_pool, 2).await;
+
+    for _ in 0..2 {
+        let (status, body) = app.get("/cube/scramble").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    let (status, problem) = app.request_problem(Method::POST, "/cube/solve", None, Some(json!({ "moves": "R U" }))).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(problem["detail"].as_str().unwrap().starts_with("too many requests"));
+    for uri in ["/daily/2026-10-18/scramble", "/daily/2026-10-18/fmc/leaderboard"] {
+        assert_eq!(app.get(uri).await.0, StatusCode::TOO_MANY_REQUESTS, "{}", uri);
+    }
+
+    // Other routes and other clients are not limited
+    assert_eq!(app.get("/cube/solved?moves=R").await.0, StatusCode::OK);
+    wait_until_full(&app.scramble_pool, 2).await;
+    let request = Request::get("/cube/scramble")
+        .extension(ConnectInfo(SocketAddr::from(([127, 0, 0, 2], 40000))))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = Request::get("/cube/scramble")
+        .extension(ConnectInfo(SocketAddr::from(TEST_PEER)))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()[header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after));
+}
+
+/// Waits until `pool` is full again, which its background task should do in seconds.
+async fn wait_until_full(pool: &ScramblePool, size: usize) {
+    let full = async {
+        while pool.available() < size {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(30), full).await.expect("scramble pool was not refilled");
+}
+
+#[tokio::test]
+async fn scramble_pool_is_refilled_in_the_background() {
+    let scramble_service = ScrambleService::new(5.0, Arc::new(Semaphore::new(1)));
+    let pool = ScramblePool::start(scramble_service, 3);
+    wait_until_full(&pool, 3).await;
+
+    let scramble = pool.take().await.unwrap();
+    assert!(CubeMove::parse_array(&scramble).is_some_and(|moves| !moves.is_empty()));
+    wait_until_full(&pool, 3).await;
+}
+
+#[tokio::test]
+async fn random_scrambles_are_only_served_from_the_pool() {
+    let clock = Arc::new(FakeClock(Mutex::new(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())));
+    let mut state = test_state(clock.clone(), Tz::UTC).await;
+    // The only solver permit is taken, so the pool stays empty
+    let permits = Arc::new(Semaphore::new(1));
+    let _taken = permits.clone().acquire_owned().await.unwrap();
+    state.scramble_pool = ScramblePool::start(ScrambleService::new(5.0, permits), 2);
+    let app = TestApp::new(state, clock);
+
+    let request = Request::get("/cube/scramble")
+        .extension(ConnectInfo(SocketAddr::from(TEST_PEER)))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+}
+
+#[tokio::test]
+async fn scrambles_wait_for_a_solver_permit() {
+    let permits = Arc::new(Semaphore::new(1));
+    let scramble_service = ScrambleService::new(5.0, permits.clone());
+
+    let taken = permits.clone().acquire_owned().await.unwrap();
+    let waiting = tokio::time::timeout(std::time::Duration::from_millis(200), scramble_service.get_random_scramble()).await;
+    assert!(waiting.is_err(), "scramble was generated without a permit");
+
+    drop(taken);
+    assert!(!scramble_service.get_random_scramble().await.unwrap().is_empty());
+}
+
+/// Whether `solution` solves the cube left by `scramble`.
+fn solves(scramble: &str, solution: &str) -> bool {
+    let mut cube = Cube::solved();
+    cube.apply_moves(&CubeMove::parse_array(scramble).unwrap());
+    cube.apply_moves(&CubeMove::parse_array(solution).unwrap());
+    cube.is_solved()
+}
+
+#[tokio::test]
+async fn solver_solves_moves_and_facelets() {
+    let app = test_app().await;
+    let scramble = "R U R' U' F2 D L' B";
+
+    for solver in ["kociemba", "shortest"] {
+        let (status, body) = app.post("/cube/solve", None, json!({ "moves": scramble, "solver": solver, "timeout_seconds": 1.0 })).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let solution: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(solution["solver"], json!(solver));
+        let moves = solution["solution"].as_str().unwrap();
+        assert!(solves(scramble, moves), "{} does not solve {}", moves, scramble);
+        assert_eq!(solution["length"], json!(moves.split_whitespace().count()));
+        assert!(solution["time_ms"].is_u64());
+    }
+
+    let mut cube = Cube::solved();
+    cube.apply_moves(&CubeMove::parse_array(scramble).unwrap());
+    let (status, body) = app.post("/cube/solve", None, json!({ "facelets": cube.to_facelets() })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let solution: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(solution["solver"], json!("kociemba"));
+    assert!(solves(scramble, solution["solution"].as_str().unwrap()));
+}
+
+#[tokio::test]
+async fn pochmann_solver_lists_targets() {
+    let app = test_app().await;
+
+    // T permutation, swapping URF with UBR and UR with UL
+    let (status, body) = app.post("/cube/solve", None, json!({ "moves": "R U R' U' R' F R2 U' R' U' R U R' F'", "solver": "pochmann" })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let solution: Value = serde_json::from_str(&body).unwrap();
+    // The corner swap is away from the buffer, so it takes a cycle break
+    let targets: Vec<&str> = solution["solution"].as_str().unwrap().split(' ').collect();
+    assert_eq!(targets.len(), 4);
+    assert_eq!(targets[0], targets[2]);
+    assert_eq!(targets[3], "UL");
+    assert_eq!(solution["length"], json!(4));
+
+    let (status, body) = app.post("/cube/solve", None, json!({ "moves": "", "solver": "pochmann" })).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["solution"], json!(""));
+}
+
+#[tokio::test]
+async fn solve_requests_are_validated() {
+    let app = test_app().await;
+
+    for request in [
+        json!({}),
+        json!({ "moves": "R U", "facelets": Cube::solved().to_facelets() }),
+        json!({ "moves": "R X" }),
+        json!({ "facelets": "UUU" }),
+        json!({ "moves": "R U", "max_length": 0 }),
+        json!({ "moves": "R U", "max_length": 17 }),
+        json!({ "moves": "R U", "solver": "shortest", "max_length": 10 }),
+        json!({ "moves": "R U", "max_length": 31 }),
+        json!({ "moves": "R U", "timeout_seconds": 0.0 }),
+    ] {
+        let (status, problem) = app.request_problem(Method::POST, "/cube/solve", None, Some(request.clone())).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{} was accepted", request);
+        assert!(problem["detail"].is_string());
+    }
+
+    let (status, _) = app.post("/cube/solve", None, json!({ "moves": "R U", "solver": "thistlethwaite" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn move_solvers_reject_lengths_they_cannot_search() {
+    let app = test_app().await;
+
+    // A 6 move solution exists, but the two-phase search can't aim that low
+    let request = json!({ "moves": "R U F D L B", "solver": "shortest", "max_length": 10 });
+    let (status, problem) = app.request_problem(Method::POST, "/cube/solve", None, Some(request)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(problem["detail"], json!("max length must be between 18 and 30."));
+
+    let request = json!({ "moves": "R U F D L B", "solver": "pochmann", "max_length": 3 });
+    assert_eq!(app.post("/cube/solve", None, request).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn races_are_opened_and_joined() {
+    let app = test_app().await;
+    let alice = app.login("alice").await;
+    let bob = app.login("bob").await;
+
+    let (status, _) = app.request_problem(Method::POST, "/races", None, Some(json!({ "event": "333" }))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.request_problem(Method::POST, "/races", Some(&alice), Some(json!({ "event": "999" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.request_problem(Method::POST, "/races", Some(&alice), Some(json!({ "event": "333", "rounds": 13 }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, body) = app.post("/races", Some(&alice), json!({ "event": "333", "rounds": 3 })).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+    let race: Value = serde_json::from_str(&body).unwrap();
+    let id = race["id"].as_str().unwrap();
+    assert_eq!(race["status"], json!("waiting"));
+    assert_eq!(race["round_count"], json!(3));
+    assert_eq!(race["participants"], json!(["alice"]));
+    // Scrambles stay hidden until their round starts
+    assert!(race.get("scramble").is_none());
+
+    let uri = format!("/races/{}/participants", id);
+    for _ in 0..2 {
+        let (status, body) = app.request(Method::POST, &uri, Some(&bob), None).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+    let race = app.get_json(&format!("/races/{}", id)).await;
+    assert_eq!(race["participants"], json!(["alice", "bob"]));
+    assert_eq!(race["standings"][1]["username"], json!("bob"));
+
+    let (status, _) = app.request_problem(Method::POST, "/races/NOPE23/participants", Some(&bob), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = app.request_problem(Method::GET, "/races/NOPE23", None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn races_run_over_websockets() {
+    let app = test_app().await;
+    let alice = app.login("alice").await;
+    let bob = app.login("bob").await;
+    let carol = app.login("carol").await;
+    let (_, body) = app.post("/races", Some(&alice), json!({ "event": "333", "rounds": 2 })).await;
+    let id = serde_json::from_str::<Value>(&body).unwrap()["id"].as_str().unwrap().to_string();
+    app.request(Method::POST, &format!("/races/{}/participants", id), Some(&bob), None).await;
+
+    let address = app.serve().await;
+    let mut alice_socket = connect_race(address, &id, Some(&alice)).await;
+    let mut bob_socket = connect_race(address, &id, Some(&bob)).await;
+    let mut spectator = connect_race(address, &id, None).await;
+    for socket in [&mut alice_socket, &mut bob_socket, &mut spectator] {
+        let message = next_race_message(socket).await.unwrap();
+        assert_eq!((&message["type"], &message["status"]), (&json!("race"), &json!("waiting")));
+    }
+
+    // Rejected commands only get an error back
+    send_race_command(&mut spectator, json!({ "type": "start" })).await;
+    assert_eq!(next_race_message(&mut spectator).await.unwrap()["status"], json!(401));
+    send_race_command(&mut bob_socket, json!({ "type": "start" })).await;
+    assert_eq!(next_race_message(&mut bob_socket).await.unwrap()["status"], json!(403));
+    send_race_command(&mut alice_socket, json!({ "type": "start" })).await;
+    for socket in [&mut alice_socket, &mut bob_socket, &mut spectator] {
+        let message = next_race_message(socket).await.unwrap();
+        assert_eq!((&message["status"], &message["current_round"]), (&json!("running"), &json!(1)));
+        assert!(message["scramble"]["moves"].is_array());
+    }
+    let (status, _) = app.request_problem(Method::POST, &format!("/races/{}/participants", id), Some(&carol), None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    send_race_command(&mut alice_socket, json!({ "type": "submit", "time": 9000 })).await;
+    let message = next_race_message(&mut spectator).await.unwrap();
+    assert_eq!(message["standings"][0]["attempts"], json!([{ "time": 9000, "penalty": "none" }]));
+    send_race_command(&mut alice_socket, json!({ "type": "submit", "time": 8000 })).await;
+    assert_eq!(next_race_message(&mut alice_socket).await.unwrap()["type"], json!("race"));
+    assert_eq!(next_race_message(&mut alice_socket).await.unwrap()["status"], json!(409));
+
+    // The last submission of a round starts the next one
+    send_race_command(&mut bob_socket, json!({ "type": "submit", "time": 12000, "penalty": "+2" })).await;
+    let message = next_race_message(&mut spectator).await.unwrap();
+    assert_eq!(message["current_round"], json!(2));
+    let totals: Vec<_> = message["standings"].as_array().unwrap().iter().map(|standing| (standing["username"].clone(), standing["total"].clone())).collect();
+    assert_eq!(totals, [(json!("alice"), json!(9000)), (json!("bob"), json!(14000))]);
+
+    // Closing the round gives a DNF to alice, who did not submit
+    send_race_command(&mut bob_socket, json!({ "type": "submit", "time": 7000 })).await;
+    send_race_command(&mut alice_socket, json!({ "type": "next" })).await;
+    let mut last = None;
+    while let Some(message) = next_race_message(&mut spectator).await {
+        last = Some(message);
+    }
+    let last = last.unwrap();
+    assert_eq!(last["status"], json!("finished"));
+
+    let race = app.get_json(&format!("/races/{}", id)).await;
+    assert_eq!(race["status"], json!("finished"));
+    assert_eq!(race["standings"], last["standings"]);
+    assert_eq!(race["standings"][0]["username"], json!("bob"));
+    assert_eq!(race["standings"][1]["attempts"][1]["penalty"], json!("DNF"));
+}
+
+#[tokio::test]
+async fn idle_races_are_closed() {
+    let clock = Arc::new(FakeClock(Mutex::new(Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap())));
+    let state = test_state(clock.clone(), Tz::UTC).await;
+    let races = state.races_service.clone();
+    let app = TestApp::new(state, clock);
+    let alice = app.login("alice").await;
+    let bob = app.login("bob").await;
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let (_, body) = app.post("/races", Some(&alice), json!({ "event": "333" })).await;
+        ids.push(serde_json::from_str::<Value>(&body).unwrap()["id"].as_str().unwrap().to_string());
+    }
+    let address = app.serve().await;
+    let mut socket = connect_race(address, &ids[0], None).await;
+    assert_eq!(next_race_message(&mut socket).await.unwrap()["status"], json!("waiting"));
+
+    // Joining the second race keeps it open
+    app.clock.advance(RACE_IDLE_TIMEOUT - Duration::minutes(1));
+    app.request(Method::POST, &format!("/races/{}/participants", ids[1]), Some(&bob), None).await;
+    assert_eq!(races.expire_idle_rooms(), 0);
+    app.clock.advance(Duration::minutes(2));
+    assert_eq!(races.expire_idle_rooms(), 1);
+
+    assert_eq!(next_race_message(&mut socket).await, None);
+    let (status, _) = app.request_problem(Method::GET, &format!("/races/{}", ids[0]), None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    app.get_json(&format!("/races/{}", ids[1])).await;
+}
+
+#[tokio::test]
+async fn openapi_document_describes_every_route() {
+    let app = test_app().await;
+    let document = app.get_json("/openapi.json").await;
+
+    assert!(document["openapi"].as_str().unwrap().starts_with("3."));
+    let paths = document["paths"].as_object().unwrap();
+    for (path, method) in [
+        ("/cube/scramble", "get"),
+        ("/cube/solved", "get"),
+        ("/cube/solve", "post"),
+        ("/cube/state", "get"),
+        ("/cube/state", "post"),
+        ("/daily/scramble", "get"),
+        ("/daily/scramble", "post"),
+        ("/daily/leaderboard", "get"),
+        ("/daily/history", "get"),
+        ("/daily/challenge", "get"),
+        ("/daily/{date}/scramble", "get"),
+        ("/daily/{date}/leaderboard", "get"),
+        ("/daily/fmc", "post"),
+        ("/daily/fmc/leaderboard", "get"),
+        ("/daily/{date}/fmc/leaderboard", "get"),
+        ("/events", "get"),
+        ("/daily/events/{event}", "post"),
+        ("/daily/events/{event}/scrambles", "get"),
+        ("/daily/events/{event}/{date}/scrambles", "get"),
+        ("/daily/events/{event}/leaderboard", "get"),
+        ("/daily/events/{event}/{date}/leaderboard", "get"),
+        ("/leaderboards/{period}", "get"),
+        ("/users", "post"),
+        ("/users/me/profile", "put"),
+        ("/users/{name}/solves", "get"),
+        ("/users/{name}/stats", "get"),
+        ("/users/{name}/averages", "get"),
+        ("/auth/login", "post"),
+        ("/races", "post"),
+        ("/races/{id}", "get"),
+        ("/races/{id}/participants", "post"),
+        ("/races/{id}/live", "get"),
+    ] {
+        assert!(paths.get(path).is_some_and(|item| item.get(method).is_some()), "{} {} is not documented", method, path);
+    }
+
+    let schemas = &document["components"]["schemas"];
+    assert_eq!(schemas["ScrambleDto"]["properties"]["moves"]["type"], json!("array"));
+    assert!(document["components"]["responses"]["ProblemDto"]["content"]["application/problem+json"].is_object());
+    assert!(document["components"]["securitySchemes"]["bearer"].is_object());
+}
@@ -0,0 +1,226 @@
+use crate::analysis::last_layer::{recognize_oll, recognize_pll, OllCase, PllCase};
+use crate::cube::{Cube, CubeMove};
+use crate::faces::Face;
+use crate::notation::{opposite_face, Axis, Orientation, TurnAmount};
+use crate::reconstruction::{Reconstruction, SolveStep};
+
+/// Number of solution moves done when a step got completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepBoundary {
+    pub step: SolveStep,
+    pub move_index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfopAnalysis {
+    /// Face of the first cross completed, the F2L and last layer are detected relative to it
+    pub cross_face: Option<Face>,
+    /// Completed steps in solve order, several steps can end on the same move
+    pub steps: Vec<StepBoundary>,
+    /// `None` when the OLL was skipped or not reached
+    pub oll_case: Option<OllCase>,
+    /// `None` when the PLL was skipped or not reached
+    pub pll_case: Option<PllCase>,
+}
+
+impl CfopAnalysis {
+    pub fn boundary(&self, step: SolveStep) -> Option<usize> {
+        self.steps
+            .iter()
+            .find(|boundary| boundary.step == step)
+            .map(|boundary| boundary.move_index)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.boundary(SolveStep::Pll).is_some()
+    }
+
+    /// Number of moves spent on each completed step.
+    pub fn step_move_counts(&self) -> Vec<(SolveStep, usize)> {
+        let mut previous_index = 0;
+        self.steps
+            .iter()
+            .map(|boundary| {
+                let move_count = boundary.move_index - previous_index;
+                previous_index = boundary.move_index;
+                (boundary.step, move_count)
+            })
+            .collect()
+    }
+}
+
+/// Replays a solution and detects when each CFOP step gets completed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CfopAnalyzer;
+
+impl CfopAnalyzer {
+    pub fn analyze(&self, scramble: &[CubeMove], solution: &[CubeMove]) -> CfopAnalysis {
+        let mut cube = Cube::solved();
+        cube.apply_moves(&scramble.to_vec());
+
+        let mut states = Vec::with_capacity(solution.len() + 1);
+        states.push(cube.clone());
+        for cube_move in solution {
+            cube.apply_moves(&vec![*cube_move]);
+            states.push(cube.clone());
+        }
+
+        let mut analysis = CfopAnalysis {
+            cross_face: None,
+            steps: Vec::new(),
+            oll_case: None,
+            pll_case: None,
+        };
+
+        // Cross, color neutral
+        let Some((cross_index, cross_face)) = states.iter().enumerate().find_map(|(i, state)| {
+            CROSS_FACES
+                .iter()
+                .find(|face| state.is_cross_solved(**face))
+                .map(|face| (i, *face))
+        }) else {
+            return analysis;
+        };
+        analysis.cross_face = Some(cross_face);
+        analysis.steps.push(StepBoundary { step: SolveStep::Cross, move_index: cross_index });
+
+        // F2L pairs, several can be completed by the same move
+        let mut solved_pairs = 0;
+        let mut f2l_index = None;
+        for (i, state) in states.iter().enumerate().skip(cross_index) {
+            if !state.is_cross_solved(cross_face) {
+                continue;
+            }
+
+            let slots = state.solved_f2l_slots(cross_face).len() as u8;
+            while solved_pairs < slots {
+                solved_pairs += 1;
+                analysis.steps.push(StepBoundary { step: SolveStep::F2l(solved_pairs), move_index: i });
+            }
+
+            if solved_pairs == 4 {
+                f2l_index = Some(i);
+                break;
+            }
+        }
+        let Some(f2l_index) = f2l_index else {
+            return analysis;
+        };
+
+        // OLL
+        let last_layer_face = opposite_face(cross_face);
+        let Some(oll_index) = (f2l_index..states.len()).find(|i| {
+            states[*i].is_f2l_solved(cross_face) && states[*i].is_face_solved(last_layer_face)
+        }) else {
+            return analysis;
+        };
+        let oll_cube = states[f2l_index].rotated(&orientation_with_down(cross_face));
+        analysis.oll_case = recognize_oll(&oll_cube).map(|recognition| recognition.case);
+        analysis.steps.push(StepBoundary { step: SolveStep::Oll, move_index: oll_index });
+
+        // PLL
+        let Some(pll_index) = (oll_index..states.len()).find(|i| states[*i].is_solved()) else {
+            return analysis;
+        };
+        let pll_cube = states[oll_index].rotated(&orientation_with_down(cross_face));
+        analysis.pll_case = recognize_pll(&pll_cube).map(|recognition| recognition.case);
+        analysis.steps.push(StepBoundary { step: SolveStep::Pll, move_index: pll_index });
+
+        analysis
+    }
+
+    /// Boundaries index the solution lowered with `Reconstruction::solution_cube_moves`,
+    /// where slice moves count as two moves and rotations as none.
+    pub fn analyze_reconstruction(&self, reconstruction: &Reconstruction) -> CfopAnalysis {
+        self.analyze(&reconstruction.scramble, &reconstruction.solution_cube_moves())
+    }
+}
+
+/// Orientation in which `face` is at the bottom.
+fn orientation_with_down(face: Face) -> Orientation {
+    let mut orientation = Orientation::default();
+    match face {
+        Face::D => {},
+        Face::U => orientation.rotate(Axis::X, TurnAmount::Double),
+        Face::F => orientation.rotate(Axis::X, TurnAmount::CounterClockwise),
+        Face::B => orientation.rotate(Axis::X, TurnAmount::Clockwise),
+        Face::L => orientation.rotate(Axis::Z, TurnAmount::CounterClockwise),
+        Face::R => orientation.rotate(Axis::Z, TurnAmount::Clockwise),
+    }
+    orientation
+}
+
+static CROSS_FACES: [Face; 6] = [
+    Face::D,
+    Face::U,
+    Face::F,
+    Face::B,
+    Face::L,
+    Face::R,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::NotationMove;
+
+    /// Lowers `solution` and returns the scramble it solves.
+    fn make_solve(solution: &str) -> (Vec<CubeMove>, Vec<CubeMove>) {
+        let notation_moves = NotationMove::parse_array(solution).expect("Solution is valid.");
+        let solution = Orientation::default().lower_all(&notation_moves);
+        let scramble = solution.iter().rev().map(CubeMove::inverted).collect();
+        (scramble, solution)
+    }
+
+    #[test]
+    fn test_last_layer_steps() {
+        // Sune, T perm and AUF
+        let (scramble, solution) = make_solve("R U R' U R U2 R' R U R' U' R' F R2 U' R' U' R U R' F' U");
+        let analysis = CfopAnalyzer.analyze(&scramble, &solution);
+
+        assert_eq!(analysis.cross_face, Some(Face::D));
+        assert_eq!(analysis.boundary(SolveStep::Cross), Some(0));
+        assert_eq!(analysis.boundary(SolveStep::F2l(4)), Some(0));
+        assert_eq!(analysis.boundary(SolveStep::Oll), Some(7));
+        assert_eq!(analysis.boundary(SolveStep::Pll), Some(22));
+        assert_eq!(analysis.oll_case, Some(OllCase(27)));
+        assert_eq!(analysis.pll_case, Some(PllCase::T));
+        assert!(analysis.is_solved());
+        assert_eq!(analysis.step_move_counts().last(), Some(&(SolveStep::Pll, 15)));
+    }
+
+    #[test]
+    fn test_cross_on_other_face() {
+        let (scramble, solution) = make_solve("x2 R U R' U R U2 R' R U R' U' R' F R2 U' R' U' R U R' F' U");
+        let analysis = CfopAnalyzer.analyze(&scramble, &solution);
+
+        assert_eq!(analysis.cross_face, Some(Face::U));
+        assert_eq!(analysis.oll_case, Some(OllCase(27)));
+        assert_eq!(analysis.pll_case, Some(PllCase::T));
+    }
+
+    #[test]
+    fn test_f2l_pairs_and_partial_solve() {
+        // Last pair inserted with U R U' R', then an unfinished OLL
+        let scramble = CubeMove::parse_array("F R U R' U' F' R U R' U'").expect("Scramble is valid.");
+        let solution = CubeMove::parse_array("U R U' R' F R U R' U'").expect("Solution is valid.");
+        let analysis = CfopAnalyzer.analyze(&scramble, &solution);
+
+        assert_eq!(analysis.boundary(SolveStep::Cross), Some(0));
+        assert_eq!(analysis.boundary(SolveStep::F2l(3)), Some(0));
+        assert_eq!(analysis.boundary(SolveStep::F2l(4)), Some(4));
+        assert_eq!(analysis.boundary(SolveStep::Oll), None);
+        assert!(!analysis.is_solved());
+    }
+
+    #[test]
+    fn test_analyze_reconstruction() {
+        let reconstruction = Reconstruction::parse("Scramble: R U2 R' U' R U' R'\n\ny2 // inspection\ny2 R U R' U R U2 R' // OLL")
+            .expect("Reconstruction is valid.");
+        let analysis = CfopAnalyzer.analyze_reconstruction(&reconstruction);
+
+        assert_eq!(analysis.oll_case, Some(OllCase(27)));
+        assert_eq!(analysis.boundary(SolveStep::Pll), Some(7));
+        assert_eq!(analysis.pll_case, None);
+    }
+}
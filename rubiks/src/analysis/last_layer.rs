@@ -0,0 +1,684 @@
+use core::fmt;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::LazyLock;
+use crate::cube::{Cube, CubeMove};
+use crate::faces::Face;
+use crate::location::CubePieceLocation;
+use crate::notation::{NotationMove, Orientation};
+use crate::piece::CubePiece;
+use crate::twist::Twist;
+use crate::utils::permutations;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OllCase(pub u8);
+
+impl OllCase {
+    /// Reference algorithm the AUFs of a recognition are given for.
+    pub fn algorithm(&self) -> &'static str {
+        OLL_ALGORITHMS[self.0 as usize - 1]
+    }
+}
+
+impl fmt::Display for OllCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OLL {}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PllCase {
+    Aa,
+    Ab,
+    E,
+    F,
+    Ga,
+    Gb,
+    Gc,
+    Gd,
+    H,
+    Ja,
+    Jb,
+    Na,
+    Nb,
+    Ra,
+    Rb,
+    T,
+    Ua,
+    Ub,
+    V,
+    Y,
+    Z,
+}
+
+impl PllCase {
+    /// Reference algorithm the AUFs of a recognition are given for.
+    pub fn algorithm(&self) -> &'static str {
+        PLL_ALGORITHMS
+            .iter()
+            .find(|(case, _)| case == self)
+            .map(|(_, algorithm)| *algorithm)
+            .expect("Every PLL case has an algorithm.")
+    }
+}
+
+impl fmt::Display for PllCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Corner orientation shape of a last layer with oriented edges, named after the OCLL cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OcllSet {
+    H,
+    Pi,
+    U,
+    T,
+    L,
+    Sune,
+    AntiSune,
+    /// Corners already oriented, only used by COLL
+    Oriented,
+}
+
+impl OcllSet {
+    fn from_oll(oll_case: Option<OllCase>) -> Option<Self> {
+        match oll_case {
+            None => Some(OcllSet::Oriented),
+            Some(OllCase(21)) => Some(OcllSet::H),
+            Some(OllCase(22)) => Some(OcllSet::Pi),
+            Some(OllCase(23)) => Some(OcllSet::U),
+            Some(OllCase(24)) => Some(OcllSet::T),
+            Some(OllCase(25)) => Some(OcllSet::L),
+            Some(OllCase(26)) => Some(OcllSet::AntiSune),
+            Some(OllCase(27)) => Some(OcllSet::Sune),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OcllSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            OcllSet::H => "H",
+            OcllSet::Pi => "Pi",
+            OcllSet::U => "U",
+            OcllSet::T => "T",
+            OcllSet::L => "L",
+            OcllSet::Sune => "S",
+            OcllSet::AntiSune => "AS",
+            OcllSet::Oriented => "O",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// COLL case, numbered from 1 within its set.
+/// The numbering is our own canonical ordering and doesn't follow any published case list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CollCase {
+    pub set: OcllSet,
+    pub index: u16,
+}
+
+impl CollCase {
+    /// Cube set up in the case, the AUFs of a recognition are given for this state.
+    pub fn representative(&self) -> Cube {
+        COLL_CASES.representatives[self].with_solvable_edges().to_cube()
+    }
+}
+
+impl fmt::Display for CollCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "COLL {}{}", self.set, self.index)
+    }
+}
+
+/// ZBLL case, numbered from 1 within its set.
+/// The numbering is our own canonical ordering and doesn't follow any published case list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ZbllCase {
+    pub set: OcllSet,
+    pub index: u16,
+}
+
+impl ZbllCase {
+    /// Cube set up in the case, the AUFs of a recognition are given for this state.
+    pub fn representative(&self) -> Cube {
+        ZBLL_CASES.representatives[self].to_cube()
+    }
+}
+
+impl fmt::Display for ZbllCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ZBLL {}{}", self.set, self.index)
+    }
+}
+
+/// A recognized case with the U layer turns which make its reference
+/// algorithm (or the representative state of generated cases) apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recognition<C> {
+    pub case: C,
+    /// Turn to do before the algorithm
+    pub pre_auf: Option<CubeMove>,
+    /// Turn to do after the algorithm, always `None` for OLL
+    pub post_auf: Option<CubeMove>,
+}
+
+/// Recognizes the OLL case of a cube with F2L solved on D.
+/// Returns `None` if F2L isn't solved or the last layer is already oriented.
+pub fn recognize_oll(cube: &Cube) -> Option<Recognition<OllCase>> {
+    let state = LastLayerState::from_cube(cube)?.orientation_only();
+    OLL_CASES
+        .recognize(&state)
+        .map(|recognition| Recognition { post_auf: None, ..recognition })
+}
+
+/// Recognizes the PLL case of a cube with F2L solved on D and an oriented last layer.
+/// Returns `None` if the last layer isn't oriented or only needs an AUF.
+pub fn recognize_pll(cube: &Cube) -> Option<Recognition<PllCase>> {
+    let state = LastLayerState::from_cube(cube)?;
+    if !state.is_oriented() {
+        return None;
+    }
+    PLL_CASES.recognize(&state)
+}
+
+/// Recognizes the COLL case of a cube with F2L solved on D and oriented last layer edges.
+/// Returns `None` if edges aren't oriented or corners are already solved.
+pub fn recognize_coll(cube: &Cube) -> Option<Recognition<CollCase>> {
+    let state = LastLayerState::from_cube(cube)?;
+    if !state.are_edges_oriented() {
+        return None;
+    }
+    COLL_CASES.recognize(&state.corners_only())
+}
+
+/// Recognizes the ZBLL case of a cube with F2L solved on D and oriented last layer edges.
+/// Returns `None` if edges aren't oriented or corners are oriented, which makes it a PLL.
+pub fn recognize_zbll(cube: &Cube) -> Option<Recognition<ZbllCase>> {
+    let state = LastLayerState::from_cube(cube)?;
+    if !state.are_edges_oriented() {
+        return None;
+    }
+    ZBLL_CASES.recognize(&state)
+}
+
+/// Pieces and twists at each last layer position, corners first in `LAST_LAYER_LOCATIONS` order.
+/// Pieces are numbered by the index of their solved position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct LastLayerState {
+    pieces: [u8; 8],
+    twists: [u8; 8],
+}
+
+impl LastLayerState {
+    /// Piece left out of a case, AUFs don't change it.
+    const IGNORED: u8 = 4;
+
+    const SOLVED: LastLayerState = LastLayerState {
+        pieces: [0, 1, 2, 3, 0, 1, 2, 3],
+        twists: [0; 8],
+    };
+
+    /// Returns `None` if F2L isn't solved on D.
+    fn from_cube(cube: &Cube) -> Option<Self> {
+        if !cube.is_f2l_solved(Face::D) {
+            return None;
+        }
+
+        let mut state = LastLayerState { pieces: [0; 8], twists: [0; 8] };
+        for (i, location) in LAST_LAYER_LOCATIONS.iter().enumerate() {
+            let piece = cube.get_piece_at(location);
+            let group = if i < 4 { &LAST_LAYER_LOCATIONS[..4] } else { &LAST_LAYER_LOCATIONS[4..] };
+            state.pieces[i] = group
+                .iter()
+                .position(|l| *l == piece.get_original_location())
+                .expect("Last layer pieces stay in the last layer with F2L solved.") as u8;
+            state.twists[i] = piece.get_twist().number_of_twists();
+        }
+
+        Some(state)
+    }
+
+    fn to_cube(self) -> Cube {
+        let pieces: Vec<(CubePieceLocation, CubePiece)> = LAST_LAYER_LOCATIONS
+            .iter()
+            .enumerate()
+            .map(|(i, location)| {
+                let group_start = if i < 4 { 0 } else { 4 };
+                let original_location = LAST_LAYER_LOCATIONS[group_start + self.pieces[i] as usize];
+                let twist = [Twist::SOLVED, Twist::CW_120, Twist::CW_240][self.twists[i] as usize];
+                (*location, CubePiece::from_location(original_location).twisted(twist))
+            })
+            .collect();
+
+        Cube::with_pieces(&pieces)
+    }
+
+    /// State reached by turning U `pre_auf` times, setting up this state, then turning U `post_auf` times.
+    fn with_aufs(&self, pre_auf: usize, post_auf: usize) -> Self {
+        let mut state = LastLayerState { pieces: [0; 8], twists: [0; 8] };
+        for group_start in [0, 4] {
+            for i in 0..4 {
+                let target = group_start + (i + post_auf) % 4;
+                // Pieces were moved by the first U turns before this state moved them
+                let piece = self.pieces[group_start + i];
+                state.pieces[target] = if piece == Self::IGNORED { piece } else { (piece + 4 - (pre_auf % 4) as u8) % 4 };
+                state.twists[target] = self.twists[group_start + i];
+            }
+        }
+        state
+    }
+
+    /// Smallest state reachable with AUFs, with the AUFs reaching it.
+    fn canonical(&self) -> (LastLayerState, usize, usize) {
+        (0..4)
+            .flat_map(|pre_auf| (0..4).map(move |post_auf| (pre_auf, post_auf)))
+            .map(|(pre_auf, post_auf)| (self.with_aufs(pre_auf, post_auf), pre_auf, post_auf))
+            .min()
+            .expect("There are AUFs.")
+    }
+
+    fn orientation_only(&self) -> Self {
+        LastLayerState { pieces: [Self::IGNORED; 8], twists: self.twists }
+    }
+
+    fn corners_only(&self) -> Self {
+        let mut state = *self;
+        state.pieces[4..].copy_from_slice(&[Self::IGNORED; 4]);
+        state.twists[4..].copy_from_slice(&[0; 4]);
+        state
+    }
+
+    /// Edges solved, or with UR and UF swapped when the corners need it for a valid cube.
+    fn with_solvable_edges(&self) -> Self {
+        let corners: Vec<usize> = self.pieces[..4].iter().map(|piece| *piece as usize).collect();
+        let mut state = *self;
+        state.pieces[4..].copy_from_slice(&LastLayerState::SOLVED.pieces[4..]);
+        state.twists[4..].copy_from_slice(&[0; 4]);
+        if !permutations::permutation_parity(&corners) {
+            state.pieces.swap(4, 5);
+        }
+        state
+    }
+
+    fn is_oriented(&self) -> bool {
+        self.twists == [0; 8]
+    }
+
+    fn are_edges_oriented(&self) -> bool {
+        self.twists[4..] == [0; 4]
+    }
+
+    fn oll_case(&self) -> Option<OllCase> {
+        OLL_CASES.recognize(&self.orientation_only()).map(|recognition| recognition.case)
+    }
+}
+
+/// Cases indexed by their canonical state, with the AUFs from the canonical state to their reference state.
+struct CaseTable<C> {
+    cases: HashMap<LastLayerState, (C, usize, usize)>,
+    representatives: HashMap<C, LastLayerState>,
+}
+
+impl<C: Copy + Eq + std::hash::Hash> CaseTable<C> {
+    fn new(references: impl IntoIterator<Item = (C, LastLayerState)>) -> Self {
+        let mut cases = HashMap::new();
+        let mut representatives = HashMap::new();
+        for (case, reference) in references {
+            let (canonical, pre_auf, post_auf) = reference.canonical();
+            cases.entry(canonical).or_insert((case, pre_auf, post_auf));
+            representatives.entry(case).or_insert(reference);
+        }
+        Self { cases, representatives }
+    }
+
+    fn recognize(&self, state: &LastLayerState) -> Option<Recognition<C>> {
+        let (canonical, pre_auf, post_auf) = state.canonical();
+        let (case, reference_pre_auf, reference_post_auf) = self.cases.get(&canonical)?;
+
+        // state = U^(reference_pre_auf - pre_auf) reference U^(reference_post_auf - post_auf)
+        Some(Recognition {
+            case: *case,
+            pre_auf: AUFS[(post_auf + 4 - reference_post_auf) % 4],
+            post_auf: AUFS[(pre_auf + 4 - reference_pre_auf) % 4],
+        })
+    }
+}
+
+fn algorithm_state(algorithm: &str) -> LastLayerState {
+    let notation_moves = NotationMove::parse_array(algorithm).expect("Case algorithms are valid.");
+    let inverse: Vec<CubeMove> = Orientation::default()
+        .lower_all(&notation_moves)
+        .iter()
+        .rev()
+        .map(CubeMove::inverted)
+        .collect();
+
+    let mut cube = Cube::solved();
+    cube.apply_moves(&inverse);
+    LastLayerState::from_cube(&cube).expect("Case algorithms keep F2L solved.")
+}
+
+/// Every valid last layer state with oriented edges.
+fn oriented_edges_states() -> impl Iterator<Item = LastLayerState> {
+    let permutations = all_permutations();
+    let corner_twists: Vec<[u8; 4]> = (0..81u8)
+        .map(|i| [i % 3, (i / 3) % 3, (i / 9) % 3, (i / 27) % 3])
+        .filter(|twists| twists.iter().sum::<u8>() % 3 == 0)
+        .collect();
+
+    let mut states = Vec::new();
+    for corners in &permutations {
+        for edges in &permutations {
+            if permutations::permutation_parity(corners) != permutations::permutation_parity(edges) {
+                continue;
+            }
+            for twists in &corner_twists {
+                let mut state = LastLayerState { pieces: [0; 8], twists: [0; 8] };
+                for i in 0..4 {
+                    state.pieces[i] = corners[i] as u8;
+                    state.pieces[4 + i] = edges[i] as u8;
+                    state.twists[i] = twists[i];
+                }
+                states.push(state);
+            }
+        }
+    }
+    states.into_iter()
+}
+
+fn all_permutations() -> Vec<[usize; 4]> {
+    let mut permutations = Vec::with_capacity(24);
+    for a in 0..4 {
+        for b in (0..4).filter(|b| *b != a) {
+            for c in (0..4).filter(|c| *c != a && *c != b) {
+                permutations.push([a, b, c, 6 - a - b - c]);
+            }
+        }
+    }
+    permutations
+}
+
+/// Numbers the canonical states of each set in order.
+fn numbered_cases<C>(states: impl Iterator<Item = LastLayerState>, make_case: fn(OcllSet, u16) -> C) -> Vec<(C, LastLayerState)> {
+    let mut sets: HashMap<OcllSet, BTreeSet<LastLayerState>> = HashMap::new();
+    for state in states {
+        if let Some(set) = OcllSet::from_oll(state.oll_case()) {
+            sets.entry(set).or_default().insert(state.canonical().0);
+        }
+    }
+
+    sets.into_iter()
+        .flat_map(|(set, canonical_states)| {
+            canonical_states
+                .into_iter()
+                .enumerate()
+                .map(move |(i, state)| (make_case(set, i as u16 + 1), state))
+        })
+        .collect()
+}
+
+static OLL_CASES: LazyLock<CaseTable<OllCase>> = LazyLock::new(|| {
+    CaseTable::new(OLL_ALGORITHMS
+        .iter()
+        .enumerate()
+        .map(|(i, algorithm)| (OllCase(i as u8 + 1), algorithm_state(algorithm).orientation_only())))
+});
+
+static PLL_CASES: LazyLock<CaseTable<PllCase>> = LazyLock::new(|| {
+    CaseTable::new(PLL_ALGORITHMS
+        .iter()
+        .map(|(case, algorithm)| (*case, algorithm_state(algorithm))))
+});
+
+static COLL_CASES: LazyLock<CaseTable<CollCase>> = LazyLock::new(|| {
+    let solved_corners = LastLayerState::SOLVED.corners_only().canonical().0;
+    let states = oriented_edges_states()
+        .map(|state| state.corners_only())
+        .filter(|state| state.canonical().0 != solved_corners);
+    CaseTable::new(numbered_cases(states, |set, index| CollCase { set, index }))
+});
+
+static ZBLL_CASES: LazyLock<CaseTable<ZbllCase>> = LazyLock::new(|| {
+    let states = oriented_edges_states().filter(|state| !state.is_oriented());
+    CaseTable::new(numbered_cases(states, |set, index| ZbllCase { set, index }))
+});
+
+static LAST_LAYER_LOCATIONS: [CubePieceLocation; 8] = [
+    CubePieceLocation::URF,
+    CubePieceLocation::UFL,
+    CubePieceLocation::ULB,
+    CubePieceLocation::UBR,
+    CubePieceLocation::UR,
+    CubePieceLocation::UF,
+    CubePieceLocation::UL,
+    CubePieceLocation::UB,
+];
+
+static AUFS: [Option<CubeMove>; 4] = [None, Some(CubeMove::U), Some(CubeMove::U2), Some(CubeMove::Up)];
+
+/// One algorithm solving each OLL case, in case number order.
+static OLL_ALGORITHMS: [&str; 57] = [
+    "R U2 R2 F R F' U2 R' F R F'",
+    "F R U R' U' F' f R U R' U' f'",
+    "f R U R' U' f' U' F R U R' U' F'",
+    "f R U R' U' f' U F R U R' U' F'",
+    "l' U2 L U L' U l",
+    "r U2 R' U' R U' r'",
+    "r U R' U R U2 r'",
+    "l' U' L U' L' U2 l",
+    "R U R' U' R' F R2 U R' U' F'",
+    "R U R' U R' F R F' R U2 R'",
+    "r U R' U R' F R F' R U2 r'",
+    "M' R' U' R U' R' U2 R U' R r'",
+    "F U R U' R2 F' R U R U' R'",
+    "R' F R U R' F' R F U' F'",
+    "l' U' l L' U' L U l' U l",
+    "r U r' R U R' U' r U' r'",
+    "R U R' U R' F R F' U2 R' F R F'",
+    "r U R' U R U2 r2 U' R U' R' U2 r",
+    "r' R U R U R' U' M' R' F R F'",
+    "r U R' U' M2 U R U' R' U' M'",
+    "R U2 R' U' R U R' U' R U' R'",
+    "R U2 R2 U' R2 U' R2 U2 R",
+    "R2 D' R U2 R' D R U2 R",
+    "r U R' U' r' F R F'",
+    "F' r U R' U' r' F R",
+    "R U2 R' U' R U' R'",
+    "R U R' U R U2 R'",
+    "r U R' U' r' R U R U' R'",
+    "R U R' U' R U' R' F' U' F R U R'",
+    "F R' F R2 U' R' U' R U R' F2",
+    "R' U' F U R U' R' F' R",
+    "L U F' U' L' U L F L'",
+    "R U R' U' R' F R F'",
+    "R U R2 U' R' F R U R U' F'",
+    "R U2 R2 F R F' R U2 R'",
+    "L' U' L U' L' U L U L F' L' F",
+    "F R' F' R U R U' R'",
+    "R U R' U R U' R' U' R' F R F'",
+    "L F' L' U' L U F U' L'",
+    "R' F R U R' U' F' U R",
+    "R U R' U R U2 R' F R U R' U' F'",
+    "R' U' R U' R' U2 R F R U R' U' F'",
+    "F' U' L' U L F",
+    "F U R U' R' F'",
+    "F R U R' U' F'",
+    "R' U' R' F R F' U R",
+    "R' U' R' F R F' R' F R F' U R",
+    "F R U R' U' R U R' U' F'",
+    "r U' r2 U r2 U r2 U' r",
+    "r' U r2 U' r2 U' r2 U r'",
+    "F U R U' R' U R U' R' F'",
+    "R U R' U R U' B U' B' R'",
+    "l' U2 L U L' U' L U L' U l",
+    "r U2 R' U' R U R' U' R U' r'",
+    "R' F R U R U' R2 F' R2 U' R' U R U R'",
+    "r' U' r U' R' U R U' R' U R r' U r",
+    "R U R' U' M' U R U' r'",
+];
+
+/// One algorithm solving each PLL case.
+static PLL_ALGORITHMS: [(PllCase, &str); 21] = [
+    (PllCase::Aa, "x R' U R' D2 R U' R' D2 R2 x'"),
+    (PllCase::Ab, "x R2 D2 R U R' D2 R U' R x'"),
+    (PllCase::E, "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    (PllCase::F, "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    (PllCase::Ga, "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    (PllCase::Gb, "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    (PllCase::Gc, "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    (PllCase::Gd, "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    (PllCase::H, "M2 U M2 U2 M2 U M2"),
+    (PllCase::Ja, "R' U L' U2 R U' R' U2 R L"),
+    (PllCase::Jb, "R U R' F' R U R' U' R' F R2 U' R'"),
+    (PllCase::Na, "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'"),
+    (PllCase::Nb, "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    (PllCase::Ra, "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    (PllCase::Rb, "R2 F R U R U' R' F' R U2 R' U2 R"),
+    (PllCase::T, "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    (PllCase::Ua, "R U' R U R U R U' R' U' R2"),
+    (PllCase::Ub, "R2 U R U R' U' R' U' R' U R'"),
+    (PllCase::V, "R' U R' U' y R' F' R2 U' R' U R' F R F"),
+    (PllCase::Y, "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    (PllCase::Z, "M' U M2 U M2 U M' U2 M2"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::{Axis, TurnAmount};
+
+    fn lower(algorithm: &str) -> Vec<CubeMove> {
+        let notation_moves = NotationMove::parse_array(algorithm).expect("Algorithm is valid.");
+        Orientation::default().lower_all(&notation_moves)
+    }
+
+    /// Cubes set up in the case of `algorithm` with every pre and post AUF.
+    fn case_states(algorithm: &str) -> Vec<Cube> {
+        let inverse: Vec<CubeMove> = lower(algorithm).iter().rev().map(CubeMove::inverted).collect();
+        let mut cubes = Vec::new();
+        for pre_auf in AUFS {
+            for post_auf in AUFS {
+                let mut cube = Cube::solved();
+                cube.apply_moves(&pre_auf.into_iter().collect());
+                cube.apply_moves(&inverse);
+                cube.apply_moves(&post_auf.into_iter().collect());
+                cubes.push(cube);
+            }
+        }
+        cubes
+    }
+
+    fn apply_recognition<C>(cube: &Cube, recognition: &Recognition<C>, algorithm: &[CubeMove]) -> Cube {
+        let mut cube = cube.clone();
+        cube.apply_moves(&recognition.pre_auf.into_iter().collect());
+        cube.apply_moves(&algorithm.to_vec());
+        cube.apply_moves(&recognition.post_auf.into_iter().collect());
+        cube
+    }
+
+    #[test]
+    fn test_state_aufs_match_moves() {
+        let inverse: Vec<CubeMove> = lower(PllCase::Ja.algorithm()).iter().rev().map(CubeMove::inverted).collect();
+        let state = algorithm_state(PllCase::Ja.algorithm());
+        for (pre_auf, pre_move) in AUFS.iter().enumerate() {
+            for (post_auf, post_move) in AUFS.iter().enumerate() {
+                let mut cube = Cube::solved();
+                cube.apply_moves(&pre_move.iter().copied().collect());
+                cube.apply_moves(&inverse);
+                cube.apply_moves(&post_move.iter().copied().collect());
+                assert_eq!(LastLayerState::from_cube(&cube), Some(state.with_aufs(pre_auf, post_auf)));
+            }
+        }
+        assert_eq!(LastLayerState::from_cube(&state.to_cube()), Some(state));
+    }
+
+    #[test]
+    fn test_recognize_every_case() {
+        for (i, algorithm) in OLL_ALGORITHMS.iter().enumerate() {
+            let moves = lower(algorithm);
+            for cube in case_states(algorithm) {
+                let recognition = recognize_oll(&cube).expect("OLL should be recognized.");
+                assert_eq!(recognition.case, OllCase(i as u8 + 1));
+                assert!(apply_recognition(&cube, &recognition, &moves).is_face_solved(Face::U));
+            }
+        }
+
+        for (case, algorithm) in PLL_ALGORITHMS.iter() {
+            let moves = lower(algorithm);
+            for cube in case_states(algorithm) {
+                let recognition = recognize_pll(&cube).expect("PLL should be recognized.");
+                assert_eq!(recognition.case, *case, "{} perm should be recognized.", case);
+                assert!(apply_recognition(&cube, &recognition, &moves).is_solved());
+                assert!(recognize_oll(&cube).is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_recognize_rotated() {
+        let mut y = Orientation::default();
+        y.rotate(Axis::Y, TurnAmount::Clockwise);
+
+        for cube in case_states(PllCase::Gc.algorithm()) {
+            let rotated = cube.rotated(&y);
+            assert_eq!(recognize_pll(&rotated).map(|r| r.case), Some(PllCase::Gc));
+            assert_eq!(recognize_zbll(&rotated), None);
+        }
+        for cube in case_states(OllCase(45).algorithm()) {
+            assert_eq!(recognize_oll(&cube.rotated(&y)).map(|r| r.case), Some(OllCase(45)));
+        }
+    }
+
+    #[test]
+    fn test_coll_and_zbll_cases() {
+        let count = |set, cases: Vec<OcllSet>| cases.iter().filter(|s| **s == set).count();
+        let coll_sets: Vec<OcllSet> = COLL_CASES.representatives.keys().map(|case| case.set).collect();
+        let zbll_sets: Vec<OcllSet> = ZBLL_CASES.representatives.keys().map(|case| case.set).collect();
+
+        assert_eq!(coll_sets.len(), 42);
+        assert_eq!(count(OcllSet::H, coll_sets.clone()), 4);
+        assert_eq!(count(OcllSet::Oriented, coll_sets.clone()), 2);
+        assert_eq!(count(OcllSet::Sune, coll_sets), 6);
+        for case in COLL_CASES.representatives.keys() {
+            let representative = case.representative();
+            assert!(Cube::from_facelets(&representative.to_facelets()).is_some(), "{} should be a valid cube.", case);
+            assert_eq!(recognize_coll(&representative).map(|r| r.case), Some(*case));
+        }
+
+        assert_eq!(zbll_sets.len(), 472);
+        assert_eq!(count(OcllSet::H, zbll_sets.clone()), 40);
+        assert_eq!(count(OcllSet::Pi, zbll_sets), 72);
+
+        // Doing a Sune and a U perm sets up an anti-Sune ZBLL case, which is also a COLL case
+        let mut cube = Cube::solved();
+        cube.apply_moves(&lower("R U R' U R U2 R' U R U' R U R U R U' R' U' R2 U'"));
+        let recognition = recognize_zbll(&cube).expect("ZBLL should be recognized.");
+        assert_eq!(recognition.case.set, OcllSet::AntiSune);
+        let representative = LastLayerState::from_cube(&recognition.case.representative()).expect("F2L is solved.");
+        let post_auf = AUFS.iter().position(|auf| *auf == recognition.post_auf).expect("AUF is a U turn.");
+        let mut pre_aufed = cube.clone();
+        pre_aufed.apply_moves(&recognition.pre_auf.into_iter().collect());
+        // Doing the pre AUF leaves the representative set up after undoing the post AUF
+        assert_eq!(LastLayerState::from_cube(&pre_aufed), Some(representative.with_aufs((4 - post_auf) % 4, 0)));
+        assert_eq!(recognize_coll(&cube).map(|r| r.case.set), Some(OcllSet::AntiSune));
+    }
+
+    #[test]
+    fn test_recognize_outside_last_layer() {
+        let mut cube = Cube::solved();
+        assert!(recognize_pll(&cube).is_none());
+        assert!(recognize_coll(&cube).is_none());
+
+        cube.apply_moves(&vec![CubeMove::U]);
+        assert!(recognize_oll(&cube).is_none());
+        assert!(recognize_pll(&cube).is_none());
+
+        cube.apply_moves(&vec![CubeMove::R]);
+        assert!(recognize_oll(&cube).is_none());
+        assert!(recognize_zbll(&cube).is_none());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod cfop;
+pub mod last_layer;
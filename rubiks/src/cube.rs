@@ -2,9 +2,11 @@ use core::fmt;
 use rand::Rng;
 use std::collections::HashMap;
 use std::iter::once;
+use crate::faces::{Face, FaceMask};
 use crate::location::CubePieceLocation;
+use crate::notation::Orientation;
 use crate::piece::CubePiece;
-use crate::stickers::CubeStickerLocation;
+use crate::stickers::{CubeStickerLocation, FACELET_POSITIONS};
 use crate::twist::Twist;
 use crate::utils::permutations;
 
@@ -257,6 +259,14 @@ impl Cube {
         self.cubies.iter().filter(|(_, piece)| piece.is_edge())
     }
 
+    /// Corners then edges, always in the order of the facelet model: URF, UFL, ..., DRB, then UR, UF, ..., BR.
+    pub fn iter_pieces_in_order(&self) -> impl Iterator<Item = (CubePieceLocation, CubePiece)> + '_ {
+        CORNER_LOCATIONS
+            .iter()
+            .chain(EDGE_LOCATIONS.iter())
+            .map(|location| (*location, self.cubies[location]))
+    }
+
     pub fn get_piece_at(&self, sticker_location: &CubePieceLocation) -> CubePiece {
         self.cubies[sticker_location]
     }
@@ -269,12 +279,10 @@ impl Cube {
         let piece_twist = piece.get_twist();
         let r = if piece.is_corner() {
             let opposite = piece_twist.corner_opposite();
-            let ctwist = opposite.corner_add(sticker_location.twist);
-            ctwist
+            opposite.corner_add(sticker_location.twist)
         } else {
             let opposite = piece_twist.edge_opposite();
-            let etwist = opposite.edge_add(sticker_location.twist);
-            etwist
+            opposite.edge_add(sticker_location.twist)
         };
         CubeStickerLocation {
             piece_location: piece.get_original_location(),
@@ -282,28 +290,224 @@ impl Cube {
         }
     }
 
+    /// Face whose color is shown by the sticker at `sticker_location`.
+    pub fn get_sticker_color(&self, sticker_location: &CubeStickerLocation) -> Face {
+        self.get_sticker_origin(sticker_location).face()
+    }
+
+    pub fn is_cross_solved(&self, face: Face) -> bool {
+        EDGE_LOCATIONS
+            .iter()
+            .filter(|location| location.has_face(face))
+            .all(|location| self.cubies[location].is_solved(location))
+    }
+
+    /// Whether the F2L slot made of `corner` on the cross `face` and the edge next to it is solved.
+    pub fn is_f2l_slot_solved(&self, face: Face, corner: &CubePieceLocation) -> bool {
+        let edge_mask = corner.mask().and(FaceMask::from(face).not());
+        let edge = EDGE_LOCATIONS
+            .iter()
+            .find(|location| location.mask() == edge_mask)
+            .expect("Corner should be on the cross face.");
+
+        self.cubies[corner].is_solved(corner) && self.cubies[edge].is_solved(edge)
+    }
+
+    /// Corners of the F2L slots of the cross `face` which are solved.
+    pub fn solved_f2l_slots(&self, face: Face) -> Vec<CubePieceLocation> {
+        CORNER_LOCATIONS
+            .iter()
+            .filter(|location| location.has_face(face) && self.is_f2l_slot_solved(face, location))
+            .copied()
+            .collect()
+    }
+
+    pub fn is_f2l_solved(&self, face: Face) -> bool {
+        self.is_cross_solved(face) && self.solved_f2l_slots(face).len() == 4
+    }
+
+    /// Whether every sticker on `face` has the color of the face.
+    pub fn is_face_solved(&self, face: Face) -> bool {
+        FACELET_POSITIONS
+            .iter()
+            .filter(|sticker_location| sticker_location.face() == face)
+            .all(|sticker_location| self.get_sticker_color(sticker_location) == face)
+    }
+
+    /// Make a string representation of the cube in the form
+    /// "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB".
+    pub fn to_facelets(&self) -> String {
+        let mut cube_string = String::with_capacity(54);
+        let mut start = 0;
+
+        // On each face
+        for face in FACELET_FACES {
+            // For each stickers of the face
+            for (i, sticker_location) in FACELET_POSITIONS.iter().enumerate().skip(start).take(8) {
+                // Add sticker to the cube string
+                cube_string.push_str(&self.get_sticker_color(sticker_location).to_string());
+
+                // An add also center which we don't have in our impl
+                if i == start + 3 {
+                    cube_string.push_str(&face.to_string());
+                }
+            }
+
+            start += 8;
+        }
+
+        cube_string
+    }
+
+    /// Build a cube from a string in the form returned by `to_facelets`.
+    /// Returns `None` if the string doesn't describe a reachable cube.
+    pub fn from_facelets(facelets: &str) -> Option<Cube> {
+        let colors = facelets
+            .chars()
+            .map(face_from_facelet)
+            .collect::<Option<Vec<Face>>>()?;
+
+        if colors.len() != 54 {
+            return None;
+        }
+
+        // Centers are fixed
+        for (face_index, face) in FACELET_FACES.iter().enumerate() {
+            if colors[face_index * 9 + 4] != *face {
+                return None;
+            }
+        }
+
+        let sticker_color = |sticker_location: &CubeStickerLocation| -> Face {
+            let position = FACELET_POSITIONS
+                .iter()
+                .position(|location| location == sticker_location)
+                .expect("Every sticker has a facelet position.");
+            colors[position + position / 8 + usize::from(position % 8 >= 4)]
+        };
+
+        let mut cubies = HashMap::with_capacity(8 + 12);
+        for location in CORNER_LOCATIONS.iter().chain(EDGE_LOCATIONS.iter()) {
+            let location_colors: Vec<Face> = location
+                .get_faces()
+                .iter()
+                .enumerate()
+                .map(|(twist, _)| sticker_color(&CubeStickerLocation { piece_location: *location, twist: TWISTS[twist] }))
+                .collect();
+
+            cubies.insert(*location, piece_from_colors(location, &location_colors)?);
+        }
+
+        let cube = Cube { cubies };
+        cube.is_valid().then_some(cube)
+    }
+
+    /// The cube as seen when held in `orientation`, i.e. after the whole cube rotations it tracks.
+    pub fn rotated(&self, orientation: &Orientation) -> Cube {
+        let inverse_face = |color: Face| -> Face {
+            *FACELET_FACES
+                .iter()
+                .find(|position| orientation.face_at(**position) == color)
+                .expect("Orientation is a permutation of faces.")
+        };
+
+        let mut facelets = String::with_capacity(54);
+        let mut start = 0;
+        for face in FACELET_FACES {
+            for (i, sticker_location) in FACELET_POSITIONS.iter().enumerate().skip(start).take(8) {
+                let physical_faces: String = sticker_location
+                    .to_sticker_name()
+                    .chars()
+                    .map(|c| orientation.face_at(face_from_facelet(c).expect("Sticker names are made of faces.")).to_string())
+                    .collect();
+                let physical_location = FACELET_POSITIONS
+                    .iter()
+                    .find(|location| location.to_sticker_name() == physical_faces)
+                    .expect("Rotations keep sticker names valid.");
+
+                facelets.push_str(&inverse_face(self.get_sticker_color(physical_location)).to_string());
+                if i == start + 3 {
+                    facelets.push_str(&face.to_string());
+                }
+            }
+            start += 8;
+        }
+
+        Cube::from_facelets(&facelets).expect("A rotated cube is still valid.")
+    }
+
+    fn is_valid(&self) -> bool {
+        let corners_permutation: Vec<usize> = CORNER_LOCATIONS
+            .iter()
+            .filter_map(|location| CORNER_LOCATIONS.iter().position(|l| *l == self.cubies[location].get_original_location()))
+            .collect();
+        let edges_permutation: Vec<usize> = EDGE_LOCATIONS
+            .iter()
+            .filter_map(|location| EDGE_LOCATIONS.iter().position(|l| *l == self.cubies[location].get_original_location()))
+            .collect();
+
+        let is_permutation = |permutation: &Vec<usize>, size: usize| {
+            permutation.len() == size && (0..size).all(|i| permutation.contains(&i))
+        };
+        if !is_permutation(&corners_permutation, 8) || !is_permutation(&edges_permutation, 12) {
+            return false;
+        }
+
+        let corner_twist = self.iter_corners().fold(Twist::SOLVED, |sum, (_, corner)| sum.corner_add(corner.get_twist()));
+        let edge_twist = self.iter_edges().fold(Twist::SOLVED, |sum, (_, edge)| sum.edge_add(edge.get_twist()));
+
+        corner_twist == Twist::SOLVED
+            && edge_twist == Twist::SOLVED
+            && permutations::permutation_parity(&corners_permutation) == permutations::permutation_parity(&edges_permutation)
+    }
+
     pub fn solved() -> Cube {
         Cube {
             cubies: HashMap::from(SOLVED_CUBIES),
         }
     }
 
+    /// Solved cube with some pieces replaced, the caller is responsible for keeping it valid.
+    pub(crate) fn with_pieces(pieces: &[(CubePieceLocation, CubePiece)]) -> Cube {
+        let mut cubies = HashMap::from(SOLVED_CUBIES);
+        cubies.extend(pieces.iter().copied());
+        Cube { cubies }
+    }
+
     pub fn random_uniform<R: Rng>(rng: &mut R) -> Cube {
+        let all_locations: Vec<CubePieceLocation> = CORNER_LOCATIONS.iter().chain(EDGE_LOCATIONS.iter()).copied().collect();
+        Cube::random_uniform_subset(rng, &all_locations, &all_locations)
+    }
+
+    /// Random cube where only the pieces at `permuted` locations are shuffled between themselves,
+    /// and only the pieces ending at `twisted` locations get a random twist.
+    /// Every valid cube of this subset is equally likely.
+    pub fn random_uniform_subset<R: Rng>(rng: &mut R, permuted: &[CubePieceLocation], twisted: &[CubePieceLocation]) -> Cube {
         // Generate permutations of corners and edges
-        let corners_permutation = permutations::random_uniform_permutation::<8, R>(rng);
-        let mut edges_permutation = permutations::random_uniform_permutation::<12, R>(rng);
+        let permuted_corners: Vec<usize> = (0..8).filter(|i| permuted.contains(&CORNER_LOCATIONS[*i])).collect();
+        let permuted_edges: Vec<usize> = (0..12).filter(|i| permuted.contains(&EDGE_LOCATIONS[*i])).collect();
+        let mut corners_permutation = permutations::random_uniform_partial_permutation::<8, R>(&permuted_corners, rng);
+        let mut edges_permutation = permutations::random_uniform_partial_permutation::<12, R>(&permuted_edges, rng);
 
-        // Check permutatation parity
+        // Check permutatation parity, swapping two permuted pieces keeps the distribution uniform
         if permutations::permutation_parity(&corners_permutation) != permutations::permutation_parity(&edges_permutation) {
-            edges_permutation.swap(0, 1);
+            if permuted_edges.len() >= 2 {
+                edges_permutation.swap(permuted_edges[0], permuted_edges[1]);
+            } else {
+                corners_permutation.swap(permuted_corners[0], permuted_corners[1]);
+            }
         }
 
         let solved_cubies = HashMap::from(SOLVED_CUBIES);
         let mut cubies: HashMap<CubePieceLocation, CubePiece> = HashMap::with_capacity( 8 + 12);
 
         // Generate random twists
-        let mut corner_twists: [Twist; 8] = std::array::from_fn(|_| Twist::random_uniform_corner(rng));
-        let mut edge_twists: [Twist; 12] = std::array::from_fn(|_| Twist::random_uniform_edge(rng));
+        let mut corner_twists: [Twist; 8] = std::array::from_fn(|i| {
+            if twisted.contains(&CORNER_LOCATIONS[i]) { Twist::random_uniform_corner(rng) } else { Twist::SOLVED }
+        });
+        let mut edge_twists: [Twist; 12] = std::array::from_fn(|i| {
+            if twisted.contains(&EDGE_LOCATIONS[i]) { Twist::random_uniform_edge(rng) } else { Twist::SOLVED }
+        });
 
         let corner_twist_sum = corner_twists
             .iter()
@@ -312,22 +516,26 @@ impl Cube {
             .iter()
             .fold(Twist::SOLVED, |sum, twist| twist.edge_add(sum));
 
-        // Correct twists
-        corner_twists[0] = corner_twists[0].corner_add(corner_twist_sum.corner_opposite());
-        edge_twists[0] = edge_twists[0].edge_add(edge_twist_sum.edge_opposite());
+        // Correct twists on a twisted piece
+        if let Some(i) = (0..8).find(|i| twisted.contains(&CORNER_LOCATIONS[*i])) {
+            corner_twists[i] = corner_twists[i].corner_add(corner_twist_sum.corner_opposite());
+        }
+        if let Some(i) = (0..12).find(|i| twisted.contains(&EDGE_LOCATIONS[*i])) {
+            edge_twists[i] = edge_twists[i].edge_add(edge_twist_sum.edge_opposite());
+        }
 
         // Make cube
         for (solved_index, corner_index)  in corners_permutation.into_iter().enumerate() {
             let solved_location = CORNER_LOCATIONS[solved_index];
             let permuted_corner_location = CORNER_LOCATIONS[corner_index];
-            let twist = corner_twists[corner_index];
+            let twist = corner_twists[solved_index];
             cubies.insert(solved_location, solved_cubies[&permuted_corner_location].twisted(twist));
         }
 
         for (solved_index, edge_index) in edges_permutation.into_iter().enumerate() {
             let solved_edge_location = EDGE_LOCATIONS[solved_index];
             let permuted_edge_location = EDGE_LOCATIONS[edge_index];
-            let twist = edge_twists[edge_index];
+            let twist = edge_twists[solved_index];
             cubies.insert(solved_edge_location, solved_cubies[&permuted_edge_location].twisted(twist));
         }
 
@@ -335,6 +543,45 @@ impl Cube {
     }
 }
 
+/// Find which piece, and with which twist, shows `colors` on the stickers of `location`.
+fn piece_from_colors(location: &CubePieceLocation, colors: &[Face]) -> Option<CubePiece> {
+    let locations: &[CubePieceLocation] = if location.is_corner() { &CORNER_LOCATIONS } else { &EDGE_LOCATIONS };
+    let sticker_count = colors.len();
+
+    locations.iter().find_map(|original_location| {
+        let original_faces = original_location.get_faces();
+        (0..sticker_count)
+            .find(|twist| {
+                // The sticker of twist s shows the original sticker of twist (s - twist)
+                (0..sticker_count).all(|s| colors[s] == original_faces[(s + sticker_count - twist) % sticker_count])
+            })
+            .map(|twist| CubePiece::from_location(*original_location).twisted(TWISTS[twist]))
+    })
+}
+
+fn face_from_facelet(c: char) -> Option<Face> {
+    match c {
+        'U' => Some(Face::U),
+        'R' => Some(Face::R),
+        'F' => Some(Face::F),
+        'D' => Some(Face::D),
+        'L' => Some(Face::L),
+        'B' => Some(Face::B),
+        _ => None,
+    }
+}
+
+static FACELET_FACES: [Face; 6] = [
+    Face::U,
+    Face::R,
+    Face::F,
+    Face::D,
+    Face::L,
+    Face::B,
+];
+
+static TWISTS: [Twist; 3] = [Twist::SOLVED, Twist::CW_120, Twist::CW_240];
+
 static CORNER_LOCATIONS: [CubePieceLocation; 8] = [
     CubePieceLocation::URF,
     CubePieceLocation::UFL,
@@ -659,4 +906,83 @@ mod tests {
         assert_eq!(cube.get_sticker_origin(&CubeStickerLocation::RFU).to_sticker_name(), "RDF");
         assert_eq!(cube.get_sticker_origin(&CubeStickerLocation::FUR).to_sticker_name(), "DFR");
     }
+
+    #[test]
+    fn test_facelets_round_trip() {
+        let mut rng = rand::rng();
+
+        for _ in 0..100 {
+            let cube = Cube::random_uniform(&mut rng);
+            assert_eq!(Cube::from_facelets(&cube.to_facelets()), Some(cube));
+        }
+    }
+
+    #[test]
+    fn test_invalid_facelets() {
+        let solved = Cube::solved().to_facelets();
+
+        // Twisted corner
+        let mut twisted = solved.clone().into_bytes();
+        twisted.swap(8, 9);
+        twisted.swap(9, 20);
+        assert_eq!(Cube::from_facelets(&String::from_utf8(twisted).expect("Facelets are ascii.")), None);
+
+        // Swapped edges
+        let mut swapped = solved.clone().into_bytes();
+        swapped.swap(7, 19);
+        assert_eq!(Cube::from_facelets(&String::from_utf8(swapped).expect("Facelets are ascii.")), None);
+
+        assert_eq!(Cube::from_facelets("UUU"), None);
+    }
+
+    #[test]
+    fn test_rotated() {
+        use crate::notation::{Axis, TurnAmount};
+
+        let mut orientation = Orientation::default();
+        orientation.rotate(Axis::Y, TurnAmount::Clockwise);
+        assert!(Cube::solved().rotated(&orientation).is_solved());
+
+        // Seen after y, the R face is at the front
+        let mut cube = Cube::solved();
+        cube.apply_moves(&vec![CubeMove::R]);
+        let mut expected = Cube::solved();
+        expected.apply_moves(&vec![CubeMove::F]);
+        assert_eq!(cube.rotated(&orientation), expected);
+    }
+
+    #[test]
+    fn test_partial_solve_predicates() {
+        use CubeMove::*;
+
+        let mut cube = Cube::solved();
+        cube.apply_moves(&vec![U]);
+        assert!(cube.is_f2l_solved(Face::D));
+        assert!(!cube.is_cross_solved(Face::U));
+        assert!(cube.is_face_solved(Face::U));
+        assert!(!cube.is_face_solved(Face::F));
+
+        cube.apply_moves(&vec![R, U, Rp, Up]);
+        assert!(cube.is_cross_solved(Face::D));
+        assert!(!cube.is_f2l_solved(Face::D));
+        assert_eq!(cube.solved_f2l_slots(Face::D).len(), 3);
+        assert!(!cube.is_f2l_slot_solved(Face::D, &CubePieceLocation::DFR));
+    }
+
+    #[test]
+    fn test_pieces_in_order() {
+        use CubeMove::*;
+
+        let mut cube = Cube::solved();
+        cube.apply_moves(&vec![U]);
+        let pieces: Vec<(String, String)> = cube
+            .iter_pieces_in_order()
+            .map(|(location, piece)| (location.to_string(), piece.get_original_location().to_string()))
+            .collect();
+
+        assert_eq!(pieces.len(), 20);
+        assert_eq!(pieces[0], ("URF".to_string(), "UBR".to_string()));
+        assert_eq!(pieces[8], ("UR".to_string(), "UB".to_string()));
+        assert_eq!(pieces[19], ("BR".to_string(), "BR".to_string()));
+    }
 }
@@ -28,6 +28,19 @@ pub enum FaceMask {
     R,
 }
 
+impl From<Face> for FaceMask {
+    fn from(face: Face) -> Self {
+        match face {
+            Face::U => FaceMask::U,
+            Face::D => FaceMask::D,
+            Face::F => FaceMask::F,
+            Face::B => FaceMask::B,
+            Face::L => FaceMask::L,
+            Face::R => FaceMask::R,
+        }
+    }
+}
+
 impl FaceMask {
     pub const fn is_corner(self) -> bool {
         self.bits().count_ones() == STICKERS_ON_CORNERS as u32
@@ -0,0 +1,48 @@
+use std::ops::RangeInclusive;
+
+use crate::solvers::pochmann::{BldSummary, PochmannSolver};
+
+/// Constraints on the blindfolded features of a scramble, `None` accepts any value.
+#[derive(Clone, Default)]
+pub struct BldFilter {
+    /// Solver giving the buffers the features are computed for
+    pub solver: PochmannSolver,
+    pub corner_targets: Option<RangeInclusive<usize>>,
+    pub edge_targets: Option<RangeInclusive<usize>>,
+    pub corner_cycle_breaks: Option<RangeInclusive<usize>>,
+    pub edge_cycle_breaks: Option<RangeInclusive<usize>>,
+    pub twisted_corners: Option<RangeInclusive<usize>>,
+    pub flipped_edges: Option<RangeInclusive<usize>>,
+    pub parity: Option<bool>,
+}
+
+impl BldFilter {
+    pub fn matches(&self, summary: &BldSummary) -> bool {
+        fn in_range(range: &Option<RangeInclusive<usize>>, value: usize) -> bool {
+            range.as_ref().is_none_or(|range| range.contains(&value))
+        }
+
+        in_range(&self.corner_targets, summary.corner_targets)
+            && in_range(&self.edge_targets, summary.edge_targets)
+            && in_range(&self.corner_cycle_breaks, summary.corner_cycle_breaks)
+            && in_range(&self.edge_cycle_breaks, summary.edge_cycle_breaks)
+            && in_range(&self.twisted_corners, summary.twisted_corners)
+            && in_range(&self.flipped_edges, summary.flipped_edges)
+            && self.parity.is_none_or(|parity| parity == summary.parity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let summary = BldSummary { corner_targets: 7, edge_targets: 11, flipped_edges: 2, parity: true, ..Default::default() };
+
+        assert!(BldFilter::default().matches(&summary));
+        assert!(BldFilter { flipped_edges: Some(2..=2), corner_targets: Some(6..=8), ..Default::default() }.matches(&summary));
+        assert!(!BldFilter { parity: Some(false), ..Default::default() }.matches(&summary));
+        assert!(!BldFilter { edge_targets: Some(0..=10), ..Default::default() }.matches(&summary));
+    }
+}
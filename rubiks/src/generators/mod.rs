@@ -1 +1,2 @@
+pub mod bld;
 pub mod scramble;
\ No newline at end of file
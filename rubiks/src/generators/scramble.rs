@@ -1,24 +1,302 @@
 use rand::Rng;
 
-use crate::solvers::kociemba::KociembaSolver;
+use crate::generators::bld::BldFilter;
+use crate::location::CubePieceLocation;
+use crate::solvers::kociemba::{KociembaError, KociembaSolver};
+use crate::solvers::pocket::{PocketCube, PocketSolver};
 use crate::cube::{Cube, CubeMove};
+use crate::faces::Face;
+use crate::notation::{NotationMove, TurnAmount};
 
-#[derive(Clone, Copy)]
+/// Set of cube states a scramble is drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScrambleSubset {
+    /// Any state
+    #[default]
+    Full,
+    /// F2L solved on D
+    LastLayer,
+    /// F2L solved on D and last layer edges oriented
+    Zbll,
+    /// F2L solved on D except the front right slot
+    LastSlot,
+    /// F2L solved on D and last layer oriented
+    Pll,
+    /// F2L solved on D and last layer permuted
+    Oll,
+    /// Edges solved
+    CornersOnly,
+    /// Corners solved
+    EdgesOnly,
+    /// Cross solved on D
+    CrossSolved,
+}
+
+impl ScrambleSubset {
+    /// Uniformly random cube in the subset.
+    pub fn random_cube(&self, rng: &mut impl Rng) -> Cube {
+        let (permuted, twisted): (&[CubePieceLocation], &[CubePieceLocation]) = match self {
+            ScrambleSubset::Full => (&ALL_PIECES, &ALL_PIECES),
+            ScrambleSubset::LastLayer => (&LAST_LAYER_PIECES, &LAST_LAYER_PIECES),
+            ScrambleSubset::Zbll => (&LAST_LAYER_PIECES, &LAST_LAYER_PIECES[..4]),
+            ScrambleSubset::LastSlot => (&LAST_SLOT_PIECES, &LAST_SLOT_PIECES),
+            ScrambleSubset::Pll => (&LAST_LAYER_PIECES, &[]),
+            ScrambleSubset::Oll => (&[], &LAST_LAYER_PIECES),
+            ScrambleSubset::CornersOnly => (&ALL_PIECES[..8], &ALL_PIECES[..8]),
+            ScrambleSubset::EdgesOnly => (&ALL_PIECES[8..], &ALL_PIECES[8..]),
+            ScrambleSubset::CrossSolved => (&NOT_CROSS_PIECES, &NOT_CROSS_PIECES),
+        };
+
+        Cube::random_uniform_subset(rng, permuted, twisted)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
 pub struct ScrambleGenerator {
     solver: KociembaSolver
 }
 
-impl Default for ScrambleGenerator {
-    fn default() -> Self {
-        Self {
-            solver: KociembaSolver::default()
+impl ScrambleGenerator {
+    pub fn new(solver: KociembaSolver) -> Self {
+        Self { solver }
+    }
+
+    /// Fails only if the solver can't search, its `max_size` being too short.
+    pub fn generate(&self, rng: &mut impl Rng) -> Result<Vec<CubeMove>, KociembaError> {
+        let cube = Cube::random_uniform(rng);
+        self.solver.solve(&cube)
+    }
+
+    /// Scramble leading to a uniformly random 2x2 state, in `<U, R, F>` moves.
+    pub fn generate_pocket(&self, rng: &mut impl Rng) -> Vec<CubeMove> {
+        let cube = PocketCube::random_uniform(rng);
+        let solution = PocketSolver.solve(&cube);
+        solution.iter().rev().map(CubeMove::inverted).collect()
+    }
+
+    /// Scramble leading to a uniformly random state of `subset`.
+    pub fn generate_subset(&self, subset: ScrambleSubset, rng: &mut impl Rng) -> Result<Vec<CubeMove>, KociembaError> {
+        let cube = subset.random_cube(rng);
+        self.scramble_to(&cube)
+    }
+
+    /// Scramble whose blindfolded features match `filter`, found by rejecting random states.
+    /// Returns `None` if no state matched after `BLD_MAX_ATTEMPTS` tries, the filter being too strict.
+    pub fn generate_bld(&self, filter: &BldFilter, rng: &mut impl Rng) -> Result<Option<Vec<CubeMove>>, KociembaError> {
+        (0..BLD_MAX_ATTEMPTS)
+            .map(|_| Cube::random_uniform(rng))
+            .find(|cube| filter.matches(&filter.solver.summarize(cube)))
+            .map(|cube| self.scramble_to(&cube))
+            .transpose()
+    }
+
+    /// Random state scramble followed by wide moves leaving the cube in one of its 24 orientations,
+    /// so blindfolded solvers can't rely on a fixed orientation.
+    pub fn generate_blindfolded(&self, rng: &mut impl Rng) -> Result<Vec<NotationMove>, KociembaError> {
+        let mut scramble: Vec<NotationMove> = self.generate(rng)?.into_iter().map(NotationMove::from).collect();
+
+        let top = BLD_TOP_MOVES[rng.random_range(0..BLD_TOP_MOVES.len())];
+        let front = BLD_FRONT_MOVES[rng.random_range(0..BLD_FRONT_MOVES.len())];
+        scramble.extend(top.into_iter().chain(front));
+        Ok(scramble)
+    }
+
+    fn scramble_to(&self, cube: &Cube) -> Result<Vec<CubeMove>, KociembaError> {
+        if cube.is_solved() {
+            return Ok(Vec::new());
         }
+
+        let solution = self.solver.solve(cube)?;
+        Ok(solution.iter().rev().map(CubeMove::inverted).collect())
     }
 }
 
-impl ScrambleGenerator {
-    pub fn generate(&self, rng: &mut impl Rng) -> Vec<CubeMove> {
-        let cube = Cube::random_uniform(rng);
-        self.solver.solve(&cube).expect("Random uniform cube should have a solution")
+const BLD_MAX_ATTEMPTS: usize = 10_000;
+
+/// Wide moves bringing each of the 6 centers on top
+const BLD_TOP_MOVES: [Option<NotationMove>; 6] = [
+    None,
+    Some(NotationMove::Wide(Face::R, TurnAmount::Clockwise)),
+    Some(NotationMove::Wide(Face::R, TurnAmount::Double)),
+    Some(NotationMove::Wide(Face::R, TurnAmount::CounterClockwise)),
+    Some(NotationMove::Wide(Face::F, TurnAmount::Clockwise)),
+    Some(NotationMove::Wide(Face::F, TurnAmount::CounterClockwise)),
+];
+
+/// Wide moves bringing each of the 4 side centers in front
+const BLD_FRONT_MOVES: [Option<NotationMove>; 4] = [
+    None,
+    Some(NotationMove::Wide(Face::U, TurnAmount::Clockwise)),
+    Some(NotationMove::Wide(Face::U, TurnAmount::Double)),
+    Some(NotationMove::Wide(Face::U, TurnAmount::CounterClockwise)),
+];
+
+static ALL_PIECES: [CubePieceLocation; 20] = [
+    CubePieceLocation::URF,
+    CubePieceLocation::UFL,
+    CubePieceLocation::ULB,
+    CubePieceLocation::UBR,
+    CubePieceLocation::DFR,
+    CubePieceLocation::DLF,
+    CubePieceLocation::DBL,
+    CubePieceLocation::DRB,
+    CubePieceLocation::UR,
+    CubePieceLocation::UF,
+    CubePieceLocation::UL,
+    CubePieceLocation::UB,
+    CubePieceLocation::DR,
+    CubePieceLocation::DF,
+    CubePieceLocation::DL,
+    CubePieceLocation::DB,
+    CubePieceLocation::FR,
+    CubePieceLocation::FL,
+    CubePieceLocation::BL,
+    CubePieceLocation::BR,
+];
+
+/// Corners first
+static LAST_LAYER_PIECES: [CubePieceLocation; 8] = [
+    CubePieceLocation::URF,
+    CubePieceLocation::UFL,
+    CubePieceLocation::ULB,
+    CubePieceLocation::UBR,
+    CubePieceLocation::UR,
+    CubePieceLocation::UF,
+    CubePieceLocation::UL,
+    CubePieceLocation::UB,
+];
+
+static LAST_SLOT_PIECES: [CubePieceLocation; 10] = [
+    CubePieceLocation::URF,
+    CubePieceLocation::UFL,
+    CubePieceLocation::ULB,
+    CubePieceLocation::UBR,
+    CubePieceLocation::DFR,
+    CubePieceLocation::UR,
+    CubePieceLocation::UF,
+    CubePieceLocation::UL,
+    CubePieceLocation::UB,
+    CubePieceLocation::FR,
+];
+
+static NOT_CROSS_PIECES: [CubePieceLocation; 16] = [
+    CubePieceLocation::URF,
+    CubePieceLocation::UFL,
+    CubePieceLocation::ULB,
+    CubePieceLocation::UBR,
+    CubePieceLocation::DFR,
+    CubePieceLocation::DLF,
+    CubePieceLocation::DBL,
+    CubePieceLocation::DRB,
+    CubePieceLocation::UR,
+    CubePieceLocation::UF,
+    CubePieceLocation::UL,
+    CubePieceLocation::UB,
+    CubePieceLocation::FR,
+    CubePieceLocation::FL,
+    CubePieceLocation::BL,
+    CubePieceLocation::BR,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twist::Twist;
+
+    fn is_piece_solved(cube: &Cube, location: &CubePieceLocation) -> bool {
+        let piece = cube.get_piece_at(location);
+        piece.get_original_location() == *location && piece.get_twist() == Twist::SOLVED
+    }
+
+    #[test]
+    fn test_random_subset_cubes() {
+        let mut rng = rand::rng();
+
+        for _ in 0..50 {
+            let cube = ScrambleSubset::Zbll.random_cube(&mut rng);
+            assert!(cube.is_f2l_solved(Face::D));
+            assert!(LAST_LAYER_PIECES[4..].iter().all(|l| cube.get_piece_at(l).get_twist() == Twist::SOLVED));
+
+            let cube = ScrambleSubset::Pll.random_cube(&mut rng);
+            assert!(cube.is_f2l_solved(Face::D) && cube.is_face_solved(Face::U));
+
+            let cube = ScrambleSubset::Oll.random_cube(&mut rng);
+            assert!(LAST_LAYER_PIECES.iter().all(|l| cube.get_piece_at(l).get_original_location() == *l));
+
+            let cube = ScrambleSubset::LastSlot.random_cube(&mut rng);
+            assert!(cube.is_cross_solved(Face::D));
+            let slots = cube.solved_f2l_slots(Face::D);
+            assert!([CubePieceLocation::DLF, CubePieceLocation::DBL, CubePieceLocation::DRB].iter().all(|slot| slots.contains(slot)));
+
+            let cube = ScrambleSubset::CornersOnly.random_cube(&mut rng);
+            assert!(ALL_PIECES[8..].iter().all(|l| is_piece_solved(&cube, l)));
+
+            let cube = ScrambleSubset::EdgesOnly.random_cube(&mut rng);
+            assert!(ALL_PIECES[..8].iter().all(|l| is_piece_solved(&cube, l)));
+
+            let cube = ScrambleSubset::CrossSolved.random_cube(&mut rng);
+            assert!(cube.is_cross_solved(Face::D));
+
+            for subset in [ScrambleSubset::Full, ScrambleSubset::LastLayer, ScrambleSubset::CornersOnly, ScrambleSubset::EdgesOnly] {
+                let cube = subset.random_cube(&mut rng);
+                assert!(Cube::from_facelets(&cube.to_facelets()).is_some(), "{:?} cube should be valid.", subset);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_with_short_solver_fails() {
+        let mut rng = rand::rng();
+        let generator = ScrambleGenerator::new(KociembaSolver { max_size: 10, timeout: 1.0 });
+
+        assert_eq!(generator.generate(&mut rng), Err(KociembaError::MaxSizeTooShort(10)));
+        assert_eq!(generator.generate_subset(ScrambleSubset::LastLayer, &mut rng), Err(KociembaError::MaxSizeTooShort(10)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_generate_subset() {
+        let mut rng = rand::rng();
+        let generator = ScrambleGenerator::default();
+
+        let mut cube = Cube::solved();
+        cube.apply_moves(&generator.generate_subset(ScrambleSubset::LastLayer, &mut rng).expect("Default solver should search."));
+        assert!(cube.is_f2l_solved(Face::D));
+    }
+
+    #[test]
+    fn test_generate_pocket() {
+        let mut rng = rand::rng();
+        let generator = ScrambleGenerator::default();
+
+        for _ in 0..10 {
+            let scramble = generator.generate_pocket(&mut rng);
+            let cube = PocketCube::solved().applied(&scramble).expect("Scramble should only turn U, R and F.");
+            assert!(PocketSolver.solve(&cube).len() <= scramble.len());
+        }
+    }
+
+    #[test]
+    fn test_generate_blindfolded() {
+        let mut rng = rand::rng();
+        let generator = ScrambleGenerator::default();
+
+        let scramble = generator.generate_blindfolded(&mut rng).expect("Default solver should search.");
+        let wide_count = scramble.iter().filter(|m| matches!(m, NotationMove::Wide(_, _))).count();
+        assert!(wide_count <= 2);
+        assert!(scramble[..scramble.len() - wide_count].iter().all(|m| matches!(m, NotationMove::Face(_, _))));
+    }
+
+    #[test]
+    fn test_generate_bld() {
+        let mut rng = rand::rng();
+        let generator = ScrambleGenerator::default();
+        let filter = BldFilter { parity: Some(false), flipped_edges: Some(1..=1), ..Default::default() };
+
+        let mut cube = Cube::solved();
+        cube.apply_moves(&generator.generate_bld(&filter, &mut rng).expect("Default solver should search.").expect("Filter should be satisfiable."));
+        assert!(filter.matches(&filter.solver.summarize(&cube)));
+
+        let impossible = BldFilter { corner_targets: Some(1..=1), parity: Some(false), ..Default::default() };
+        assert_eq!(generator.generate_bld(&impossible, &mut rng), Ok(None));
+    }
+}
@@ -1,6 +1,9 @@
+pub mod analysis;
 pub mod cube;
 pub mod faces;
 pub mod location;
+pub mod notation;
+pub mod reconstruction;
 pub mod solvers;
 pub mod stickers;
 pub mod twist;
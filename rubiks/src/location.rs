@@ -1,3 +1,4 @@
+use core::fmt;
 use crate::faces::{Face, FaceMask};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -43,6 +44,10 @@ impl CubePieceLocation {
         self.0.is_edge()
     }
 
+    pub fn has_face(&self, face: Face) -> bool {
+        self.0.contains(FaceMask::from(face))
+    }
+
     pub fn get_faces(&self) -> Vec<Face> {
         use Face::*;
         match *self {
@@ -70,3 +75,10 @@ impl CubePieceLocation {
         }
     }
 }
+
+/// Faces of the location in the usual order, like `URF` or `UR`.
+impl fmt::Display for CubePieceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.get_faces().iter().try_for_each(|face| write!(f, "{}", face))
+    }
+}
@@ -0,0 +1,451 @@
+use core::fmt;
+use crate::cube::CubeMove;
+use crate::faces::Face;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TurnAmount {
+    Clockwise,
+    Double,
+    CounterClockwise,
+}
+
+impl TurnAmount {
+    pub fn inverted(&self) -> Self {
+        match self {
+            TurnAmount::Clockwise => TurnAmount::CounterClockwise,
+            TurnAmount::Double => TurnAmount::Double,
+            TurnAmount::CounterClockwise => TurnAmount::Clockwise,
+        }
+    }
+
+    fn parse(str: &str) -> Option<Self> {
+        match str {
+            "" => Some(TurnAmount::Clockwise),
+            "2" | "2'" => Some(TurnAmount::Double),
+            "'" => Some(TurnAmount::CounterClockwise),
+            _ => None,
+        }
+    }
+
+    /// Returns `None` for a multiple of four turns.
+    fn from_quarter_turns(turns: usize) -> Option<Self> {
+        match turns % 4 {
+            1 => Some(TurnAmount::Clockwise),
+            2 => Some(TurnAmount::Double),
+            3 => Some(TurnAmount::CounterClockwise),
+            _ => None,
+        }
+    }
+
+    fn quarter_turns(&self) -> usize {
+        match self {
+            TurnAmount::Clockwise => 1,
+            TurnAmount::Double => 2,
+            TurnAmount::CounterClockwise => 3,
+        }
+    }
+}
+
+impl fmt::Display for TurnAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            TurnAmount::Clockwise => "",
+            TurnAmount::Double => "2",
+            TurnAmount::CounterClockwise => "'",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slice {
+    M,
+    E,
+    S,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// A move as written in standard notation, including wide moves, slice moves
+/// and whole cube rotations which `CubeMove` can't express because our cube
+/// has fixed centers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotationMove {
+    Face(Face, TurnAmount),
+    Wide(Face, TurnAmount),
+    Slice(Slice, TurnAmount),
+    Rotation(Axis, TurnAmount),
+}
+
+impl NotationMove {
+    pub fn parse(str: &str) -> Option<Self> {
+        let mut chars = str.chars();
+        let letter = chars.next()?;
+        let rest = chars.as_str();
+
+        let (wide_suffix, amount_str) = match rest.strip_prefix('w') {
+            Some(amount_str) => (true, amount_str),
+            None => (false, rest),
+        };
+        let amount = TurnAmount::parse(amount_str)?;
+
+        let notation_move = match letter {
+            'U' | 'D' | 'F' | 'B' | 'L' | 'R' => {
+                let face = face_from_char(letter)?;
+                if wide_suffix { NotationMove::Wide(face, amount) } else { NotationMove::Face(face, amount) }
+            },
+            'u' | 'd' | 'f' | 'b' | 'l' | 'r' if !wide_suffix => {
+                NotationMove::Wide(face_from_char(letter.to_ascii_uppercase())?, amount)
+            },
+            'M' if !wide_suffix => NotationMove::Slice(Slice::M, amount),
+            'E' if !wide_suffix => NotationMove::Slice(Slice::E, amount),
+            'S' if !wide_suffix => NotationMove::Slice(Slice::S, amount),
+            'x' if !wide_suffix => NotationMove::Rotation(Axis::X, amount),
+            'y' if !wide_suffix => NotationMove::Rotation(Axis::Y, amount),
+            'z' if !wide_suffix => NotationMove::Rotation(Axis::Z, amount),
+            _ => return None,
+        };
+
+        Some(notation_move)
+    }
+
+    pub fn parse_array(str: &str) -> Option<Vec<Self>> {
+        str.split_whitespace().map(Self::parse).collect()
+    }
+
+    pub fn inverted(&self) -> Self {
+        match *self {
+            NotationMove::Face(face, amount) => NotationMove::Face(face, amount.inverted()),
+            NotationMove::Wide(face, amount) => NotationMove::Wide(face, amount.inverted()),
+            NotationMove::Slice(slice, amount) => NotationMove::Slice(slice, amount.inverted()),
+            NotationMove::Rotation(axis, amount) => NotationMove::Rotation(axis, amount.inverted()),
+        }
+    }
+
+    pub fn is_rotation(&self) -> bool {
+        matches!(self, NotationMove::Rotation(_, _))
+    }
+}
+
+impl From<CubeMove> for NotationMove {
+    fn from(cube_move: CubeMove) -> Self {
+        use TurnAmount::*;
+        match cube_move {
+            CubeMove::U => NotationMove::Face(Face::U, Clockwise),
+            CubeMove::U2 => NotationMove::Face(Face::U, Double),
+            CubeMove::Up => NotationMove::Face(Face::U, CounterClockwise),
+            CubeMove::R => NotationMove::Face(Face::R, Clockwise),
+            CubeMove::R2 => NotationMove::Face(Face::R, Double),
+            CubeMove::Rp => NotationMove::Face(Face::R, CounterClockwise),
+            CubeMove::F => NotationMove::Face(Face::F, Clockwise),
+            CubeMove::F2 => NotationMove::Face(Face::F, Double),
+            CubeMove::Fp => NotationMove::Face(Face::F, CounterClockwise),
+            CubeMove::D => NotationMove::Face(Face::D, Clockwise),
+            CubeMove::D2 => NotationMove::Face(Face::D, Double),
+            CubeMove::Dp => NotationMove::Face(Face::D, CounterClockwise),
+            CubeMove::L => NotationMove::Face(Face::L, Clockwise),
+            CubeMove::L2 => NotationMove::Face(Face::L, Double),
+            CubeMove::Lp => NotationMove::Face(Face::L, CounterClockwise),
+            CubeMove::B => NotationMove::Face(Face::B, Clockwise),
+            CubeMove::B2 => NotationMove::Face(Face::B, Double),
+            CubeMove::Bp => NotationMove::Face(Face::B, CounterClockwise),
+        }
+    }
+}
+
+impl fmt::Display for NotationMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationMove::Face(face, amount) => write!(f, "{}{}", face, amount),
+            NotationMove::Wide(face, amount) => write!(f, "{}{}", face.to_string().to_lowercase(), amount),
+            NotationMove::Slice(slice, amount) => write!(f, "{:?}{}", slice, amount),
+            NotationMove::Rotation(axis, amount) => write!(f, "{}{}", format!("{:?}", axis).to_lowercase(), amount),
+        }
+    }
+}
+
+/// Tracks which original face sits at each position while rotations are applied,
+/// so that moves written in the rotated frame can be translated to `CubeMove`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orientation {
+    // Indexed by position in FACES order, gives the original face at that position
+    faces: [Face; 6],
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Self { faces: FACES }
+    }
+}
+
+impl Orientation {
+    pub fn face_at(&self, position: Face) -> Face {
+        self.faces[face_index(position)]
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.faces == FACES
+    }
+
+    pub fn rotate(&mut self, axis: Axis, amount: TurnAmount) {
+        // Each cycle lists positions so that the face at a position moves to the next one
+        let cycle = match axis {
+            Axis::X => [Face::F, Face::U, Face::B, Face::D],
+            Axis::Y => [Face::F, Face::L, Face::B, Face::R],
+            Axis::Z => [Face::U, Face::R, Face::D, Face::L],
+        };
+
+        for _ in 0..amount.quarter_turns() {
+            let previous = self.faces;
+            for i in 0..4 {
+                let from = cycle[i];
+                let to = cycle[(i + 1) % 4];
+                self.faces[face_index(to)] = previous[face_index(from)];
+            }
+        }
+    }
+
+    /// Translates a move written in this orientation to moves on our fixed center cube,
+    /// updating the orientation for wide moves, slice moves and rotations.
+    pub fn lower(&mut self, notation_move: NotationMove) -> Vec<CubeMove> {
+        match notation_move {
+            NotationMove::Face(face, amount) => vec![self.face_move(face, amount)],
+            NotationMove::Wide(face, amount) => {
+                // A wide move is the opposite face turned the same way plus a rotation
+                let (axis, axis_amount) = face_axis(face, amount);
+                let cube_move = self.face_move(opposite_face(face), amount);
+                self.rotate(axis, axis_amount);
+                vec![cube_move]
+            },
+            NotationMove::Slice(slice, amount) => {
+                // M = R L' x', E = U D' y', S = F' B z
+                let (first, second, axis, axis_amount) = match slice {
+                    Slice::M => (Face::R, Face::L, Axis::X, amount.inverted()),
+                    Slice::E => (Face::U, Face::D, Axis::Y, amount.inverted()),
+                    Slice::S => (Face::B, Face::F, Axis::Z, amount),
+                };
+                let cube_moves = vec![
+                    self.face_move(first, amount),
+                    self.face_move(second, amount.inverted()),
+                ];
+                self.rotate(axis, axis_amount);
+                cube_moves
+            },
+            NotationMove::Rotation(axis, amount) => {
+                self.rotate(axis, amount);
+                vec![]
+            },
+        }
+    }
+
+    pub fn lower_all(&mut self, notation_moves: &[NotationMove]) -> Vec<CubeMove> {
+        notation_moves
+            .iter()
+            .flat_map(|notation_move| self.lower(*notation_move))
+            .collect()
+    }
+
+    fn face_move(&self, position: Face, amount: TurnAmount) -> CubeMove {
+        face_cube_move(self.face_at(position), amount)
+    }
+}
+
+pub fn face_cube_move(face: Face, amount: TurnAmount) -> CubeMove {
+    use TurnAmount::*;
+    match (face, amount) {
+        (Face::U, Clockwise) => CubeMove::U,
+        (Face::U, Double) => CubeMove::U2,
+        (Face::U, CounterClockwise) => CubeMove::Up,
+        (Face::R, Clockwise) => CubeMove::R,
+        (Face::R, Double) => CubeMove::R2,
+        (Face::R, CounterClockwise) => CubeMove::Rp,
+        (Face::F, Clockwise) => CubeMove::F,
+        (Face::F, Double) => CubeMove::F2,
+        (Face::F, CounterClockwise) => CubeMove::Fp,
+        (Face::D, Clockwise) => CubeMove::D,
+        (Face::D, Double) => CubeMove::D2,
+        (Face::D, CounterClockwise) => CubeMove::Dp,
+        (Face::L, Clockwise) => CubeMove::L,
+        (Face::L, Double) => CubeMove::L2,
+        (Face::L, CounterClockwise) => CubeMove::Lp,
+        (Face::B, Clockwise) => CubeMove::B,
+        (Face::B, Double) => CubeMove::B2,
+        (Face::B, CounterClockwise) => CubeMove::Bp,
+    }
+}
+
+/// Merges consecutive moves of the same face and drops the ones cancelling out,
+/// looking through a move of the opposite face since they commute, e.g. `R L R'` becomes `L`.
+/// The length of the result is the HTM count of the sequence.
+pub fn simplify(moves: &[CubeMove]) -> Vec<CubeMove> {
+    let mut simplified: Vec<(Face, TurnAmount)> = Vec::with_capacity(moves.len());
+
+    for cube_move in moves {
+        let NotationMove::Face(face, amount) = NotationMove::from(*cube_move) else {
+            unreachable!("Cube moves are face moves.");
+        };
+
+        let len = simplified.len();
+        let same_face_index = if len >= 1 && simplified[len - 1].0 == face {
+            Some(len - 1)
+        } else if len >= 2 && simplified[len - 1].0 == opposite_face(face) && simplified[len - 2].0 == face {
+            Some(len - 2)
+        } else {
+            None
+        };
+
+        match same_face_index {
+            Some(i) => match TurnAmount::from_quarter_turns(simplified[i].1.quarter_turns() + amount.quarter_turns()) {
+                Some(merged) => simplified[i].1 = merged,
+                None => {
+                    simplified.remove(i);
+                },
+            },
+            None => simplified.push((face, amount)),
+        }
+    }
+
+    simplified
+        .into_iter()
+        .map(|(face, amount)| face_cube_move(face, amount))
+        .collect()
+}
+
+pub fn opposite_face(face: Face) -> Face {
+    match face {
+        Face::U => Face::D,
+        Face::D => Face::U,
+        Face::F => Face::B,
+        Face::B => Face::F,
+        Face::L => Face::R,
+        Face::R => Face::L,
+    }
+}
+
+/// Rotation turning the whole cube the same way as `face` turned by `amount`.
+fn face_axis(face: Face, amount: TurnAmount) -> (Axis, TurnAmount) {
+    match face {
+        Face::R => (Axis::X, amount),
+        Face::L => (Axis::X, amount.inverted()),
+        Face::U => (Axis::Y, amount),
+        Face::D => (Axis::Y, amount.inverted()),
+        Face::F => (Axis::Z, amount),
+        Face::B => (Axis::Z, amount.inverted()),
+    }
+}
+
+fn face_from_char(c: char) -> Option<Face> {
+    match c {
+        'U' => Some(Face::U),
+        'D' => Some(Face::D),
+        'F' => Some(Face::F),
+        'B' => Some(Face::B),
+        'L' => Some(Face::L),
+        'R' => Some(Face::R),
+        _ => None,
+    }
+}
+
+fn face_index(face: Face) -> usize {
+    match face {
+        Face::U => 0,
+        Face::D => 1,
+        Face::F => 2,
+        Face::B => 3,
+        Face::L => 4,
+        Face::R => 5,
+    }
+}
+
+static FACES: [Face; 6] = [
+    Face::U,
+    Face::D,
+    Face::F,
+    Face::B,
+    Face::L,
+    Face::R,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+    use crate::location::CubePieceLocation;
+    use crate::piece::CubePiece;
+
+    fn apply_notation(moves_str: &str) -> Cube {
+        let notation_moves = NotationMove::parse_array(moves_str).expect("Moves are valid.");
+        let mut cube = Cube::solved();
+        cube.apply_moves(&Orientation::default().lower_all(&notation_moves));
+        cube
+    }
+
+    #[test]
+    fn test_parse_display_round_trip() {
+        let moves_str = "R U' F2 r' Lw2 M E' S2 x y' z2 Bw";
+        let notation_moves = NotationMove::parse_array(moves_str).expect("Moves are valid.");
+        let displayed: Vec<String> = notation_moves.iter().map(|m| m.to_string()).collect();
+
+        assert_eq!(displayed.join(" "), "R U' F2 r' l2 M E' S2 x y' z2 b");
+        assert_eq!(NotationMove::parse("Rw'"), NotationMove::parse("r'"));
+        assert_eq!(NotationMove::parse("rw"), None);
+        assert_eq!(NotationMove::parse("Q"), None);
+    }
+
+    #[test]
+    fn test_rotations_return_to_identity() {
+        let mut orientation = Orientation::default();
+        for axis in [Axis::X, Axis::Y, Axis::Z] {
+            orientation.rotate(axis, TurnAmount::Clockwise);
+            assert!(!orientation.is_identity());
+            orientation.rotate(axis, TurnAmount::CounterClockwise);
+            assert!(orientation.is_identity());
+        }
+    }
+
+    #[test]
+    fn test_rotated_sexy_move() {
+        // After y, R is the original B face
+        let cube = apply_notation("y R U R' U'");
+        let mut expected = Cube::solved();
+        expected.apply_moves(&vec![CubeMove::B, CubeMove::U, CubeMove::Bp, CubeMove::Up]);
+
+        assert_eq!(cube, expected);
+    }
+
+    #[test]
+    fn test_wide_and_slice_moves() {
+        // H perm written with slices swaps opposite edges and leaves everything else solved
+        let cube = apply_notation("M2 U M2 U2 M2 U M2");
+        assert_eq!(cube.get_piece_at(&CubePieceLocation::UF), CubePiece::UB);
+        assert_eq!(cube.get_piece_at(&CubePieceLocation::UB), CubePiece::UF);
+        assert_eq!(cube.get_piece_at(&CubePieceLocation::UL), CubePiece::UR);
+        assert_eq!(cube.get_piece_at(&CubePieceLocation::UR), CubePiece::UL);
+        assert!(cube.iter_corners().all(|(location, piece)| piece.is_solved(location)));
+
+        assert!(apply_notation("R L' x' M'").is_solved());
+        assert!(apply_notation("u y' D'").is_solved());
+        assert!(apply_notation("r U R' U' r' F R F' F R' F' r U R U' r'").is_solved());
+        assert!(apply_notation("M E S S' E' M'").is_solved());
+    }
+
+    #[test]
+    fn test_simplify() {
+        let simplify_str = |str: &str| {
+            let moves = CubeMove::parse_array(str).expect("Moves are valid.");
+            simplify(&moves).iter().map(|m| m.to_string()).collect::<Vec<_>>().join(" ")
+        };
+
+        assert_eq!(simplify_str("R R"), "R2");
+        assert_eq!(simplify_str("R U U' R'"), "");
+        assert_eq!(simplify_str("R L R'"), "L");
+        assert_eq!(simplify_str("R2 L R U"), "R' L U");
+        assert_eq!(simplify_str("R U R'"), "R U R'");
+        assert_eq!(simplify_str("F B F B"), "F2 B2");
+    }
+}
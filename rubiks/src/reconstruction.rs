@@ -0,0 +1,449 @@
+use core::fmt;
+use std::time::Duration;
+use crate::cube::{Cube, CubeMove};
+use crate::notation::{NotationMove, Orientation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolveStep {
+    Inspection,
+    Cross,
+    /// F2L pair number, from 1 to 4
+    F2l(u8),
+    Oll,
+    Pll,
+}
+
+impl SolveStep {
+    /// Recognizes the step labels used in reconstruction comments
+    /// such as "cross", "2nd pair", "f2l 3", "OLL" or "PLL".
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.trim().to_lowercase();
+
+        if label.starts_with("inspection") {
+            return Some(SolveStep::Inspection);
+        }
+        if label.starts_with("cross") || label.starts_with("xcross") || label.starts_with("x-cross") {
+            return Some(SolveStep::Cross);
+        }
+        if label.starts_with("oll") {
+            return Some(SolveStep::Oll);
+        }
+        if label.starts_with("pll") {
+            return Some(SolveStep::Pll);
+        }
+        if label.starts_with("last pair") || label.starts_with("last slot") {
+            return Some(SolveStep::F2l(4));
+        }
+
+        (1..=4u8)
+            .find(|n| {
+                let i = (*n - 1) as usize;
+                [
+                    format!("{} pair", PAIR_ORDINALS[i]),
+                    format!("{} pair", PAIR_ORDINAL_WORDS[i]),
+                    format!("pair {}", n),
+                    format!("f2l {}", n),
+                    format!("f2l{}", n),
+                    format!("slot {}", n),
+                ]
+                .iter()
+                .any(|prefix| label.starts_with(prefix.as_str()))
+            })
+            .map(SolveStep::F2l)
+    }
+}
+
+impl fmt::Display for SolveStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveStep::Inspection => write!(f, "inspection"),
+            SolveStep::Cross => write!(f, "cross"),
+            SolveStep::F2l(n) => write!(f, "{} pair", PAIR_ORDINALS[(*n as usize).clamp(1, 4) - 1]),
+            SolveStep::Oll => write!(f, "OLL"),
+            SolveStep::Pll => write!(f, "PLL"),
+        }
+    }
+}
+
+/// A solution move with the optional time at which it was done, counted from the start of the solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedMove {
+    pub notation_move: NotationMove,
+    pub timestamp: Option<Duration>,
+}
+
+impl TimedMove {
+    /// Parses a move optionally followed by its timestamp in milliseconds, like `R'@1520`.
+    pub fn parse(str: &str) -> Option<Self> {
+        let (move_str, timestamp) = match str.split_once('@') {
+            Some((move_str, millis)) => (move_str, Some(Duration::from_millis(millis.parse().ok()?))),
+            None => (str, None),
+        };
+
+        Some(Self {
+            notation_move: NotationMove::parse(move_str)?,
+            timestamp,
+        })
+    }
+}
+
+impl fmt::Display for TimedMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.timestamp {
+            Some(timestamp) => write!(f, "{}@{}", self.notation_move, timestamp.as_millis()),
+            None => write!(f, "{}", self.notation_move),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconstructionLine {
+    pub moves: Vec<TimedMove>,
+    pub step: Option<SolveStep>,
+    pub comment: Option<String>,
+}
+
+/// Move count and duration of consecutive solution lines belonging to the same step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepStats {
+    pub step: Option<SolveStep>,
+    /// Number of moves, rotations excluded
+    pub move_count: usize,
+    /// Only known when every move of the step has a timestamp
+    pub duration: Option<Duration>,
+}
+
+impl StepStats {
+    pub fn tps(&self) -> Option<f64> {
+        self.duration.and_then(|duration| tps(self.move_count, duration))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReconstructionParseError {
+    MissingScramble,
+    InvalidScramble(String),
+    InvalidTime(String),
+    InvalidMove { line: usize, token: String },
+}
+
+impl fmt::Display for ReconstructionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReconstructionParseError::MissingScramble => write!(f, "reconstruction has no 'Scramble:' line"),
+            ReconstructionParseError::InvalidScramble(scramble) => write!(f, "scramble '{}' is invalid", scramble),
+            ReconstructionParseError::InvalidTime(time) => write!(f, "time '{}' is invalid", time),
+            ReconstructionParseError::InvalidMove { line, token } => write!(f, "move '{}' on line {} is invalid", token, line),
+        }
+    }
+}
+
+impl std::error::Error for ReconstructionParseError {}
+
+/// A scramble and an annotated solution, in the text format used by reconstruction sites:
+///
+/// ```text
+/// Scramble: R2 D' B2 U F2 R2 U' B2 D F2 L' F U' L2 B' R U R2 D' F
+/// Time: 7.53
+///
+/// x2 // inspection
+/// R' F R D2 // cross
+/// U R U' R' // 1st pair
+/// ```
+///
+/// Moves can carry a timestamp in milliseconds from the start of the solve, like `R@1200`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconstruction {
+    pub scramble: Vec<CubeMove>,
+    pub lines: Vec<ReconstructionLine>,
+    pub time: Option<Duration>,
+}
+
+impl Reconstruction {
+    pub fn parse(str: &str) -> Result<Self, ReconstructionParseError> {
+        let mut scramble = None;
+        let mut time = None;
+        let mut lines = Vec::new();
+
+        for (line_index, line) in str.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(scramble_str) = strip_header(line, "scramble:") {
+                let moves = scramble_str
+                    .split_whitespace()
+                    .map(CubeMove::parse)
+                    .collect::<Option<Vec<CubeMove>>>()
+                    .ok_or_else(|| ReconstructionParseError::InvalidScramble(scramble_str.to_string()))?;
+                scramble = Some(moves);
+                continue;
+            }
+
+            if let Some(time_str) = strip_header(line, "time:") {
+                let seconds = time_str
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                    .ok_or_else(|| ReconstructionParseError::InvalidTime(time_str.to_string()))?;
+                time = Some(Duration::from_secs_f64(seconds));
+                continue;
+            }
+
+            let (moves_str, comment) = match line.split_once("//") {
+                Some((moves_str, comment)) => (moves_str, Some(comment.trim().to_string())),
+                None => (line, None),
+            };
+
+            let moves = moves_str
+                .split_whitespace()
+                .map(|token| TimedMove::parse(token).ok_or_else(|| ReconstructionParseError::InvalidMove {
+                    line: line_index + 1,
+                    token: token.to_string(),
+                }))
+                .collect::<Result<Vec<TimedMove>, ReconstructionParseError>>()?;
+
+            lines.push(ReconstructionLine {
+                moves,
+                step: comment.as_deref().and_then(SolveStep::from_label),
+                comment,
+            });
+        }
+
+        Ok(Self {
+            scramble: scramble.ok_or(ReconstructionParseError::MissingScramble)?,
+            lines,
+            time,
+        })
+    }
+
+    pub fn solution(&self) -> impl Iterator<Item = &TimedMove> {
+        self.lines.iter().flat_map(|line| line.moves.iter())
+    }
+
+    /// Solution translated to the fixed center moves of `Cube`.
+    pub fn solution_cube_moves(&self) -> Vec<CubeMove> {
+        let mut orientation = Orientation::default();
+        self.solution()
+            .flat_map(|timed_move| orientation.lower(timed_move.notation_move))
+            .collect()
+    }
+
+    pub fn scrambled_cube(&self) -> Cube {
+        let mut cube = Cube::solved();
+        cube.apply_moves(&self.scramble);
+        cube
+    }
+
+    /// Iterates over the state of the cube after every solution move.
+    pub fn replay(&self) -> Replay<'_> {
+        Replay {
+            cube: self.scrambled_cube(),
+            orientation: Orientation::default(),
+            moves: Box::new(self.solution()),
+        }
+    }
+
+    /// Number of solution moves, rotations excluded.
+    pub fn move_count(&self) -> usize {
+        self.solution().filter(|m| !m.notation_move.is_rotation()).count()
+    }
+
+    /// Solve duration, either from the `Time:` header or from the last move timestamp.
+    pub fn duration(&self) -> Option<Duration> {
+        self.time.or_else(|| self.solution().filter_map(|m| m.timestamp).last())
+    }
+
+    pub fn tps(&self) -> Option<f64> {
+        self.duration().and_then(|duration| tps(self.move_count(), duration))
+    }
+
+    /// Groups consecutive lines by step, lines without a label belonging to the step before them.
+    pub fn step_stats(&self) -> Vec<StepStats> {
+        let mut groups: Vec<(Option<SolveStep>, Vec<&TimedMove>)> = Vec::new();
+        for line in &self.lines {
+            let previous_step = groups.last().and_then(|(step, _)| *step);
+            let step = line.step.or(previous_step);
+
+            match groups.last_mut() {
+                Some((group_step, moves)) if *group_step == step => moves.extend(line.moves.iter()),
+                _ => groups.push((step, line.moves.iter().collect())),
+            }
+        }
+
+        let mut previous_end = Some(Duration::ZERO);
+        groups
+            .into_iter()
+            .map(|(step, moves)| {
+                let mut turns = moves.iter().filter(|m| !m.notation_move.is_rotation());
+                let move_count = turns.clone().count();
+                let end = if turns.all(|m| m.timestamp.is_some()) {
+                    moves.iter().rev().find_map(|m| m.timestamp).or(previous_end)
+                } else {
+                    None
+                };
+                let duration = match (previous_end, end) {
+                    (Some(start), Some(end)) => Some(end.saturating_sub(start)),
+                    _ => None,
+                };
+                previous_end = end;
+
+                StepStats { step, move_count, duration }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Reconstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scramble: Vec<String> = self.scramble.iter().map(|m| m.to_string()).collect();
+        writeln!(f, "Scramble: {}", scramble.join(" "))?;
+        if let Some(time) = self.time {
+            writeln!(f, "Time: {}", time.as_secs_f64())?;
+        }
+        writeln!(f)?;
+
+        for line in &self.lines {
+            let moves: Vec<String> = line.moves.iter().map(|m| m.to_string()).collect();
+            let comment = line.comment.clone().or_else(|| line.step.map(|step| step.to_string()));
+
+            match (moves.is_empty(), comment) {
+                (true, Some(comment)) => writeln!(f, "// {}", comment)?,
+                (false, Some(comment)) => writeln!(f, "{} // {}", moves.join(" "), comment)?,
+                (_, None) => writeln!(f, "{}", moves.join(" "))?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Replay<'a> {
+    cube: Cube,
+    orientation: Orientation,
+    moves: Box<dyn Iterator<Item = &'a TimedMove> + 'a>,
+}
+
+impl Iterator for Replay<'_> {
+    type Item = Cube;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let timed_move = self.moves.next()?;
+        let cube_moves = self.orientation.lower(timed_move.notation_move);
+        self.cube.apply_moves(&cube_moves);
+        Some(self.cube.clone())
+    }
+}
+
+fn strip_header<'a>(line: &'a str, header: &str) -> Option<&'a str> {
+    if line.get(..header.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(header)) {
+        Some(line[header.len()..].trim())
+    } else {
+        None
+    }
+}
+
+fn tps(move_count: usize, duration: Duration) -> Option<f64> {
+    if duration.is_zero() {
+        None
+    } else {
+        Some(move_count as f64 / duration.as_secs_f64())
+    }
+}
+
+static PAIR_ORDINALS: [&str; 4] = ["1st", "2nd", "3rd", "4th"];
+static PAIR_ORDINAL_WORDS: [&str; 4] = ["first", "second", "third", "fourth"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SEXY_RECONSTRUCTION: &str = "\
+        Scramble: R U R' U'\n\
+        Time: 2.5\n\
+        \n\
+        y y' // inspection\n\
+        U@200 R@400 // cross\n\
+        U'@900 // 1st pair\n\
+        R'@1000\n\
+        // nice\n\
+        x x' // PLL\n";
+
+    #[test]
+    fn test_parse_labels_and_comments() {
+        let reconstruction = Reconstruction::parse(SEXY_RECONSTRUCTION).expect("Reconstruction is valid.");
+
+        assert_eq!(reconstruction.scramble, vec![CubeMove::R, CubeMove::U, CubeMove::Rp, CubeMove::Up]);
+        assert_eq!(reconstruction.time, Some(Duration::from_millis(2500)));
+        assert_eq!(reconstruction.lines.len(), 6);
+        assert_eq!(reconstruction.lines[0].step, Some(SolveStep::Inspection));
+        assert_eq!(reconstruction.lines[2].step, Some(SolveStep::F2l(1)));
+        assert_eq!(reconstruction.lines[3].step, None);
+        assert_eq!(reconstruction.lines[4].comment.as_deref(), Some("nice"));
+        assert_eq!(reconstruction.lines[1].moves[1].timestamp, Some(Duration::from_millis(400)));
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let reconstruction = Reconstruction::parse(SEXY_RECONSTRUCTION).expect("Reconstruction is valid.");
+        let serialized = reconstruction.to_string();
+
+        assert!(serialized.starts_with("Scramble: R U R' U'\nTime: 2.5\n"));
+        assert!(serialized.contains("U@200 R@400 // cross\n"));
+        assert_eq!(Reconstruction::parse(&serialized), Ok(reconstruction));
+    }
+
+    #[test]
+    fn test_replay_solves_cube() {
+        let reconstruction = Reconstruction::parse(SEXY_RECONSTRUCTION).expect("Reconstruction is valid.");
+        let states: Vec<Cube> = reconstruction.replay().collect();
+
+        assert_eq!(states.len(), 8);
+        assert!(!states[2].is_solved());
+        assert!(states.last().expect("Replay has states.").is_solved());
+    }
+
+    #[test]
+    fn test_step_stats() {
+        let reconstruction = Reconstruction::parse(SEXY_RECONSTRUCTION).expect("Reconstruction is valid.");
+        let stats = reconstruction.step_stats();
+
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats[0].move_count, 0);
+        assert_eq!(stats[0].duration, Some(Duration::ZERO));
+        assert_eq!(stats[1].step, Some(SolveStep::Cross));
+        assert_eq!(stats[1].move_count, 2);
+        assert_eq!(stats[1].duration, Some(Duration::from_millis(400)));
+        assert_eq!(stats[1].tps(), Some(5.0));
+        assert_eq!(stats[2].step, Some(SolveStep::F2l(1)));
+        assert_eq!(stats[2].move_count, 2);
+        assert_eq!(stats[2].duration, Some(Duration::from_millis(600)));
+        assert_eq!(stats[3].step, Some(SolveStep::Pll));
+        assert_eq!(reconstruction.move_count(), 4);
+        assert_eq!(reconstruction.tps(), Some(1.6));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Reconstruction::parse("R U"), Err(ReconstructionParseError::MissingScramble));
+        assert_eq!(
+            Reconstruction::parse("Scramble: R\nR Q // cross"),
+            Err(ReconstructionParseError::InvalidMove { line: 2, token: "Q".into() })
+        );
+        assert_eq!(SolveStep::from_label("Third pair (keyhole)"), Some(SolveStep::F2l(3)));
+        assert_eq!(SolveStep::from_label("OLL(CP)"), Some(SolveStep::Oll));
+        assert_eq!(SolveStep::from_label("nice"), None);
+    }
+
+    #[test]
+    fn test_parse_non_ascii_text() {
+        assert_eq!(Reconstruction::parse("R U' // é bien"), Err(ReconstructionParseError::MissingScramble));
+
+        let reconstruction = Reconstruction::parse("Scramble: R
+R' // très bien
+// ça tourne").expect("Reconstruction is valid.");
+        assert_eq!(reconstruction.lines[0].comment.as_deref(), Some("très bien"));
+        assert_eq!(reconstruction.lines[1].comment.as_deref(), Some("ça tourne"));
+    }
+}
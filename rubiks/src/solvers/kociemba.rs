@@ -1,8 +1,33 @@
 // TODO Implement own Kociemba later
 
-use crate::{cube::{Cube, CubeMove}, faces::Face, stickers::CubeStickerLocation};
+use std::fmt;
+use std::time::Instant;
+use crate::cube::{Cube, CubeMove};
 use kociemba::{moves::Move, solver::solve};
 
+/// Shortest `max_size` the crate can search with. It deepens phase 1 up to 19 moves after finding
+/// a solution too long for `max_size`, and underflows once that solution is shorter than phase 1.
+pub const MIN_MAX_SIZE: usize = 18;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KociembaError {
+    /// `max_size` is below `MIN_MAX_SIZE`
+    MaxSizeTooShort(usize),
+    /// The crate rejected the cube
+    InvalidCube(String),
+}
+
+impl fmt::Display for KociembaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KociembaError::MaxSizeTooShort(max_size) => write!(f, "max size {} is below {}", max_size, MIN_MAX_SIZE),
+            KociembaError::InvalidCube(error) => write!(f, "cube is invalid: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for KociembaError {}
+
 #[derive(Clone, Copy)]
 pub struct KociembaSolver {
     pub max_size: usize,
@@ -16,27 +41,62 @@ impl Default for KociembaSolver {
 }
 
 impl KociembaSolver {
-    pub fn solve(&self, cube: &Cube) -> Option<Vec<CubeMove>> {
-        let cube_string = make_cube_string(&cube);
+    pub fn solve(&self, cube: &Cube) -> Result<Vec<CubeMove>, KociembaError> {
+        if self.max_size < MIN_MAX_SIZE {
+            return Err(KociembaError::MaxSizeTooShort(self.max_size));
+        }
+        let cube_string = cube.to_facelets();
 
-        match solve(&cube_string, self.max_size, self.timeout) {
-            Ok(solution) => Some(
-                solution.solution
+        solve(&cube_string, self.max_size, self.timeout)
+            .map(|solution| solution.solution
                 .into_iter()
                 .map(|m| m.into())
                 .collect()
-            ),
-            Err(error) => {
-                println!("{:?}", error);
-                None
-            },
+            )
+            .map_err(|error| KociembaError::InvalidCube(error.to_string()))
+    }
+}
+
+impl KociembaSolver {
+    /// Looks for shorter and shorter solutions, each search getting `timeout` seconds,
+    /// until one fails to improve or `MIN_MAX_SIZE` is reached. The result is not optimal,
+    /// searches can't aim below `MIN_MAX_SIZE` moves and keep the first solution under it.
+    pub fn solve_shortest(&self, cube: &Cube) -> Result<Vec<CubeMove>, KociembaError> {
+        self.shortest(cube, None)
+    }
+
+    /// Like `solve_shortest`, but no search starts after `deadline` and each one ends by it.
+    /// The first solution is always returned, even when found late.
+    pub fn solve_shortest_before(&self, cube: &Cube, deadline: Instant) -> Result<Vec<CubeMove>, KociembaError> {
+        self.shortest(cube, Some(deadline))
+    }
+
+    fn shortest(&self, cube: &Cube, deadline: Option<Instant>) -> Result<Vec<CubeMove>, KociembaError> {
+        let mut best = self.solve(cube)?;
+
+        while best.len() > MIN_MAX_SIZE {
+            let timeout = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()).as_secs_f32().min(self.timeout),
+                None => self.timeout,
+            };
+            if timeout <= 0.0 {
+                break;
+            }
+
+            let solver = KociembaSolver { max_size: best.len() - 1, timeout };
+            match solver.solve(cube) {
+                Ok(solution) if solution.len() < best.len() => best = solution,
+                _ => break,
+            }
         }
+
+        Ok(best)
     }
 }
 
-impl Into<CubeMove> for Move {
-    fn into(self) -> CubeMove {
-        match self {
+impl From<Move> for CubeMove {
+    fn from(m: Move) -> CubeMove {
+        match m {
             Move::U => CubeMove::U,
             Move::U2 => CubeMove::U2,
             Move::U3 => CubeMove::Up,
@@ -59,109 +119,11 @@ impl Into<CubeMove> for Move {
     }
 }
 
-/// Make a string representation of the cube in the form
-/// "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB".
-fn make_cube_string(cube: &Cube) -> String {
-    let mut cube_string = String::with_capacity(54);
-    let mut start = 0;
-
-    // On each face
-    for face in FACES {
-        // For each stickers of the face
-        for i in start..(start + 8) {            
-            // Add sticker to the cube string
-            let sticker_location = FACELET_POSITIONS[i];
-            let sticker_name = cube.get_sticker_origin(&sticker_location).to_sticker_name();
-            let sticker_face = sticker_name.chars().nth(0).expect("Stickers name have a least one character.");
-            cube_string.push(sticker_face);
-
-            // An add also center which we don't have in our impl
-            if i == start + 3 {
-                cube_string.push_str(&face.to_string());
-            }
-        }
-
-        start += 8;
-    }
-
-    cube_string
-}
-
-static FACELET_POSITIONS: [CubeStickerLocation; 48]  = [
-    // U
-    CubeStickerLocation::ULB,
-    CubeStickerLocation::UB,
-    CubeStickerLocation::UBR,
-    CubeStickerLocation::UL,
-    CubeStickerLocation::UR,
-    CubeStickerLocation::UFL,
-    CubeStickerLocation::UF,
-    CubeStickerLocation::URF,
-
-    // R
-    CubeStickerLocation::RFU,
-    CubeStickerLocation::RU,
-    CubeStickerLocation::RUB,
-    CubeStickerLocation::RF,
-    CubeStickerLocation::RB,
-    CubeStickerLocation::RDF,
-    CubeStickerLocation::RD,
-    CubeStickerLocation::RBD,
-
-    // F
-    CubeStickerLocation::FLU,
-    CubeStickerLocation::FU,
-    CubeStickerLocation::FUR,
-    CubeStickerLocation::FL,
-    CubeStickerLocation::FR,
-    CubeStickerLocation::FDL,
-    CubeStickerLocation::FD,
-    CubeStickerLocation::FRD,
-
-    // D
-    CubeStickerLocation::DLF,
-    CubeStickerLocation::DF,
-    CubeStickerLocation::DFR,
-    CubeStickerLocation::DL,
-    CubeStickerLocation::DR,
-    CubeStickerLocation::DBL,
-    CubeStickerLocation::DB,
-    CubeStickerLocation::DRB,
-
-    // L
-    CubeStickerLocation::LBU,
-    CubeStickerLocation::LU,
-    CubeStickerLocation::LUF,
-    CubeStickerLocation::LB,
-    CubeStickerLocation::LF,
-    CubeStickerLocation::LDB,
-    CubeStickerLocation::LD,
-    CubeStickerLocation::LFD,
-
-    // B
-    CubeStickerLocation::BRU,
-    CubeStickerLocation::BU,
-    CubeStickerLocation::BUL,
-    CubeStickerLocation::BR,
-    CubeStickerLocation::BL,
-    CubeStickerLocation::BDR,
-    CubeStickerLocation::BD,
-    CubeStickerLocation::BLD,
-];
-
-static FACES: [Face; 6] = [
-    Face::U,
-    Face::R,
-    Face::F,
-    Face::D,
-    Face::L,
-    Face::B,
-];
-
 #[cfg(test)]
 mod tests {
+    use std::time::Instant;
     use crate::location::CubePieceLocation;
-    use crate::solvers::kociemba::{make_cube_string, KociembaSolver};
+    use crate::solvers::kociemba::{KociembaError, KociembaSolver, MIN_MAX_SIZE};
     use crate::cube::{Cube, CubeMove};
 
     #[test]
@@ -184,7 +146,7 @@ mod tests {
 
         for _ in 0..1000 {
             let cube = Cube::random_uniform(&mut rng);
-            let cube_string = make_cube_string(&cube);
+            let cube_string = cube.to_facelets();
     
             assert_eq!(cube_string.len(), 54, "Cube string should be of size 54.");            
             assert_eq!(cube_string.chars().filter(|c| *c == 'U').count(), 9, "Should be 9 Us in cube string.");
@@ -199,7 +161,7 @@ mod tests {
     #[test]
     fn test_cube_string_expected_solved() {
         let cube = Cube::solved();
-        let cube_string = make_cube_string(&cube);
+        let cube_string = cube.to_facelets();
         assert_eq!(cube_string, "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB");
     }
 
@@ -209,7 +171,7 @@ mod tests {
         let scramble = CubeMove::parse_array("L").expect("Scramble is valid.");
         cube.apply_moves(&scramble);
 
-        let cube_string = make_cube_string(&cube);
+        let cube_string = cube.to_facelets();
         assert_eq!(cube_string, "BUUBUUBUURRRRRRRRRUFFUFFUFFFDDFDDFDDLLLLLLLLLBBDBBDBBD");
     }
 
@@ -220,7 +182,7 @@ mod tests {
         let scramble = CubeMove::parse_array("R U R' U' R' F R2 U' R' U' R U R' F'").expect("Scramble is valid.");
         cube.apply_moves(&scramble);
 
-        let cube_string = make_cube_string(&cube);
+        let cube_string = cube.to_facelets();
         assert_eq!(cube_string, "UUUUUUUUUBLFRRRRRRFFRFFFFFFDDDDDDDDDLRLLLLLLLRBBBBBBBB");
     }
 
@@ -231,7 +193,7 @@ mod tests {
             .expect("Scramble is valid.");
         cube.apply_moves(&scramble);
 
-        let cube_string = make_cube_string(&cube);
+        let cube_string = cube.to_facelets();
         assert_eq!(cube_string, "UDUDUDUDURLRLRLRLRFBFBFBFBFDUDUDUDUDLRLRLRLRLBFBFBFBFB");
     }
 
@@ -243,7 +205,41 @@ mod tests {
 
         println!("{:?}", cube.get_piece_at(&CubePieceLocation::ULB).get_original_location().get_faces());
 
-        let cube_string = make_cube_string(&cube);
+        let cube_string = cube.to_facelets();
         assert_eq!(cube_string, "FFBBUBFUDFDRDRLULDUBRUFRFRRDFBBDFLDLLRRDLRULLDUUUBLBFB");
     }
+
+    #[test]
+    fn test_solve_shortest() {
+        let solver = KociembaSolver { max_size: 20, timeout: 1.0 };
+        let mut cube = Cube::solved();
+        cube.apply_moves(&CubeMove::parse_array("R U2 F' D").expect("Scramble is valid."));
+
+        // Searches never ask for less than `MIN_MAX_SIZE` moves, so the 4 moves of the scramble are not guaranteed
+        let solution = solver.solve_shortest(&cube).expect("Cube should be solvable.");
+        assert!(solution.len() <= MIN_MAX_SIZE);
+        cube.apply_moves(&solution);
+        assert!(cube.is_solved());
+    }
+
+    #[test]
+    fn test_solve_shortest_before_past_deadline() {
+        let solver = KociembaSolver { max_size: 20, timeout: 1.0 };
+        let mut cube = Cube::solved();
+        cube.apply_moves(&CubeMove::parse_array("R U2 F' D").expect("Scramble is valid."));
+
+        // Only the first search runs
+        let solution = solver.solve_shortest_before(&cube, Instant::now()).expect("Cube should be solvable.");
+        cube.apply_moves(&solution);
+        assert!(cube.is_solved());
+    }
+
+    #[test]
+    fn test_solve_rejects_short_max_size() {
+        let solver = KociembaSolver { max_size: MIN_MAX_SIZE - 1, timeout: 1.0 };
+        let mut cube = Cube::solved();
+        cube.apply_moves(&CubeMove::parse_array("R U2 F' D").expect("Scramble is valid."));
+
+        assert_eq!(solver.solve(&cube), Err(KociembaError::MaxSizeTooShort(MIN_MAX_SIZE - 1)));
+    }
 }
@@ -1,2 +1,3 @@
 pub mod pochmann;
-pub mod kociemba;
\ No newline at end of file
+pub mod kociemba;
+pub mod pocket;
\ No newline at end of file
@@ -6,16 +6,40 @@ use crate::piece::CubePiece;
 use crate::stickers::CubeStickerLocation;
 use crate::twist::Twist;
 
+#[derive(Clone, Copy)]
 pub struct PochmannSolver {
     pub buffer_corner: CubeStickerLocation,
     pub buffer_edge: CubeStickerLocation,
 }
 
+impl Default for PochmannSolver {
+    fn default() -> Self {
+        Self { buffer_corner: CubeStickerLocation::ULB, buffer_edge: CubeStickerLocation::UR }
+    }
+}
+
+/// Cycles starting with the buffer one, which only holds the buffer sticker when the buffer piece is in place.
+/// Other cycles start with the cycle break target and end with the same piece.
 pub struct PochmannSolution {
     pub corner_cycles: Vec<Vec<CubeStickerLocation>>,
     pub edge_cycles: Vec<Vec<CubeStickerLocation>>
 }
 
+/// Difficulty features of a blindfolded solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BldSummary {
+    pub corner_targets: usize,
+    pub edge_targets: usize,
+    pub corner_cycle_breaks: usize,
+    pub edge_cycle_breaks: usize,
+    /// Corners in place but twisted, buffer excluded
+    pub twisted_corners: usize,
+    /// Edges in place but flipped, buffer excluded
+    pub flipped_edges: usize,
+    /// Odd number of corner targets, so odd number of edge targets too
+    pub parity: bool,
+}
+
 impl PochmannSolver {
     pub fn solve(&self, cube: &Cube) -> PochmannSolution {
         let corner_cycles = Self::solve_pieces(cube, &self.buffer_corner);
@@ -27,6 +51,55 @@ impl PochmannSolver {
         }
     }
 
+    pub fn summarize(&self, cube: &Cube) -> BldSummary {
+        let solution = self.solve(cube);
+        let corner_targets = Self::count_targets(cube, &solution.corner_cycles, &self.buffer_corner);
+
+        BldSummary {
+            corner_targets,
+            edge_targets: Self::count_targets(cube, &solution.edge_cycles, &self.buffer_edge),
+            corner_cycle_breaks: solution.corner_cycles.len() - 1,
+            edge_cycle_breaks: solution.edge_cycles.len() - 1,
+            twisted_corners: Self::count_misoriented_pieces(cube.iter_corners(), &self.buffer_corner),
+            flipped_edges: Self::count_misoriented_pieces(cube.iter_edges(), &self.buffer_edge),
+            parity: corner_targets % 2 == 1,
+        }
+    }
+
+    /// Stickers to memorize in solving order, corners then edges, cycle breaks included.
+    pub fn targets(&self, cube: &Cube) -> (Vec<CubeStickerLocation>, Vec<CubeStickerLocation>) {
+        let solution = self.solve(cube);
+        (
+            Self::cycle_targets(cube, &solution.corner_cycles, &self.buffer_corner),
+            Self::cycle_targets(cube, &solution.edge_cycles, &self.buffer_edge),
+        )
+    }
+
+    fn count_targets(cube: &Cube, cycles: &[Vec<CubeStickerLocation>], buffer: &CubeStickerLocation) -> usize {
+        Self::cycle_targets(cube, cycles, buffer).len()
+    }
+
+    /// The buffer cycle only holds the buffer sticker when the buffer piece is in place.
+    fn cycle_targets(cube: &Cube, cycles: &[Vec<CubeStickerLocation>], buffer: &CubeStickerLocation) -> Vec<CubeStickerLocation> {
+        let buffer_in_place = cube.get_piece_at(&buffer.piece_location).get_original_location() == buffer.piece_location;
+        cycles
+            .iter()
+            .skip(usize::from(buffer_in_place))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn count_misoriented_pieces<'a>(it: impl Iterator<Item = (&'a CubePieceLocation, &'a CubePiece)>, buffer: &CubeStickerLocation) -> usize {
+        it
+            .filter(|(location, piece)| {
+                **location != buffer.piece_location
+                    && piece.get_original_location() == **location
+                    && piece.get_twist() != Twist::SOLVED
+            })
+            .count()
+    }
+
     fn solve_pieces(cube: &Cube, buffer: &CubeStickerLocation) -> Vec<Vec<CubeStickerLocation>> {
         let mut solved_locations = HashSet::from([
             buffer.piece_location
@@ -37,12 +110,9 @@ impl PochmannSolver {
         let mut next_start = Some(*buffer);
 
         while let Some(start_location) = next_start {
-            println!("Cycle {:?}", start_location);
-
             // Build the cycle starting from `start`
             let mut current_cycle = Vec::new();
-            for (next_location, does_solve) in PochmannSolver::iter_single_cycle(cube, &start_location, &buffer) {
-                println!("    {:?}", next_location);
+            for (next_location, does_solve) in PochmannSolver::iter_single_cycle(cube, &start_location, buffer) {
                 current_cycle.push(next_location);
 
                 if does_solve {
@@ -62,7 +132,7 @@ impl PochmannSolver {
         std::iter::successors(
             Some(
                 if start.piece_location == buffer.piece_location {
-                    (cube.get_sticker_origin(&start), true)
+                    (cube.get_sticker_origin(start), true)
                 } else {
                     (*start, false)
                 }
@@ -73,7 +143,6 @@ impl PochmannSolver {
                 }
 
                 let next = cube.get_sticker_origin(prev);
-                println!("    twist is {:?}", next.twist);
                 if next.piece_location == buffer.piece_location {
                     None
                 } else {
@@ -96,4 +165,62 @@ impl PochmannSolver {
             .find(|(location, piece)| !solved_locations.contains(location) && piece.get_original_location() != **location)
             .map(|(location, _)| CubeStickerLocation { piece_location: *location, twist: Twist::SOLVED })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piece(location: CubePieceLocation, twist: Twist) -> CubePiece {
+        CubePiece::from_location(location).twisted(twist)
+    }
+
+    #[test]
+    fn test_summarize_solved() {
+        let summary = PochmannSolver::default().summarize(&Cube::solved());
+        assert_eq!(summary, BldSummary::default());
+    }
+
+    #[test]
+    fn test_summarize_cycles() {
+        let solver = PochmannSolver::default();
+
+        // Corner 3-cycle through the buffer
+        let cube = Cube::with_pieces(&[
+            (CubePieceLocation::ULB, piece(CubePieceLocation::URF, Twist::SOLVED)),
+            (CubePieceLocation::URF, piece(CubePieceLocation::UBR, Twist::SOLVED)),
+            (CubePieceLocation::UBR, piece(CubePieceLocation::ULB, Twist::SOLVED)),
+        ]);
+        let summary = solver.summarize(&cube);
+        assert_eq!((summary.corner_targets, summary.corner_cycle_breaks, summary.parity), (2, 0, false));
+        let (corners, edges) = solver.targets(&cube);
+        assert_eq!(corners, [CubeStickerLocation::URF, CubeStickerLocation::UBR]);
+        assert!(edges.is_empty());
+
+        // Corner 3-cycle away from the buffer and two twisted corners
+        let cube = Cube::with_pieces(&[
+            (CubePieceLocation::URF, piece(CubePieceLocation::UFL, Twist::SOLVED)),
+            (CubePieceLocation::UFL, piece(CubePieceLocation::UBR, Twist::SOLVED)),
+            (CubePieceLocation::UBR, piece(CubePieceLocation::URF, Twist::SOLVED)),
+            (CubePieceLocation::DFR, piece(CubePieceLocation::DFR, Twist::CW_120)),
+            (CubePieceLocation::DRB, piece(CubePieceLocation::DRB, Twist::CW_240)),
+        ]);
+        let summary = solver.summarize(&cube);
+        assert_eq!((summary.corner_targets, summary.corner_cycle_breaks), (4, 1));
+        assert_eq!(summary.twisted_corners, 2);
+
+        // Swaps of corners and edges, and a flipped edge
+        let cube = Cube::with_pieces(&[
+            (CubePieceLocation::ULB, piece(CubePieceLocation::URF, Twist::SOLVED)),
+            (CubePieceLocation::URF, piece(CubePieceLocation::ULB, Twist::SOLVED)),
+            (CubePieceLocation::UR, piece(CubePieceLocation::UF, Twist::SOLVED)),
+            (CubePieceLocation::UF, piece(CubePieceLocation::UR, Twist::SOLVED)),
+            (CubePieceLocation::DF, piece(CubePieceLocation::DF, Twist::FLIPPED)),
+            (CubePieceLocation::DB, piece(CubePieceLocation::DB, Twist::FLIPPED)),
+        ]);
+        let summary = solver.summarize(&cube);
+        assert_eq!((summary.corner_targets, summary.edge_targets), (1, 1));
+        assert_eq!(summary.flipped_edges, 2);
+        assert!(summary.parity);
+    }
+}
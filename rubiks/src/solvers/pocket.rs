@@ -0,0 +1,314 @@
+use std::sync::OnceLock;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::cube::{Cube, CubeMove};
+use crate::location::CubePieceLocation;
+
+/// Moves a 2x2 is scrambled and solved with, which all keep the DBL corner in place.
+pub const POCKET_MOVES: [CubeMove; 9] = [
+    CubeMove::U, CubeMove::U2, CubeMove::Up,
+    CubeMove::R, CubeMove::R2, CubeMove::Rp,
+    CubeMove::F, CubeMove::F2, CubeMove::Fp,
+];
+
+/// No 2x2 state needs more moves
+const MAX_SOLUTION_LENGTH: usize = 11;
+
+/// Corners moved by `POCKET_MOVES`
+const CORNERS: [CubePieceLocation; 7] = [
+    CubePieceLocation::URF,
+    CubePieceLocation::UFL,
+    CubePieceLocation::ULB,
+    CubePieceLocation::UBR,
+    CubePieceLocation::DFR,
+    CubePieceLocation::DLF,
+    CubePieceLocation::DRB,
+];
+
+const PERMUTATION_COUNT: usize = 5040;
+const TWIST_COUNT: usize = 729;
+
+/// State of a 2x2, seen as the corners of a cube whose DBL corner is never turned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PocketCube {
+    /// Corner at each location of `CORNERS`, as an index in it
+    permutation: [u8; 7],
+    /// Twist of the corner at each location
+    twists: [u8; 7],
+}
+
+impl PocketCube {
+    pub fn solved() -> Self {
+        Self {
+            permutation: std::array::from_fn(|i| i as u8),
+            twists: [0; 7],
+        }
+    }
+
+    /// Every state is equally likely, whichever the parity of its corner permutation.
+    pub fn random_uniform<R: Rng>(rng: &mut R) -> Self {
+        let mut permutation: [u8; 7] = std::array::from_fn(|i| i as u8);
+        permutation.shuffle(rng);
+
+        let mut twists = [0; 7];
+        for twist in &mut twists[..6] {
+            *twist = rng.random_range(0..3);
+        }
+        twists[6] = (3 - twists[..6].iter().sum::<u8>() % 3) % 3;
+
+        Self { permutation, twists }
+    }
+
+    /// Cube after `moves`, `None` if one of them is not in `POCKET_MOVES`.
+    pub fn applied(&self, moves: &[CubeMove]) -> Option<Self> {
+        let tables = tables();
+        moves.iter().try_fold(*self, |cube, cube_move| {
+            let index = POCKET_MOVES.iter().position(|m| m == cube_move)?;
+            Some(cube.moved(&tables.moves[index]))
+        })
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::solved()
+    }
+
+    fn moved(&self, corner_moves: &[(u8, u8); 7]) -> Self {
+        let mut moved = *self;
+        for (location, (origin, twist)) in corner_moves.iter().enumerate() {
+            moved.permutation[location] = self.permutation[*origin as usize];
+            moved.twists[location] = (self.twists[*origin as usize] + twist) % 3;
+        }
+        moved
+    }
+
+    /// Lehmer code of the permutation, 0 when solved.
+    fn permutation_index(&self) -> usize {
+        (0..7).fold(0, |index, i| {
+            let smaller_after = self.permutation[i + 1..].iter().filter(|corner| **corner < self.permutation[i]).count();
+            index * (7 - i) + smaller_after
+        })
+    }
+
+    fn from_permutation_index(mut index: usize) -> Self {
+        let mut digits = [0; 7];
+        for i in (0..7).rev() {
+            digits[i] = index % (7 - i);
+            index /= 7 - i;
+        }
+
+        let mut remaining: Vec<u8> = (0..7).collect();
+        let mut cube = Self::solved();
+        for (location, digit) in digits.into_iter().enumerate() {
+            cube.permutation[location] = remaining.remove(digit);
+        }
+        cube
+    }
+
+    /// Twists of the first 6 corners in base 3, the last one following from them.
+    fn twist_index(&self) -> usize {
+        self.twists[..6].iter().fold(0, |index, twist| index * 3 + *twist as usize)
+    }
+
+    fn from_twist_index(mut index: usize) -> Self {
+        let mut cube = Self::solved();
+        for i in (0..6).rev() {
+            cube.twists[i] = (index % 3) as u8;
+            index /= 3;
+        }
+        cube.twists[6] = (3 - cube.twists[..6].iter().sum::<u8>() % 3) % 3;
+        cube
+    }
+}
+
+/// Optimal 2x2 solver, searching `POCKET_MOVES` by iterative deepening.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PocketSolver;
+
+impl PocketSolver {
+    pub fn solve(&self, cube: &PocketCube) -> Vec<CubeMove> {
+        let tables = tables();
+        let mut solution = Vec::with_capacity(MAX_SOLUTION_LENGTH);
+
+        for depth in 0..=MAX_SOLUTION_LENGTH {
+            if search(tables, cube.permutation_index(), cube.twist_index(), depth, None, &mut solution) {
+                return solution;
+            }
+        }
+        unreachable!("every 2x2 state should be solvable in {} moves", MAX_SOLUTION_LENGTH)
+    }
+}
+
+fn search(tables: &Tables, permutation: usize, twist: usize, depth: usize, last_face: Option<usize>, solution: &mut Vec<CubeMove>) -> bool {
+    if depth == 0 {
+        return permutation == 0 && twist == 0;
+    }
+    if tables.permutation_depths[permutation].max(tables.twist_depths[twist]) as usize > depth {
+        return false;
+    }
+
+    for (index, cube_move) in POCKET_MOVES.iter().enumerate() {
+        // Turning the same face twice in a row is never shorter
+        let face = index / 3;
+        if last_face == Some(face) {
+            continue;
+        }
+
+        solution.push(*cube_move);
+        if search(tables, tables.permutation_moves[permutation][index], tables.twist_moves[twist][index], depth - 1, Some(face), solution) {
+            return true;
+        }
+        solution.pop();
+    }
+    false
+}
+
+struct Tables {
+    /// For each move, where the corner ending at each location comes from and the twist it gains
+    moves: [[(u8, u8); 7]; 9],
+    permutation_moves: Vec<[usize; 9]>,
+    twist_moves: Vec<[usize; 9]>,
+    /// Moves needed to solve each permutation or twist alone, a lower bound for the whole cube
+    permutation_depths: Vec<u8>,
+    twist_depths: Vec<u8>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        // Moves read off a 3x3, so twists follow the same conventions
+        let moves = POCKET_MOVES.map(|cube_move| {
+            let mut cube = Cube::solved();
+            cube.apply_moves(&vec![cube_move]);
+            CORNERS.map(|location| {
+                let piece = cube.get_piece_at(&location);
+                let origin = CORNERS
+                    .iter()
+                    .position(|corner| *corner == piece.get_original_location())
+                    .expect("Pocket moves should keep DBL in place");
+                (origin as u8, piece.get_twist().number_of_twists())
+            })
+        });
+
+        let permutation_moves: Vec<[usize; 9]> = (0..PERMUTATION_COUNT)
+            .map(|index| {
+                let cube = PocketCube::from_permutation_index(index);
+                moves.map(|corner_moves| cube.moved(&corner_moves).permutation_index())
+            })
+            .collect();
+        let twist_moves: Vec<[usize; 9]> = (0..TWIST_COUNT)
+            .map(|index| {
+                let cube = PocketCube::from_twist_index(index);
+                moves.map(|corner_moves| cube.moved(&corner_moves).twist_index())
+            })
+            .collect();
+
+        Tables {
+            moves,
+            permutation_depths: depths(&permutation_moves),
+            twist_depths: depths(&twist_moves),
+            permutation_moves,
+            twist_moves,
+        }
+    })
+}
+
+/// Breadth first distances from the solved coordinate, 0.
+fn depths(coordinate_moves: &[[usize; 9]]) -> Vec<u8> {
+    let mut depths = vec![u8::MAX; coordinate_moves.len()];
+    depths[0] = 0;
+    let mut frontier = vec![0];
+
+    for depth in 1.. {
+        if frontier.is_empty() {
+            break;
+        }
+        frontier = frontier
+            .into_iter()
+            .flat_map(|coordinate: usize| coordinate_moves[coordinate])
+            .filter(|next| {
+                let unseen = depths[*next] == u8::MAX;
+                if unseen {
+                    depths[*next] = depth;
+                }
+                unseen
+            })
+            .collect();
+    }
+    depths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::permutations::permutation_parity;
+
+    #[test]
+    fn test_coordinates_round_trip() {
+        let mut rng = rand::rng();
+
+        for _ in 0..100 {
+            let cube = PocketCube::random_uniform(&mut rng);
+            assert_eq!(PocketCube::from_permutation_index(cube.permutation_index()).permutation, cube.permutation);
+            assert_eq!(PocketCube::from_twist_index(cube.twist_index()).twists, cube.twists);
+        }
+        assert_eq!(PocketCube::solved().permutation_index(), 0);
+        assert_eq!(PocketCube::solved().twist_index(), 0);
+    }
+
+    #[test]
+    fn test_moves_match_sexy_move() {
+        let sexy = CubeMove::parse_array("R U R' U'").expect("Moves are valid.");
+        let cube = (0..6).try_fold(PocketCube::solved(), |cube, _| cube.applied(&sexy)).expect("Moves are pocket moves.");
+        assert!(cube.is_solved());
+        assert!(!PocketCube::solved().applied(&sexy).expect("Moves are pocket moves.").is_solved());
+        assert_eq!(PocketCube::solved().applied(&[CubeMove::D]), None);
+    }
+
+    #[test]
+    fn test_corners_match_cube() {
+        let moves = CubeMove::parse_array("R U F2 R' U2 F' R2 U' F").expect("Moves are valid.");
+        let pocket = PocketCube::solved().applied(&moves).expect("Moves are pocket moves.");
+        let mut cube = Cube::solved();
+        cube.apply_moves(&moves);
+
+        for (location, (corner, twist)) in CORNERS.iter().zip(pocket.permutation.iter().zip(pocket.twists)) {
+            let piece = cube.get_piece_at(location);
+            assert_eq!(piece.get_original_location(), CORNERS[*corner as usize]);
+            assert_eq!(piece.get_twist().number_of_twists(), twist);
+        }
+    }
+
+    #[test]
+    fn test_solve_random_cubes() {
+        let mut rng = rand::rng();
+        let solver = PocketSolver;
+
+        for _ in 0..20 {
+            let cube = PocketCube::random_uniform(&mut rng);
+            let solution = solver.solve(&cube);
+            assert!(solution.len() <= MAX_SOLUTION_LENGTH);
+            assert!(cube.applied(&solution).expect("Solution should use pocket moves.").is_solved());
+        }
+    }
+
+    #[test]
+    fn test_solve_is_optimal() {
+        let cube = PocketCube::solved().applied(&CubeMove::parse_array("R U2 F'").expect("Moves are valid.")).expect("Moves are pocket moves.");
+        assert_eq!(PocketSolver.solve(&cube), CubeMove::parse_array("F U2 R'").expect("Moves are valid."));
+        assert!(PocketSolver.solve(&PocketCube::solved()).is_empty());
+    }
+
+    #[test]
+    fn test_random_cubes_have_both_parities() {
+        let mut rng = rand::rng();
+
+        let parities: Vec<bool> = (0..200)
+            .map(|_| {
+                let cube = PocketCube::random_uniform(&mut rng);
+                permutation_parity(&cube.permutation.map(usize::from))
+            })
+            .collect();
+        assert!(parities.contains(&true));
+        assert!(parities.contains(&false));
+    }
+}
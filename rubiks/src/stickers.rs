@@ -1,4 +1,5 @@
 use core::fmt;
+use crate::faces::Face;
 use crate::location::CubePieceLocation;
 use crate::twist::Twist;
 
@@ -203,6 +204,11 @@ impl CubeStickerLocation {
         twist: Twist::FLIPPED,
     };
 
+    /// Face on which the sticker lies.
+    pub fn face(&self) -> Face {
+        self.piece_location.get_faces()[self.twist.number_of_twists() as usize]
+    }
+
     pub fn to_sticker_name(&self) -> String {
         let number_of_twists = self.twist.number_of_twists();
         let mut faces = self.piece_location.get_faces();
@@ -212,6 +218,69 @@ impl CubeStickerLocation {
     }
 }
 
+/// Stickers of each face, in the order they appear in a facelet string (centers excluded).
+pub(crate) static FACELET_POSITIONS: [CubeStickerLocation; 48]  = [
+    // U
+    CubeStickerLocation::ULB,
+    CubeStickerLocation::UB,
+    CubeStickerLocation::UBR,
+    CubeStickerLocation::UL,
+    CubeStickerLocation::UR,
+    CubeStickerLocation::UFL,
+    CubeStickerLocation::UF,
+    CubeStickerLocation::URF,
+
+    // R
+    CubeStickerLocation::RFU,
+    CubeStickerLocation::RU,
+    CubeStickerLocation::RUB,
+    CubeStickerLocation::RF,
+    CubeStickerLocation::RB,
+    CubeStickerLocation::RDF,
+    CubeStickerLocation::RD,
+    CubeStickerLocation::RBD,
+
+    // F
+    CubeStickerLocation::FLU,
+    CubeStickerLocation::FU,
+    CubeStickerLocation::FUR,
+    CubeStickerLocation::FL,
+    CubeStickerLocation::FR,
+    CubeStickerLocation::FDL,
+    CubeStickerLocation::FD,
+    CubeStickerLocation::FRD,
+
+    // D
+    CubeStickerLocation::DLF,
+    CubeStickerLocation::DF,
+    CubeStickerLocation::DFR,
+    CubeStickerLocation::DL,
+    CubeStickerLocation::DR,
+    CubeStickerLocation::DBL,
+    CubeStickerLocation::DB,
+    CubeStickerLocation::DRB,
+
+    // L
+    CubeStickerLocation::LBU,
+    CubeStickerLocation::LU,
+    CubeStickerLocation::LUF,
+    CubeStickerLocation::LB,
+    CubeStickerLocation::LF,
+    CubeStickerLocation::LDB,
+    CubeStickerLocation::LD,
+    CubeStickerLocation::LFD,
+
+    // B
+    CubeStickerLocation::BRU,
+    CubeStickerLocation::BU,
+    CubeStickerLocation::BUL,
+    CubeStickerLocation::BR,
+    CubeStickerLocation::BL,
+    CubeStickerLocation::BDR,
+    CubeStickerLocation::BD,
+    CubeStickerLocation::BLD,
+];
+
 impl fmt::Debug for CubeStickerLocation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.to_sticker_name())
@@ -3,17 +3,23 @@ use rand::seq::SliceRandom;
 
 pub type Permutation<const N: usize> = [usize; N];
 
-pub fn random_uniform_permutation<const N: usize, R: Rng>(rng: &mut R) -> Permutation<N> {
+/// Random permutation only moving the elements at `indices` between themselves.
+pub fn random_uniform_partial_permutation<const N: usize, R: Rng>(indices: &[usize], rng: &mut R) -> Permutation<N> {
+    let mut shuffled = indices.to_vec();
+    shuffled.shuffle(rng);
+
     let mut permutation = identity_permutation::<N>();
-    permutation.shuffle(rng);
+    for (index, element) in indices.iter().zip(shuffled) {
+        permutation[*index] = element;
+    }
     permutation
 }
 
 pub fn identity_permutation<const N: usize>() -> Permutation<N> {
     let mut permutation = [0; N];
 
-    for i in 0..N {
-        permutation[i] = i;
+    for (i, element) in permutation.iter_mut().enumerate() {
+        *element = i;
     }
 
     permutation